@@ -13,6 +13,8 @@ MAX_MOVE_LEN = 60
 -- move closer to targets even if they cannot directly attack, up to the specified distance
 -- multiplied by the parent size.  This normally will make it easy
 -- for the player to dispatch them with area of effect attacks.
+-- UsePlanner value of 0 or 1.  When 1, the parent will use the built in action planner
+-- (see ScriptEntity::plan_actions) to choose its actions rather than the scripted search below.
 
 function ai_action(parent, params)
     -- set default value of 0 for all params
@@ -54,6 +56,10 @@ function ai_action(parent, params)
     precompute_weights(parent, hostiles, weights)
     precompute_weights(parent, friendlies, weights)
 
+    if params["UsePlanner"] ~= 0 then
+        return execute_plan(parent, hostiles, friendlies, weights)
+    end
+
     local failed_use_count = 0
 
     if not parent:has_flag("ai_force_attack") then
@@ -153,6 +159,48 @@ function end_turn(parent)
     return parent:state_end()
 end
 
+-- Executes the highest scored plan that can be carried out.  See ScriptAIPlan
+function execute_plan(parent, hostiles, friendlies, weights)
+    local plans = parent:plan_actions()
+    game:log("  Got " .. tostring(#plans) .. " plans")
+
+    for i = 1, #plans do
+        local plan = plans[i]
+        local target = plan:target()
+        game:log("    Checking plan " .. plan:kind() .. " against " .. target:id() .. " with score "
+            .. tostring(plan:score()))
+
+        if plan:needs_move() then
+            if check_move_towards(parent, target, plan:move_dist() - MOVE_THRESHOLD).done then
+                return parent:state_wait(WAIT_TIME)
+            end
+        elseif plan:kind() == "Attack" then
+            parent:anim_weapon_attack(target, nil, true)
+            return parent:state_wait(WAIT_TIME)
+        else
+            local src = plan:ability()
+            if src ~= nil then
+                parent:use_ability(src)
+            else
+                src = plan:item()
+                parent:use_item(src)
+            end
+
+            if not game:has_targeter() then
+                return parent:state_wait(WAIT_TIME)
+            end
+
+            local result = handle_targeter(parent, target, src, src:ai_data(), hostiles,
+                friendlies, weights)
+            if result.done then
+                return parent:state_wait(WAIT_TIME)
+            end
+        end
+    end
+
+    return end_turn(parent)
+end
+
 function attempt_run_away(parent, hostiles)
     local parent_x = parent:x()
     local parent_y = parent:y()
@@ -695,8 +743,8 @@ function compute_weight(parent, target)
         modifiers = modifiers - compute_defensive_strength(target_stats)
     end
 
-    -- hostiles that have hurt us are higher priority
-    modifiers = modifiers + parent:get_num_flag("__damage_taken_from" .. target:id())
+    -- hostiles that have generated threat against us are higher priority
+    modifiers = modifiers + parent:threat(target) / parent:stats().max_hp
 
     -- hostiles that are difficult to damage with our regular attack are lower priority
     modifiers = modifiers + parent:get_num_flag("__hard_target_for" .. target:id())
@@ -733,15 +781,7 @@ end
 
 -- OnDamaged script hook
 function on_damaged(parent, targets, hit)
-    local target = targets:first()
-    local max_hp = parent:stats().max_hp
-    local damage = hit:total_damage()
-    local frac = damage / max_hp
-    parent:add_num_flag("__damage_taken_from_" .. target:id(), frac)
-
-    game:debug("Added damage_taken_from " .. tostring(frac) .. " for " .. target:id() .. " on "
-        .. parent:id())
-
+    -- damage taken is tracked in the parent's threat table
     -- game:log(parent:name() .. " damaged by " .. target:name() .. ": "
     --     .. hit:kind() .. " for " .. hit:total_damage() .. " damage.")
 end
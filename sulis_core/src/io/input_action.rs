@@ -30,6 +30,7 @@ pub enum InputAction {
     ToggleMap,
     ToggleJournal,
     ToggleFormation,
+    ToggleCrafting,
     Back,
     EndTurn,
     Rest,
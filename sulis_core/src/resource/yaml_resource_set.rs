@@ -55,10 +55,12 @@ pub enum YamlResourceKind {
     Encounter,
     Item,
     ItemAdjective,
+    ItemSet,
     LootList,
     Prop,
     Quest,
     Race,
+    Recipe,
     Size,
     Tile,
     Generator,
@@ -105,10 +107,12 @@ impl YamlResourceKind {
             "encounters" => Encounter,
             "items" => Item,
             "item_adjectives" => ItemAdjective,
+            "item_sets" => ItemSet,
             "loot_lists" => LootList,
             "props" => Prop,
             "quests" => Quest,
             "races" => Race,
+            "recipes" => Recipe,
             "sizes" => Size,
             "tiles" => Tile,
             "generators" => Generator,
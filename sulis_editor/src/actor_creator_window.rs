@@ -102,6 +102,7 @@ impl ActorCreatorWindow {
             attributes: AttributeList::new(Module::rules().base_attribute as u8),
             conversation: None,
             faction: Some(self.selected_faction),
+            affiliation: None,
             images,
             hue: Some(self.selected_hue),
             hair_color: None,
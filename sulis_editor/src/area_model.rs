@@ -151,6 +151,7 @@ impl AreaModel {
             on_activate: Vec::new(),
             initially_enabled: true,
             fire_more_than_once: false,
+            condition: None,
         });
     }
 
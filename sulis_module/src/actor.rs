@@ -132,6 +132,9 @@ pub struct Actor {
     pub id: String,
     pub name: String,
     faction: Faction,
+
+    /// The named campaign faction this actor belongs to, if any
+    pub affiliation: Option<String>,
     pub conversation: Option<Rc<Conversation>>,
     pub portrait: Option<Rc<dyn Image>>,
     pub race: Rc<Race>,
@@ -216,6 +219,7 @@ impl Actor {
             id: other.id.to_string(),
             name: other.name.to_string(),
             faction: other.faction,
+            affiliation: other.affiliation.clone(),
             conversation: other.conversation.clone(),
             portrait: other.portrait.clone(),
             race: Rc::clone(&other.race),
@@ -366,6 +370,7 @@ impl Actor {
             name: builder.name,
             conversation,
             faction: builder.faction.unwrap_or(Faction::Hostile),
+            affiliation: builder.affiliation,
             portrait,
             race,
             sex,
@@ -475,6 +480,9 @@ pub struct ActorBuilder {
     pub conversation: Option<String>,
     pub faction: Option<Faction>,
 
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub affiliation: Option<String>,
+
     #[serde(default)]
     pub images: HashMap<ImageLayer, String>,
 
@@ -26,6 +26,17 @@ pub enum FuncKind {
     OnRoundElapsed,
 }
 
+/// An AI Template, specifying the script and params used by AI controlled
+/// actors.  Most params are only read by the Lua script, but the following
+/// are also used by the engine to compute morale:
+/// `MoraleFleeThreshold` - when set, the actor breaks when its morale falls
+/// below this value.  If unset, the actor will fight to the death.
+/// `MoraleSurrender` - when non-zero, the actor surrenders instead of fleeing.
+/// `MoraleBase` - the starting morale of the actor, defaults to 100.
+/// `MoraleAllyLoss` - morale lost for each member of the actor's AI group
+/// that has died, fled, or surrendered, defaults to 20.
+/// `MoraleHpLoss` - morale lost when the actor is at zero hit points, scaled
+/// linearly by the fraction of hit points lost, defaults to 50.
 #[derive(Deserialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct AITemplate {
@@ -38,3 +49,14 @@ pub struct AITemplate {
     #[serde(default)]
     pub params: HashMap<String, i32>,
 }
+
+impl AITemplate {
+    /// Returns the value of the specified param, or `default` if it is not set
+    pub fn param(&self, key: &str, default: i32) -> i32 {
+        *self.params.get(key).unwrap_or(&default)
+    }
+
+    pub fn has_param(&self, key: &str) -> bool {
+        self.params.contains_key(key)
+    }
+}
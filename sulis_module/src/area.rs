@@ -43,7 +43,7 @@ use sulis_core::util::{unable_to_create_error, Point, Size};
 use sulis_core::io::SoundSource;
 
 use crate::generator::{EncounterParams, EncounterParamsBuilder, PropParams, PropParamsBuilder};
-use crate::{Encounter, ItemListEntrySaveState, Module, ObjectSize, OnTrigger, Prop};
+use crate::{Condition, Encounter, ItemListEntrySaveState, Module, ObjectSize, OnTrigger, Prop};
 
 pub const MAX_AREA_SIZE: i32 = 128;
 
@@ -62,6 +62,9 @@ pub struct Trigger {
     pub on_activate: Vec<OnTrigger>,
     pub initially_enabled: bool,
     pub fire_more_than_once: bool,
+
+    /// If set, the trigger only fires while this condition is met
+    pub condition: Option<Condition>,
 }
 
 #[derive(Debug, Clone)]
@@ -199,11 +202,19 @@ impl Area {
     ) -> Result<(Vec<Trigger>, Vec<EncounterData>), Error> {
         let mut triggers: Vec<Trigger> = Vec::new();
         for tbuilder in &builder.triggers {
+            if let Some(condition) = &tbuilder.condition {
+                if !Module::validate_condition(condition) {
+                    warn!("Invalid condition for trigger {:?}", tbuilder.kind);
+                    return unable_to_create_error("area", &builder.id);
+                }
+            }
+
             triggers.push(Trigger {
                 kind: tbuilder.kind.clone(),
                 on_activate: tbuilder.on_activate.clone(),
                 initially_enabled: tbuilder.initially_enabled,
                 fire_more_than_once: tbuilder.fire_more_than_once,
+                condition: tbuilder.condition.clone(),
             });
         }
 
@@ -666,6 +677,9 @@ pub struct TriggerBuilder {
 
     #[serde(default)]
     pub fire_more_than_once: bool,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<Condition>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -0,0 +1,246 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use std::collections::HashMap;
+
+use crate::{Condition, Module, OnTrigger};
+
+/// The events that may cause an actor to bark a line
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum BarkEvent {
+    /// The party has entered a new area.  Only party members bark
+    EnterArea,
+
+    /// Combat has started.  Party members and visible hostiles may bark
+    CombatStart,
+
+    /// The actor's hit points have fallen below the low hit point fraction
+    LowHp,
+
+    /// The actor has killed another
+    KillingBlow,
+
+    /// The party has stood still outside of combat for the idle time
+    Idle,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BarkLine {
+    pub text: String,
+
+    #[serde(default = "one")]
+    pub weight: u32,
+
+    /// Checked with the player as the player and the speaker as the target
+    #[serde(default)]
+    pub condition: Option<Condition>,
+}
+
+/// The pools of lines an actor may bark, keyed by event
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BarkSet {
+    /// The minimum time in milliseconds between barks from a single actor
+    #[serde(default = "default_cooldown")]
+    pub cooldown: u32,
+
+    /// The percentage chance that an event produces a bark
+    #[serde(default = "default_chance")]
+    pub chance: u32,
+
+    pub lines: HashMap<BarkEvent, Vec<BarkLine>>,
+}
+
+impl BarkSet {
+    fn validate(&self) -> bool {
+        for line in self.lines.values().flatten() {
+            if let Some(condition) = &line.condition {
+                if !Module::validate_condition(condition) {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BanterLine {
+    /// The actor ID of the party member speaking this line
+    pub speaker: String,
+    pub text: String,
+
+    /// The time in milliseconds before the next line is spoken
+    #[serde(default = "default_line_delay")]
+    pub delay: u32,
+}
+
+/// A sequence of lines spoken between party members while exploring.
+/// Every speaker must be in the party for the banter to start
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Banter {
+    pub lines: Vec<BanterLine>,
+
+    #[serde(default = "one")]
+    pub weight: u32,
+
+    /// Checked with the player as the player and the first speaker as the target
+    #[serde(default)]
+    pub condition: Option<Condition>,
+
+    /// Banter that is not repeatable is only ever played once
+    #[serde(default)]
+    pub repeatable: bool,
+
+    /// Fired once the final line has been spoken
+    #[serde(default)]
+    pub on_complete: Vec<OnTrigger>,
+}
+
+impl Banter {
+    pub fn speakers(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().map(|line| &line.speaker[..])
+    }
+
+    fn validate(&self) -> bool {
+        if self.lines.is_empty() {
+            warn!("Banter must contain at least one line");
+            return false;
+        }
+
+        for speaker in self.speakers() {
+            if Module::actor(speaker).is_none() {
+                warn!("Invalid banter speaker '{}'", speaker);
+                return false;
+            }
+        }
+
+        if let Some(condition) = &self.condition {
+            if !Module::validate_condition(condition) {
+                return false;
+            }
+        }
+
+        for trigger in self.on_complete.iter() {
+            if let OnTrigger::Condition(condition) = trigger {
+                if !Module::validate_condition(condition) {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Barks {
+    /// The time in milliseconds the party must stand still outside of
+    /// combat before an idle bark
+    #[serde(default = "default_idle_time")]
+    pub idle_time: u32,
+
+    /// The fraction of maximum hit points below which an actor is low on hit points
+    #[serde(default = "default_low_hp")]
+    pub low_hp: f32,
+
+    /// The time in milliseconds spent exploring between attempts to start banter
+    #[serde(default = "default_banter_interval")]
+    pub banter_interval: u32,
+
+    /// The percentage chance that banter starts at each attempt
+    #[serde(default = "default_chance")]
+    pub banter_chance: u32,
+
+    /// Bark lines keyed by actor ID
+    #[serde(default)]
+    pub actors: HashMap<String, BarkSet>,
+
+    #[serde(default)]
+    pub banter: HashMap<String, Banter>,
+}
+
+impl Default for Barks {
+    fn default() -> Barks {
+        Barks {
+            idle_time: default_idle_time(),
+            low_hp: default_low_hp(),
+            banter_interval: default_banter_interval(),
+            banter_chance: default_chance(),
+            actors: HashMap::new(),
+            banter: HashMap::new(),
+        }
+    }
+}
+
+impl Barks {
+    pub fn validate(&self) -> bool {
+        for (id, bark_set) in self.actors.iter() {
+            if Module::actor(id).is_none() {
+                warn!("Invalid bark actor '{}'", id);
+                return false;
+            }
+
+            if !bark_set.validate() {
+                warn!("Invalid bark lines for actor '{}'", id);
+                return false;
+            }
+        }
+
+        for (id, banter) in self.banter.iter() {
+            if !banter.validate() {
+                warn!("Invalid banter '{}'", id);
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+fn one() -> u32 {
+    1
+}
+
+fn default_cooldown() -> u32 {
+    30000
+}
+
+fn default_chance() -> u32 {
+    50
+}
+
+fn default_line_delay() -> u32 {
+    3000
+}
+
+fn default_idle_time() -> u32 {
+    60000
+}
+
+fn default_low_hp() -> f32 {
+    0.25
+}
+
+fn default_banter_interval() -> u32 {
+    120000
+}
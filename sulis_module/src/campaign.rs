@@ -25,12 +25,77 @@ use sulis_core::image::Image;
 use sulis_core::resource::ResourceSet;
 use sulis_core::util::{unable_to_create_error, Point};
 
-use crate::{on_trigger, Conversation, Module};
+use crate::{actor::Faction, bark::Barks, on_trigger, Conversation, Module, OnTrigger};
 
 pub struct WorldMap {
     pub size: (f32, f32),
     pub offset: (f32, f32),
     pub locations: Vec<WorldMapLocation>,
+    pub provisions: Option<TravelProvisions>,
+}
+
+/// Items consumed from the party stash when travelling on the world map
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TravelProvisions {
+    pub item: String,
+
+    /// One item is consumed for each started period of this many hours
+    pub hours_per_item: u32,
+}
+
+impl TravelProvisions {
+    /// The number of items needed to travel for the specified hours
+    pub fn required(&self, hours: u32) -> u32 {
+        hours.div_ceil(self.hours_per_item.max(1))
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TravelEncounter {
+    /// The area the party is moved to.  This must be a generated area,
+    /// which is generated anew each time the encounter occurs
+    pub area: String,
+    pub location: Point,
+
+    #[serde(default = "one")]
+    pub weight: u32,
+}
+
+/// The random encounters that may interrupt travel along a route
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TravelEncounterTable {
+    /// The percentage chance that an encounter occurs when travelling
+    pub chance: u32,
+    pub encounters: Vec<TravelEncounter>,
+}
+
+impl TravelEncounterTable {
+    fn validate(&self) -> bool {
+        if self.encounters.is_empty() || self.encounters.iter().all(|e| e.weight == 0) {
+            warn!("Travel encounter tables must have at least one weighted encounter");
+            return false;
+        }
+
+        for encounter in self.encounters.iter() {
+            let area = match Module::area(&encounter.area) {
+                None => {
+                    warn!("Invalid travel encounter area '{}'", encounter.area);
+                    return false;
+                }
+                Some(area) => area,
+            };
+
+            if area.generator.is_none() {
+                warn!("Travel encounter area '{}' must have a generator", area.id);
+                return false;
+            }
+        }
+
+        true
+    }
 }
 
 pub struct WorldMapLocation {
@@ -45,6 +110,121 @@ pub struct WorldMapLocation {
     pub linked_area_pos: Point,
 
     pub travel_times: HashMap<String, u32>,
+    pub travel_encounters: HashMap<String, Rc<TravelEncounterTable>>,
+}
+
+/// A faction the party may gain or lose reputation with.  Actors with a
+/// matching `affiliation` become hostile or neutral towards the party
+/// based on the current reputation
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NamedFaction {
+    pub name: String,
+
+    #[serde(default)]
+    pub description: String,
+
+    #[serde(default)]
+    pub initial_reputation: i32,
+
+    /// Members are hostile to the party while reputation is below this value
+    pub hostile_below: i32,
+
+    /// If set, members that would otherwise be hostile are neutral while
+    /// reputation is at or above this value
+    #[serde(default)]
+    pub neutral_at: Option<i32>,
+
+    /// Reputation lost each time the party kills a member
+    #[serde(default)]
+    pub kill_penalty: i32,
+}
+
+impl NamedFaction {
+    /// Returns the faction a member with the `base` faction acts as at the
+    /// specified reputation.  Friendly members are never changed
+    pub fn effective_faction(&self, base: Faction, reputation: i32) -> Faction {
+        let neutral = self.neutral_at.is_some_and(|value| reputation >= value);
+        if base == Faction::Friendly {
+            base
+        } else if self.is_hostile(reputation) {
+            Faction::Hostile
+        } else if neutral {
+            Faction::Neutral
+        } else {
+            base
+        }
+    }
+
+    pub fn is_hostile(&self, reputation: i32) -> bool {
+        reputation < self.hostile_below
+    }
+}
+
+/// Triggers fired the first time a companion's approval of the party
+/// reaches a value.  Exactly one of `at_least` or `below` should be set,
+/// and the threshold must not already be reached by the initial approval
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ApprovalThreshold {
+    #[serde(default)]
+    pub at_least: Option<i32>,
+
+    #[serde(default)]
+    pub below: Option<i32>,
+
+    pub on_reached: Vec<OnTrigger>,
+}
+
+impl ApprovalThreshold {
+    pub fn is_reached(&self, approval: i32) -> bool {
+        match (self.at_least, self.below) {
+            (Some(value), _) => approval >= value,
+            (None, Some(value)) => approval < value,
+            (None, None) => false,
+        }
+    }
+}
+
+/// Approval tracking for an actor that may join the party
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Companion {
+    #[serde(default)]
+    pub initial_approval: i32,
+
+    #[serde(default)]
+    pub thresholds: Vec<ApprovalThreshold>,
+}
+
+impl Companion {
+    fn validate(&self) -> bool {
+        for threshold in self.thresholds.iter() {
+            if threshold.at_least.is_some() == threshold.below.is_some() {
+                warn!("Approval thresholds must specify one of at_least or below");
+                return false;
+            }
+
+            // thresholds are only checked when approval changes
+            if threshold.is_reached(self.initial_approval) {
+                warn!(
+                    "Approval threshold is already reached by initial approval {}",
+                    self.initial_approval
+                );
+                return false;
+            }
+
+            for trigger in threshold.on_reached.iter() {
+                if let OnTrigger::Condition(condition) = trigger {
+                    if !Module::validate_condition(condition) {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        true
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -95,6 +275,9 @@ pub struct Campaign {
     pub on_round_elapsed_script: Option<on_trigger::ScriptData>,
     pub world_map: WorldMap,
     pub group: Option<CampaignGroup>,
+    pub factions: HashMap<String, NamedFaction>,
+    pub companions: HashMap<String, Companion>,
+    pub barks: Barks,
 }
 
 impl Campaign {
@@ -120,6 +303,15 @@ impl Campaign {
                 Some(img) => img,
             };
 
+            let mut travel_encounters = HashMap::new();
+            for (from, table) in location.travel_encounters {
+                if !table.validate() {
+                    warn!("Invalid travel encounters from '{}' to '{}'", from, id);
+                    return unable_to_create_error("module", &builder.name);
+                }
+                travel_encounters.insert(from, Rc::new(table));
+            }
+
             locations.push(WorldMapLocation {
                 id,
                 name: location.name,
@@ -130,9 +322,45 @@ impl Campaign {
                 linked_area: location.linked_area,
                 linked_area_pos: location.linked_area_pos,
                 travel_times: location.travel_times,
+                travel_encounters,
             });
         }
 
+        for actor in Module::all_actors() {
+            if let Some(affiliation) = &actor.affiliation {
+                if !builder.factions.contains_key(affiliation) {
+                    warn!(
+                        "Actor '{}' has invalid faction affiliation '{}'",
+                        actor.id, affiliation
+                    );
+                    return unable_to_create_error("module", &builder.name);
+                }
+            }
+        }
+
+        for (id, companion) in builder.companions.iter() {
+            if Module::actor(id).is_none() {
+                warn!("Invalid companion actor '{}'", id);
+                return unable_to_create_error("module", &builder.name);
+            }
+
+            if !companion.validate() {
+                warn!("Invalid approval thresholds for companion '{}'", id);
+                return unable_to_create_error("module", &builder.name);
+            }
+        }
+
+        if !builder.barks.validate() {
+            return unable_to_create_error("module", &builder.name);
+        }
+
+        if let Some(provisions) = &builder.world_map.provisions {
+            if Module::item(&provisions.item).is_none() {
+                warn!("Invalid travel provisions item '{}'", provisions.item);
+                return unable_to_create_error("module", &builder.name);
+            }
+        }
+
         Ok(Campaign {
             group: builder.group,
             starting_time: builder.starting_time,
@@ -146,10 +374,14 @@ impl Campaign {
             on_party_death_script: builder.on_party_death_script,
             on_tick_script: builder.on_tick_script,
             on_round_elapsed_script: builder.on_round_elapsed_script,
+            factions: builder.factions,
+            companions: builder.companions,
+            barks: builder.barks,
             world_map: WorldMap {
                 size: builder.world_map.size,
                 offset: builder.world_map.offset,
                 locations,
+                provisions: builder.world_map.provisions,
             },
         })
     }
@@ -171,6 +403,17 @@ pub struct CampaignBuilder {
     pub on_tick_script: Option<on_trigger::ScriptData>,
     pub on_round_elapsed_script: Option<on_trigger::ScriptData>,
     pub world_map: WorldMapBuilder,
+
+    #[serde(default)]
+    pub factions: HashMap<String, NamedFaction>,
+
+    /// Approval tracking for companions, keyed by actor ID
+    #[serde(default)]
+    pub companions: HashMap<String, Companion>,
+
+    /// Ambient lines spoken by actors and banter between party members
+    #[serde(default)]
+    pub barks: Barks,
 }
 
 #[derive(Deserialize, Debug)]
@@ -191,16 +434,28 @@ pub struct WorldMapLocationBuilder {
 
     #[serde(default)]
     pub travel_times: HashMap<String, u32>,
+
+    /// Random encounters for travel to this location, keyed by the
+    /// location travelled from
+    #[serde(default)]
+    pub travel_encounters: HashMap<String, TravelEncounterTable>,
 }
 
 fn bool_true() -> bool {
     true
 }
 
+fn one() -> u32 {
+    1
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct WorldMapBuilder {
     pub size: (f32, f32),
     pub offset: (f32, f32),
     pub locations: HashMap<String, WorldMapLocationBuilder>,
+
+    #[serde(default)]
+    pub provisions: Option<TravelProvisions>,
 }
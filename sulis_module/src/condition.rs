@@ -0,0 +1,259 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use crate::on_trigger::QuestEntryState;
+use crate::{Attribute, Module, OnTrigger};
+
+/// Which of the two entities involved in a conversation or trigger a
+/// condition is checked against
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum ConditionTarget {
+    #[default]
+    Player,
+    Target,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum CompareOp {
+    #[serde(alias = "==")]
+    Eq,
+    #[serde(alias = "!=")]
+    Ne,
+    #[serde(alias = "<")]
+    Lt,
+    #[serde(alias = "<=")]
+    Le,
+    #[serde(alias = ">")]
+    Gt,
+    #[serde(alias = ">=")]
+    #[default]
+    Ge,
+}
+
+impl CompareOp {
+    /// Returns the result of `a op b`
+    pub fn compare<T: PartialOrd>(self, a: T, b: T) -> bool {
+        use self::CompareOp::*;
+        match self {
+            Eq => a == b,
+            Ne => a != b,
+            Lt => a < b,
+            Le => a <= b,
+            Gt => a > b,
+            Ge => a >= b,
+        }
+    }
+}
+
+/// A condition evaluated against the current game state.  Conditions may
+/// be grouped with `all`, `any`, and `not`.  Comparisons default to
+/// greater than or equal, and checks against an entity default to the
+/// player.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum Condition {
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+    Not(Box<Condition>),
+
+    Flag {
+        #[serde(default)]
+        target: ConditionTarget,
+        flag: String,
+    },
+
+    NumFlag {
+        #[serde(default)]
+        target: ConditionTarget,
+        flag: String,
+        #[serde(default)]
+        op: CompareOp,
+        value: f32,
+    },
+
+    /// Compares the entity's current attribute, including bonuses
+    Attribute {
+        #[serde(default)]
+        target: ConditionTarget,
+        attribute: Attribute,
+        #[serde(default)]
+        op: CompareOp,
+        value: i32,
+    },
+
+    /// Compares the entity's level in the specified class, or total level
+    /// if no class is specified
+    Level {
+        #[serde(default)]
+        target: ConditionTarget,
+        #[serde(default)]
+        class: Option<String>,
+        #[serde(default)]
+        op: CompareOp,
+        value: u32,
+    },
+
+    Race {
+        #[serde(default)]
+        target: ConditionTarget,
+        race: String,
+    },
+
+    Ability {
+        #[serde(default)]
+        target: ConditionTarget,
+        ability: String,
+    },
+
+    /// The actor with the specified ID is in the party
+    PartyMember(String),
+
+    PartySize {
+        #[serde(default)]
+        op: CompareOp,
+        value: u32,
+    },
+
+    /// Compares the quantity of the specified item in the party stash
+    ItemCount {
+        item: String,
+        #[serde(default)]
+        op: CompareOp,
+        #[serde(default = "one")]
+        value: u32,
+    },
+
+    Coins {
+        #[serde(default)]
+        op: CompareOp,
+        value: i32,
+    },
+
+    QuestState {
+        quest: String,
+        #[serde(default)]
+        entry: Option<String>,
+        state: QuestEntryState,
+    },
+
+    /// Compares the party's reputation with the named campaign faction
+    Reputation {
+        faction: String,
+        #[serde(default)]
+        op: CompareOp,
+        value: i32,
+    },
+
+    /// Compares the specified companion's approval of the party
+    Approval {
+        companion: String,
+        #[serde(default)]
+        op: CompareOp,
+        value: i32,
+    },
+}
+
+impl Condition {
+    /// Checks that all resources referenced by this condition exist,
+    /// logging a warning for each that does not
+    pub fn validate(&self, module: &Module) -> bool {
+        use self::Condition::*;
+        match self {
+            All(conditions) | Any(conditions) => {
+                if conditions.is_empty() {
+                    warn!("Condition groups must contain at least one condition");
+                    return false;
+                }
+                conditions.iter().all(|c| c.validate(module))
+            }
+            Not(condition) => condition.validate(module),
+            Level {
+                class: Some(class), ..
+            } => {
+                if !module.classes.contains_key(class) {
+                    warn!("Invalid class '{}' in condition", class);
+                    return false;
+                }
+                true
+            }
+            Race { race, .. } => {
+                if !module.races.contains_key(race) {
+                    warn!("Invalid race '{}' in condition", race);
+                    return false;
+                }
+                true
+            }
+            Ability { ability, .. } => {
+                if !module.abilities.contains_key(ability) {
+                    warn!("Invalid ability '{}' in condition", ability);
+                    return false;
+                }
+                true
+            }
+            ItemCount { item, .. } => {
+                if !module.items.contains_key(item) {
+                    warn!("Invalid item '{}' in condition", item);
+                    return false;
+                }
+                true
+            }
+            QuestState { quest, entry, .. } => {
+                let quest_data = match module.quests.get(quest) {
+                    None => {
+                        warn!("Invalid quest '{}' in condition", quest);
+                        return false;
+                    }
+                    Some(quest_data) => quest_data,
+                };
+
+                if let Some(entry) = entry {
+                    if !quest_data.entries.contains_key(entry) {
+                        warn!(
+                            "Invalid entry '{}' for quest '{}' in condition",
+                            entry, quest
+                        );
+                        return false;
+                    }
+                }
+                true
+            }
+            Flag { .. }
+            | NumFlag { .. }
+            | Attribute { .. }
+            | Level { .. }
+            | PartyMember(_)
+            | PartySize { .. }
+            | Coins { .. }
+            | Reputation { .. }
+            | Approval { .. } => true,
+        }
+    }
+}
+
+/// Validates all conditions found in the specified triggers.  See
+/// `Condition::validate`
+pub fn validate_triggers(triggers: &[OnTrigger], module: &Module) -> bool {
+    triggers.iter().all(|trigger| match trigger {
+        OnTrigger::Condition(condition) => condition.validate(module),
+        _ => true,
+    })
+}
+
+fn one() -> u32 {
+    1
+}
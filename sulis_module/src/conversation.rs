@@ -20,7 +20,7 @@ use std::slice::Iter;
 
 use sulis_core::util::unable_to_create_error;
 
-use crate::{Module, OnTrigger};
+use crate::{condition, Attribute, Module, OnTrigger};
 
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
@@ -33,6 +33,52 @@ pub struct Response {
 
     #[serde(default)]
     pub to_view: Vec<OnTrigger>,
+
+    /// An attribute check made when this response is selected.  The
+    /// requirement and chance of success are shown with the response
+    #[serde(default)]
+    pub check: Option<ResponseCheck>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum CheckKind {
+    /// Succeeds if the attribute is at least the difficulty
+    #[default]
+    Fixed,
+
+    /// Succeeds if a d20 roll plus the attribute bonus over the base
+    /// attribute is at least the difficulty
+    Roll,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ResponseCheck {
+    pub attribute: Attribute,
+    pub difficulty: i32,
+
+    #[serde(default)]
+    pub kind: CheckKind,
+
+    /// If true, the party member with the best chance of success makes the
+    /// check rather than the player
+    #[serde(default)]
+    pub party: bool,
+
+    /// The node to move to on success.  Defaults to the response's `to`
+    #[serde(default)]
+    pub success: Option<String>,
+
+    /// The node to move to on failure.  Defaults to the response's `to`
+    #[serde(default)]
+    pub failure: Option<String>,
+
+    #[serde(default)]
+    pub on_success: Vec<OnTrigger>,
+
+    #[serde(default)]
+    pub on_failure: Vec<OnTrigger>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -62,7 +108,7 @@ impl PartialEq for Conversation {
 }
 
 impl Conversation {
-    pub fn new(builder: ConversationBuilder, _module: &Module) -> Result<Conversation, Error> {
+    pub fn new(builder: ConversationBuilder, module: &Module) -> Result<Conversation, Error> {
         if builder.initial_nodes.is_empty() {
             warn!("Must specify at least one initial node for conversation");
             return unable_to_create_error("conversation", &builder.id);
@@ -75,17 +121,41 @@ impl Conversation {
                 return unable_to_create_error("conversation", &builder.id);
             }
 
+            if !condition::validate_triggers(&node.to_view, module) {
+                warn!("Invalid condition for initial node '{}'", node.id);
+                return unable_to_create_error("conversation", &builder.id);
+            }
+
             initial_nodes.push((node.id, node.to_view));
         }
 
-        for (_, ref node) in builder.nodes.iter() {
+        for (id, ref node) in builder.nodes.iter() {
+            if !condition::validate_triggers(&node.on_view, module) {
+                warn!("Invalid condition in on_view for node '{}'", id);
+                return unable_to_create_error("conversation", &builder.id);
+            }
+
             for response in node.responses.iter() {
+                if !condition::validate_triggers(&response.to_view, module) {
+                    warn!("Invalid condition in response to_view for node '{}'", id);
+                    return unable_to_create_error("conversation", &builder.id);
+                }
+
                 if let Some(ref to) = response.to {
                     if !builder.nodes.contains_key(to) {
                         warn!("Invalid to '{}' for node response.  Must be a node ID", to);
                         return unable_to_create_error("conversation", &builder.id);
                     }
                 }
+
+                if let Some(ref check) = response.check {
+                    for to in check.success.iter().chain(check.failure.iter()) {
+                        if !builder.nodes.contains_key(to) {
+                            warn!("Invalid check node '{}' for node response", to);
+                            return unable_to_create_error("conversation", &builder.id);
+                        }
+                    }
+                }
             }
         }
 
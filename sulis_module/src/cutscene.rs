@@ -16,30 +16,122 @@
 
 use std::io::Error;
 
-use crate::{Module, OnTrigger};
+use sulis_core::resource::ResourceSet;
+use sulis_core::util::{unable_to_create_error, Point};
+
+use crate::{condition, Condition, Module, OnTrigger};
+
+/// How a cutscene is presented.  `Window` cutscenes are shown in a modal
+/// window over the area.  `Area` cutscenes play out in the area itself, with
+/// the user interface blocked for the duration of each frame.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum CutsceneKind {
+    #[default]
+    Window,
+    Area,
+}
+
+/// Moves the entity with the specified unique ID through each point of the
+/// path in turn
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct EntityMove {
+    pub entity: String,
+    pub path: Vec<Point>,
+}
 
 pub struct Frame {
     pub text: String,
+
+    /// If set, the frame is skipped unless this condition is met
+    pub condition: Option<Condition>,
+
+    pub background: Option<String>,
+    pub foreground: Option<String>,
+    pub music: Option<String>,
+    pub sfx: Option<String>,
+
+    /// If set, the frame automatically advances after this many millis
+    pub duration: Option<u32>,
+
+    /// Whether the frame fades in from black
+    pub fade: bool,
+
+    /// Triggers activated when the frame is first shown, such as `scroll_view`
+    pub on_start: Vec<OnTrigger>,
+    pub moves: Vec<EntityMove>,
 }
 
 pub struct Cutscene {
     pub id: String,
+    pub kind: CutsceneKind,
     pub frames: Vec<Frame>,
     pub on_end: Vec<OnTrigger>,
 }
 
 impl Cutscene {
-    pub fn new(builder: CutsceneBuilder, _module: &Module) -> Result<Cutscene, Error> {
+    pub fn new(builder: CutsceneBuilder, module: &Module) -> Result<Cutscene, Error> {
         let mut frames = Vec::new();
         for frame_builder in builder.frames {
+            if let Some(condition) = &frame_builder.condition {
+                if !condition.validate(module) {
+                    warn!("Invalid condition for cutscene frame");
+                    return unable_to_create_error("cutscene", &builder.id);
+                }
+            }
+
+            for image in frame_builder
+                .background
+                .iter()
+                .chain(&frame_builder.foreground)
+            {
+                if ResourceSet::image(image).is_none() {
+                    warn!("Unable to find image '{}' for cutscene frame", image);
+                    return unable_to_create_error("cutscene", &builder.id);
+                }
+            }
+
+            for sound in frame_builder.music.iter().chain(&frame_builder.sfx) {
+                if let Err(e) = ResourceSet::sound(sound) {
+                    warn!("Unable to find sound '{}' for cutscene frame: {}", sound, e);
+                    return unable_to_create_error("cutscene", &builder.id);
+                }
+            }
+
+            if builder.kind == CutsceneKind::Area && frame_builder.duration.is_none() {
+                warn!("All frames of an area cutscene must specify a duration");
+                return unable_to_create_error("cutscene", &builder.id);
+            }
+
+            if !condition::validate_triggers(&frame_builder.on_start, module) {
+                warn!("Invalid on_start condition for cutscene frame");
+                return unable_to_create_error("cutscene", &builder.id);
+            }
+
+            if frame_builder.moves.iter().any(|m| m.path.is_empty()) {
+                warn!("Cutscene entity moves must have at least one point");
+                return unable_to_create_error("cutscene", &builder.id);
+            }
+
             let frame = Frame {
                 text: frame_builder.text,
+                condition: frame_builder.condition,
+                background: frame_builder.background,
+                foreground: frame_builder.foreground,
+                music: frame_builder.music,
+                sfx: frame_builder.sfx,
+                duration: frame_builder.duration,
+                fade: frame_builder.fade,
+                on_start: frame_builder.on_start,
+                moves: frame_builder.moves,
             };
             frames.push(frame);
         }
 
         Ok(Cutscene {
             id: builder.id,
+            kind: builder.kind,
             frames,
             on_end: builder.on_end,
         })
@@ -49,13 +141,45 @@ impl Cutscene {
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct FrameBuilder {
+    #[serde(default)]
     pub text: String,
+
+    #[serde(default)]
+    pub condition: Option<Condition>,
+
+    #[serde(default)]
+    pub background: Option<String>,
+
+    #[serde(default)]
+    pub foreground: Option<String>,
+
+    #[serde(default)]
+    pub music: Option<String>,
+
+    #[serde(default)]
+    pub sfx: Option<String>,
+
+    #[serde(default)]
+    pub duration: Option<u32>,
+
+    #[serde(default)]
+    pub fade: bool,
+
+    #[serde(default)]
+    pub on_start: Vec<OnTrigger>,
+
+    #[serde(default)]
+    pub moves: Vec<EntityMove>,
 }
 
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct CutsceneBuilder {
     pub id: String,
+
+    #[serde(default)]
+    pub kind: CutsceneKind,
+
     pub frames: Vec<FrameBuilder>,
 
     #[serde(default)]
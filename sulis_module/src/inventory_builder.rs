@@ -45,6 +45,9 @@ pub struct ItemSaveState {
 
     #[serde(default)]
     pub variant: Option<usize>,
+
+    #[serde(default)]
+    pub durability: Option<u32>,
 }
 
 impl ItemSaveState {
@@ -60,6 +63,7 @@ impl ItemSaveState {
             id: item.item.original_id.clone(),
             adjectives,
             variant: item.variant,
+            durability: item.durability(),
         }
     }
 }
@@ -142,7 +146,8 @@ impl InventoryBuilder {
                     None
                 }
                 Some(item) => {
-                    let state = ItemState::new(item, entry.item.variant);
+                    let state = ItemState::new(item, entry.item.variant)
+                        .with_durability(entry.item.durability);
                     Some((qty, state))
                 }
             }
@@ -170,7 +175,8 @@ impl InventoryBuilder {
                 return None;
             }
 
-            Some((slot, ItemState::new(item, item_save.variant)))
+            let item_state = ItemState::new(item, item_save.variant);
+            Some((slot, item_state.with_durability(item_save.durability)))
         })
     }
 
@@ -206,9 +212,13 @@ impl InventoryBuilder {
                 Usable1 | Usable2 | Usable3 | Usable4 => {
                     item.usable.as_ref()?;
                 }
+                Ammo => {
+                    item.ammo.as_ref()?;
+                }
             }
 
-            Some((slot, ItemState::new(item, item_save.variant)))
+            let item_state = ItemState::new(item, item_save.variant);
+            Some((slot, item_state.with_durability(item_save.durability)))
         })
     }
 }
@@ -20,13 +20,14 @@ use std::collections::HashMap;
 use std::io::Error;
 use std::rc::Rc;
 
-use crate::rules::{bonus::AttackBuilder, BonusList, ItemKind, Slot};
+use crate::rules::{bonus::AttackBuilder, AttackBonuses, BonusList, ItemKind, Slot, WeaponKind};
 use sulis_core::image::Image;
 use sulis_core::resource::ResourceSet;
 use sulis_core::util::unable_to_create_error;
 
 use crate::{
     ability::{AIData, Duration},
+    on_trigger::ScriptData,
     Actor, ImageLayer, ItemAdjective, Module, PrereqList, PrereqListBuilder,
 };
 
@@ -38,6 +39,31 @@ pub struct Equippable {
     pub blocks_slot: Option<Slot>,
     pub bonuses: BonusList,
     pub attack: Option<AttackBuilder>,
+
+    /// The maximum durability of this item.  Items with no durability
+    /// never degrade.
+    #[serde(default)]
+    pub durability: Option<u32>,
+
+    /// If true, this weapon cannot attack without matching ammunition
+    /// in the ammo quick slot
+    #[serde(default)]
+    pub requires_ammo: bool,
+}
+
+/// Ammunition which may be placed in the ammo quick slot and is consumed
+/// by ranged weapons of the matching kinds on each attack
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Ammo {
+    pub weapon_kinds: Vec<WeaponKind>,
+
+    #[serde(default)]
+    pub bonuses: AttackBonuses,
+
+    /// Script function called with the attacker, defender, and hit
+    /// whenever an attack using this ammo does not miss
+    pub on_hit: Option<ScriptData>,
 }
 
 #[derive(Debug, Clone)]
@@ -68,6 +94,7 @@ pub struct Item {
     pub value: i32,
     pub weight: i32,
     pub usable: Option<Usable>,
+    pub ammo: Option<Ammo>,
 
     // original values from before any adjectives are applied
     pub original_id: String,
@@ -156,6 +183,7 @@ impl Item {
             value,
             weight: item.weight,
             usable: item.usable.clone(),
+            ammo: item.ammo.clone(),
             prereqs,
             original_id: item.original_id.clone(),
             original_value: item.original_value,
@@ -250,6 +278,7 @@ impl Item {
             value,
             weight: builder.weight as i32,
             usable,
+            ammo: builder.ammo,
             prereqs,
             original_id: builder.id,
             original_value: builder.value as i32,
@@ -313,6 +342,28 @@ impl Item {
             _ => false,
         }
     }
+
+    /// Returns true if this item is ammunition usable by the specified weapon
+    pub fn is_ammo_for(&self, weapon: &Item) -> bool {
+        let ammo = match &self.ammo {
+            None => return false,
+            Some(ammo) => ammo,
+        };
+
+        match weapon.kind {
+            ItemKind::Weapon { kind } => ammo.weapon_kinds.contains(&kind),
+            _ => false,
+        }
+    }
+
+    /// Returns true if this item is a weapon that must have ammunition
+    /// to attack
+    pub fn requires_ammo(&self) -> bool {
+        match &self.equippable {
+            None => false,
+            Some(equippable) => equippable.requires_ammo,
+        }
+    }
 }
 
 fn apply_adjectives(
@@ -435,6 +486,7 @@ pub struct ItemBuilder {
     value: u32,
     weight: u32,
     usable: Option<UsableBuilder>,
+    ammo: Option<Ammo>,
     #[serde(default)]
     adjectives: Vec<String>,
 
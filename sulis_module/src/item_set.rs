@@ -0,0 +1,104 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use std::io::Error;
+
+use sulis_core::util::unable_to_create_error;
+
+use crate::{on_trigger::ScriptData, BonusList, Module};
+
+/// Bonuses granted while at least `pieces` items of the set are equipped
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SetBonus {
+    pub pieces: u32,
+
+    #[serde(default)]
+    pub bonuses: BonusList,
+
+    /// Script function called with the wearer when this bonus becomes active
+    pub on_activate: Option<ScriptData>,
+
+    /// Script function called with the wearer when this bonus is lost
+    pub on_deactivate: Option<ScriptData>,
+}
+
+/// A group of items which grant additional bonuses when several of them
+/// are equipped at once.
+#[derive(Debug)]
+pub struct ItemSet {
+    pub id: String,
+    pub name: String,
+
+    /// The IDs of the items in this set.  Items with adjectives applied
+    /// still count as their base item
+    pub items: Vec<String>,
+
+    /// Bonuses, sorted by the number of pieces required
+    pub bonuses: Vec<SetBonus>,
+}
+
+impl ItemSet {
+    pub fn new(builder: ItemSetBuilder, module: &Module) -> Result<ItemSet, Error> {
+        for id in builder.items.iter() {
+            if !module.items.contains_key(id) {
+                warn!("Unable to find item '{}'", id);
+                return unable_to_create_error("item_set", &builder.id);
+            }
+        }
+
+        let mut bonuses = builder.bonuses;
+        for bonus in bonuses.iter() {
+            if bonus.pieces == 0 || bonus.pieces as usize > builder.items.len() {
+                warn!(
+                    "Set bonus requires {} pieces but the set has {} items",
+                    bonus.pieces,
+                    builder.items.len()
+                );
+                return unable_to_create_error("item_set", &builder.id);
+            }
+        }
+        bonuses.sort_by_key(|bonus| bonus.pieces);
+
+        Ok(ItemSet {
+            id: builder.id,
+            name: builder.name,
+            items: builder.items,
+            bonuses,
+        })
+    }
+
+    pub fn contains(&self, item_id: &str) -> bool {
+        self.items.iter().any(|id| id == item_id)
+    }
+
+    /// Returns the bonuses which are active with the specified number of
+    /// pieces equipped
+    pub fn active_bonuses(&self, pieces: u32) -> impl Iterator<Item = &SetBonus> {
+        self.bonuses
+            .iter()
+            .take_while(move |bonus| bonus.pieces <= pieces)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ItemSetBuilder {
+    pub id: String,
+    pub name: String,
+    pub items: Vec<String>,
+    pub bonuses: Vec<SetBonus>,
+}
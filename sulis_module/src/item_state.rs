@@ -17,27 +17,32 @@
 use std::collections::hash_map::Iter;
 use std::rc::Rc;
 
-use crate::{ImageLayer, Item, Module};
+use crate::{ImageLayer, Item, Module, Rules};
 use sulis_core::image::Image;
 
 #[derive(Debug, Clone)]
 pub struct ItemState {
     pub item: Rc<Item>,
     pub variant: Option<usize>,
+    durability: Option<u32>,
 }
 
 impl PartialEq for ItemState {
     fn eq(&self, other: &ItemState) -> bool {
-        Rc::ptr_eq(&self.item, &other.item) && self.variant == other.variant
+        Rc::ptr_eq(&self.item, &other.item)
+            && self.variant == other.variant
+            && self.durability == other.durability
     }
 }
 
 impl ItemState {
     pub fn new(item: Rc<Item>, variant: Option<usize>) -> ItemState {
+        let durability = max_durability(&item);
         match variant {
             None => ItemState {
                 item,
                 variant: None,
+                durability,
             },
             Some(idx) => {
                 if idx >= item.num_variants() {
@@ -45,14 +50,133 @@ impl ItemState {
                     ItemState {
                         item,
                         variant: None,
+                        durability,
                     }
                 } else {
-                    ItemState { item, variant }
+                    ItemState {
+                        item,
+                        variant,
+                        durability,
+                    }
                 }
             }
         }
     }
 
+    /// Sets the current durability of this item, typically from a saved
+    /// state.  Has no effect on items without durability, or if `durability`
+    /// is `None`.
+    pub fn with_durability(mut self, durability: Option<u32>) -> ItemState {
+        if let (Some(max), Some(cur)) = (self.max_durability(), durability) {
+            self.durability = Some(cur.min(max));
+            self.update_condition();
+        }
+        self
+    }
+
+    /// The current durability of this item, or `None` if this item
+    /// does not degrade
+    pub fn durability(&self) -> Option<u32> {
+        self.durability
+    }
+
+    pub fn max_durability(&self) -> Option<u32> {
+        max_durability(&self.item)
+    }
+
+    /// Returns true if this item has durability and is not at full durability
+    pub fn is_damaged(&self) -> bool {
+        match (self.durability, self.max_durability()) {
+            (Some(cur), Some(max)) => cur < max,
+            _ => false,
+        }
+    }
+
+    /// Returns the fraction of this item's durability that has been lost,
+    /// between 0.0 and 1.0
+    pub fn damage_frac(&self) -> f32 {
+        match (self.durability, self.max_durability()) {
+            (Some(cur), Some(max)) if max > 0 => 1.0 - cur as f32 / max as f32,
+            _ => 0.0,
+        }
+    }
+
+    /// Removes the specified amount of durability from this item.  Returns
+    /// true if the item's condition changed as a result, meaning its bonuses
+    /// may have changed
+    pub fn damage(&mut self, amount: u32) -> bool {
+        let cur = match self.durability {
+            None => return false,
+            Some(cur) => cur,
+        };
+
+        self.durability = Some(cur.saturating_sub(amount));
+        self.update_condition()
+    }
+
+    /// Restores this item to full durability.  Returns true if the item's
+    /// condition changed as a result
+    pub fn repair(&mut self) -> bool {
+        if !self.is_damaged() {
+            return false;
+        }
+
+        self.durability = self.max_durability();
+        self.update_condition()
+    }
+
+    /// Returns the item this item would be if it were fully repaired
+    pub fn repaired_item(&self) -> Rc<Item> {
+        let rules = Module::rules();
+        let adjectives = base_adjectives(&self.item, &rules);
+        match Module::create_get_item(&self.item.original_id, &adjectives) {
+            None => Rc::clone(&self.item),
+            Some(item) => item,
+        }
+    }
+
+    // applies the condition adjective specified in the rules for the current
+    // durability, replacing any other condition adjective
+    fn update_condition(&mut self) -> bool {
+        let rules = Module::rules();
+        let frac = 1.0 - self.damage_frac();
+        let condition = rules
+            .item_conditions
+            .iter()
+            .find(|cond| frac <= cond.threshold)
+            .map(|cond| cond.adjective.to_string());
+
+        let cur_condition = self
+            .item
+            .added_adjectives
+            .iter()
+            .find(|adj| rules.item_conditions.iter().any(|c| c.adjective == adj.id))
+            .map(|adj| adj.id.to_string());
+
+        if condition == cur_condition {
+            return false;
+        }
+
+        let mut adjectives = base_adjectives(&self.item, &rules);
+        if let Some(condition) = condition {
+            adjectives.push(condition);
+        }
+
+        match Module::create_get_item(&self.item.original_id, &adjectives) {
+            None => {
+                warn!(
+                    "Unable to create item '{}' with adjectives '{:?}'",
+                    self.item.original_id, adjectives
+                );
+                false
+            }
+            Some(item) => {
+                self.item = item;
+                true
+            }
+        }
+    }
+
     pub fn from(id: &str) -> Option<ItemState> {
         match Module::item(id) {
             None => None,
@@ -72,3 +196,16 @@ impl ItemState {
         self.item.icon(self.variant)
     }
 }
+
+fn max_durability(item: &Item) -> Option<u32> {
+    item.equippable.as_ref().and_then(|equip| equip.durability)
+}
+
+// the item's added adjectives, excluding any applied due to its condition
+fn base_adjectives(item: &Item, rules: &Rules) -> Vec<String> {
+    item.added_adjectives
+        .iter()
+        .filter(|adj| !rules.item_conditions.iter().any(|c| c.adjective == adj.id))
+        .map(|adj| adj.id.to_string())
+        .collect()
+}
@@ -37,9 +37,14 @@ pub use self::ai::AITemplate;
 pub mod area;
 pub use self::area::Area;
 
+pub mod bark;
+
 pub mod class;
 pub use self::class::Class;
 
+pub mod condition;
+pub use self::condition::Condition;
+
 pub mod conversation;
 pub use self::conversation::Conversation;
 
@@ -74,6 +79,7 @@ pub use self::inventory_builder::ItemListEntrySaveState;
 pub use self::inventory_builder::ItemSaveState;
 
 pub mod item;
+pub use self::item::Ammo;
 pub use self::item::Equippable;
 pub use self::item::Item;
 pub use self::item::Usable;
@@ -84,6 +90,9 @@ pub use self::item_state::ItemState;
 pub mod item_adjective;
 pub use self::item_adjective::{ItemAdjective, ItemAdjectiveBuilder};
 
+pub mod item_set;
+pub use self::item_set::{ItemSet, SetBonus};
+
 pub mod loot_list;
 pub use self::loot_list::LootList;
 
@@ -103,12 +112,17 @@ pub use self::quest::Quest;
 pub mod race;
 pub use self::race::Race;
 
+pub mod recipe;
+pub use self::recipe::Recipe;
+pub use self::recipe::RecipeOutput;
+
 pub mod rules;
 pub use self::rules::bonus;
 pub use self::rules::{
     AccuracyKind, Armor, ArmorKind, Attack, AttackBonuses, AttackKind, Attribute, AttributeList,
-    Bonus, BonusKind, BonusList, Damage, DamageKind, DamageList, HitFlags, HitKind, ItemKind,
-    QuickSlot, Resistance, Rules, Slot, StatList, Time, WeaponKind, WeaponStyle, ROUND_TIME_MILLIS,
+    Bonus, BonusKind, BonusList, Cover, Damage, DamageKind, DamageList, HitFlags, HitKind,
+    ItemKind, QuickSlot, Resistance, Rules, Slot, StatList, Time, WeaponKind, WeaponStyle,
+    ROUND_TIME_MILLIS,
 };
 
 use std::cell::RefCell;
@@ -139,10 +153,12 @@ use self::conversation::ConversationBuilder;
 use self::cutscene::CutsceneBuilder;
 use self::encounter::EncounterBuilder;
 use self::item::ItemBuilder;
+use self::item_set::ItemSetBuilder;
 use self::loot_list::LootListBuilder;
 use self::object_size::ObjectSizeBuilder;
 use self::prop::PropBuilder;
 use self::race::RaceBuilder;
+use self::recipe::RecipeBuilder;
 
 pub const MOVE_TO_THRESHOLD: f32 = 0.1;
 
@@ -164,10 +180,12 @@ pub struct Module {
     encounters: HashMap<String, Rc<Encounter>>,
     items: HashMap<String, Rc<Item>>,
     item_adjectives: HashMap<String, Rc<ItemAdjective>>,
+    item_sets: HashMap<String, Rc<ItemSet>>,
     loot_lists: HashMap<String, Rc<LootList>>,
     props: HashMap<String, Rc<Prop>>,
     quests: HashMap<String, Rc<Quest>>,
     races: HashMap<String, Rc<Race>>,
+    recipes: HashMap<String, Rc<Recipe>>,
     sizes: HashMap<String, Rc<ObjectSize>>,
     tiles: HashMap<String, Rc<Tile>>,
     scripts: HashMap<String, String>,
@@ -432,10 +450,12 @@ impl Module {
             module.encounters.clear();
             module.items.clear();
             module.item_adjectives.clear();
+            module.item_sets.clear();
             module.loot_lists.clear();
             module.quests.clear();
             module.props.clear();
             module.races.clear();
+            module.recipes.clear();
             module.sizes.clear();
             module.tiles.clear();
             module.scripts.clear();
@@ -502,6 +522,15 @@ impl Module {
                 insert_if_ok("item", id, Item::new(builder, &module), &mut module.items);
             }
 
+            for (id, builder) in builder_set.item_set_builders {
+                insert_if_ok(
+                    "item_set",
+                    id,
+                    ItemSet::new(builder, &module),
+                    &mut module.item_sets,
+                );
+            }
+
             for (id, builder) in builder_set.loot_builders.into_iter() {
                 insert_if_ok(
                     "loot list",
@@ -515,6 +544,15 @@ impl Module {
                 insert_if_ok("prop", id, Prop::new(builder, &module), &mut module.props);
             }
 
+            for (id, builder) in builder_set.recipe_builders {
+                insert_if_ok(
+                    "recipe",
+                    id,
+                    Recipe::new(builder, &module),
+                    &mut module.recipes,
+                );
+            }
+
             for (id, builder) in builder_set.race_builders.into_iter() {
                 insert_if_ok("race", id, Race::new(builder, &module), &mut module.races);
             }
@@ -759,11 +797,13 @@ impl Module {
         encounter, encounters, Encounter;
         item, items, Item;
         item_adjective, item_adjectives, ItemAdjective;
+        item_set, item_sets, ItemSet;
         loot_list, loot_lists, LootList;
         object_size, sizes, ObjectSize;
         quest, quests, Quest;
         prop, props, Prop;
         race, races, Race;
+        recipe, recipes, Recipe;
         tile, tiles, Tile;
         generator, generators, AreaGenerator;
         size, sizes, ObjectSize;
@@ -780,6 +820,24 @@ impl Module {
         })
     }
 
+    /// Returns the item set containing the item with the specified base ID,
+    /// if there is one
+    pub fn item_set_for(item_id: &str) -> Option<Rc<ItemSet>> {
+        MODULE.with(|r| {
+            r.borrow()
+                .item_sets
+                .values()
+                .find(|set| set.contains(item_id))
+                .cloned()
+        })
+    }
+
+    /// Validates the condition against the currently loaded resources.
+    /// See `Condition::validate`
+    pub fn validate_condition(condition: &Condition) -> bool {
+        MODULE.with(|r| condition.validate(&r.borrow()))
+    }
+
     pub fn all_sizes() -> Vec<Rc<ObjectSize>> {
         MODULE.with(|r| all_resources(&r.borrow().sizes))
     }
@@ -805,6 +863,10 @@ impl Module {
         })
     }
 
+    pub fn all_recipes() -> Vec<Rc<Recipe>> {
+        MODULE.with(|r| all_resources(&r.borrow().recipes))
+    }
+
     pub fn all_classes() -> Vec<Rc<Class>> {
         MODULE.with(|r| all_resources(&r.borrow().classes))
     }
@@ -853,8 +915,10 @@ impl Default for Module {
             props: HashMap::new(),
             quests: HashMap::new(),
             item_adjectives: HashMap::new(),
+            item_sets: HashMap::new(),
             loot_lists: HashMap::new(),
             races: HashMap::new(),
+            recipes: HashMap::new(),
             sizes: HashMap::new(),
             tiles: HashMap::new(),
             scripts: HashMap::new(),
@@ -880,9 +944,11 @@ struct ModuleBuilder {
     conversation_builders: HashMap<String, ConversationBuilder>,
     encounter_builders: HashMap<String, EncounterBuilder>,
     item_builders: HashMap<String, ItemBuilder>,
+    item_set_builders: HashMap<String, ItemSetBuilder>,
     loot_builders: HashMap<String, LootListBuilder>,
     prop_builders: HashMap<String, PropBuilder>,
     race_builders: HashMap<String, RaceBuilder>,
+    recipe_builders: HashMap<String, RecipeBuilder>,
     size_builders: HashMap<String, ObjectSizeBuilder>,
     tile_builders: HashMap<String, Tileset>,
     generator_builders: HashMap<String, GeneratorBuilder>,
@@ -906,10 +972,12 @@ impl ModuleBuilder {
             encounter_builders: read_builders(resources, Encounter)?,
             item_builders: read_builders(resources, Item)?,
             item_adjectives: read_builders(resources, ItemAdjective)?,
+            item_set_builders: read_builders(resources, ItemSet)?,
             loot_builders: read_builders(resources, LootList)?,
             prop_builders: read_builders(resources, Prop)?,
             quests: read_builders(resources, Quest)?,
             race_builders: read_builders(resources, Race)?,
+            recipe_builders: read_builders(resources, Recipe)?,
             size_builders: read_builders(resources, Size)?,
             tile_builders: read_builders(resources, Tile)?,
             generator_builders: read_builders(resources, Generator)?,
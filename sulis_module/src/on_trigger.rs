@@ -17,6 +17,7 @@
 use std::collections::HashMap;
 
 use crate::rules::Time;
+use crate::Condition;
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
@@ -39,6 +40,16 @@ pub struct MerchantData {
 
     #[serde(default)]
     pub refresh_time: Time,
+
+    /// If set, this merchant will repair items for this fraction of the
+    /// repaired item value
+    #[serde(default)]
+    pub repair_frac: Option<f32>,
+
+    /// Merchants sharing a faction also share the party's reputation.  If
+    /// not set, reputation is tracked for this merchant alone
+    #[serde(default)]
+    pub faction: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -83,6 +94,20 @@ pub struct NumFlagData {
     pub val: f32,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ReputationData {
+    pub faction: String,
+    pub amount: i32,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ApprovalData {
+    pub companion: String,
+    pub amount: i32,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum QuestEntryState {
     Hidden,
@@ -128,6 +153,8 @@ pub struct ModuleLoadData {
 pub enum OnTrigger {
     BlockUI(u32), // block user interface for specified number of millis
     PlayerCoins(i32),
+    FactionReputation(ReputationData),
+    CompanionApproval(ApprovalData),
     PartyMember(String),
     PartyItem(String),
     PlayerNumFlag(NumFlagData),
@@ -154,4 +181,5 @@ pub enum OnTrigger {
     NotQuestState(QuestStateData),
     FadeOutIn,
     CheckEndTurn,
+    Condition(Condition),
 }
@@ -16,6 +16,8 @@
 
 use std::collections::HashMap;
 
+use sulis_core::util::Point;
+
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct Quest {
@@ -29,4 +31,127 @@ pub struct Quest {
 #[serde(deny_unknown_fields)]
 pub struct QuestEntry {
     pub description: String,
+
+    /// Objectives which are tracked automatically while this entry is
+    /// active.  Once all of them are met, the entry is completed
+    #[serde(default)]
+    pub objectives: Vec<QuestObjective>,
+
+    /// The entry which becomes active once this entry's objectives are met
+    #[serde(default)]
+    pub next: Option<String>,
+
+    /// If true, the whole quest is completed once this entry's objectives
+    /// are met
+    #[serde(default)]
+    pub completes_quest: bool,
+
+    /// Rewards given to the party once this entry's objectives are met
+    #[serde(default)]
+    pub rewards: QuestRewards,
+
+    /// Where to show a marker on the area view and world map while this
+    /// entry is active and the quest is being tracked
+    #[serde(default)]
+    pub marker: Option<QuestMarker>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct QuestMarker {
+    pub area: String,
+
+    /// The location within the area to mark
+    #[serde(default)]
+    pub location: Option<Point>,
+
+    /// The unique ID of an entity to mark.  The marker follows the entity
+    /// as it moves
+    #[serde(default)]
+    pub entity: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct QuestObjective {
+    pub description: String,
+    pub kind: ObjectiveKind,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum ObjectiveKind {
+    /// Kill the specified number of actors with the given ID
+    Kill {
+        actor: String,
+
+        #[serde(default = "one")]
+        count: u32,
+    },
+
+    /// Have the specified number of items with the given ID in the stash
+    Obtain {
+        item: String,
+
+        #[serde(default = "one")]
+        count: u32,
+    },
+
+    /// Enter the specified area.  If a location is given, the party must
+    /// arrive within `distance` of it
+    Reach {
+        area: String,
+
+        #[serde(default)]
+        location: Option<Point>,
+
+        #[serde(default)]
+        distance: f32,
+    },
+
+    /// Start a conversation with an actor with the given ID
+    Talk { actor: String },
+
+    /// A counter which is advanced by scripts
+    Counter {
+        id: String,
+
+        #[serde(default = "one")]
+        count: u32,
+    },
+}
+
+impl ObjectiveKind {
+    /// Returns the progress needed to meet this objective
+    pub fn count(&self) -> u32 {
+        use self::ObjectiveKind::*;
+        match self {
+            Kill { count, .. } | Obtain { count, .. } | Counter { count, .. } => *count,
+            Reach { .. } | Talk { .. } => 1,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct QuestRewards {
+    #[serde(default)]
+    pub xp: u32,
+
+    #[serde(default)]
+    pub coins: i32,
+
+    /// Item IDs and quantities added to the party stash
+    #[serde(default)]
+    pub items: HashMap<String, u32>,
+}
+
+impl QuestRewards {
+    pub fn is_empty(&self) -> bool {
+        self.xp == 0 && self.coins == 0 && self.items.is_empty()
+    }
+}
+
+fn one() -> u32 {
+    1
 }
@@ -0,0 +1,273 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use std::collections::HashMap;
+use std::io::Error;
+use std::rc::Rc;
+
+use sulis_core::util::unable_to_create_error;
+
+use crate::rules::Slot;
+use crate::{Item, ItemAdjective, ItemState, Module, PrereqList, PrereqListBuilder};
+
+#[derive(Debug)]
+pub struct Ingredient {
+    pub item: Rc<Item>,
+    pub quantity: u32,
+}
+
+#[derive(Debug)]
+pub enum RecipeOutput {
+    /// Creates a new item, with the specified adjectives, in the party stash
+    Item {
+        id: String,
+        adjectives: Vec<String>,
+        quantity: u32,
+    },
+
+    /// Adds the adjective to an existing item chosen by the player.  If
+    /// `slots` is non-empty, only items equippable in one of those slots
+    /// may be chosen.
+    Adjective {
+        adjective: Rc<ItemAdjective>,
+        slots: Vec<Slot>,
+    },
+}
+
+/// A recipe combines a set of ingredients from the party stash into a new
+/// item, or applies an adjective to an existing item.
+#[derive(Debug)]
+pub struct Recipe {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub ingredients: Vec<Ingredient>,
+
+    /// Items that must be present in the stash but are not consumed
+    pub tools: Vec<Rc<Item>>,
+
+    /// If non-empty, the crafter must be near a prop with one of these IDs
+    pub near_props: Vec<String>,
+    pub prop_distance: f32,
+
+    /// Prereqs that must be met by the crafter
+    pub prereqs: Option<PrereqList>,
+
+    /// Class stats that are consumed from the crafter
+    pub class_stats: HashMap<String, u32>,
+
+    pub output: RecipeOutput,
+}
+
+impl Recipe {
+    pub fn new(builder: RecipeBuilder, module: &Module) -> Result<Recipe, Error> {
+        let builder_id = builder.id.clone();
+        let get_item = |id: &str| match module.items.get(id) {
+            None => {
+                warn!("Unable to find item '{}'", id);
+                unable_to_create_error("recipe", &builder_id)
+            }
+            Some(item) => Ok(Rc::clone(item)),
+        };
+
+        let get_adjective = |id: &str| match module.item_adjectives.get(id) {
+            None => {
+                warn!("Unable to find item adjective '{}'", id);
+                unable_to_create_error("recipe", &builder_id)
+            }
+            Some(adj) => Ok(Rc::clone(adj)),
+        };
+
+        let mut ingredients = Vec::new();
+        for (id, quantity) in builder.ingredients.iter() {
+            ingredients.push(Ingredient {
+                item: get_item(id)?,
+                quantity: *quantity,
+            });
+        }
+        ingredients.sort_by(|a, b| a.item.name.cmp(&b.item.name));
+
+        let mut tools = Vec::new();
+        for id in builder.tools.iter() {
+            tools.push(get_item(id)?);
+        }
+
+        for id in builder.near_props.iter() {
+            if !module.props.contains_key(id) {
+                warn!("Unable to find prop '{}'", id);
+                return unable_to_create_error("recipe", &builder.id);
+            }
+        }
+
+        let output = match builder.output {
+            RecipeOutputBuilder::Item {
+                id,
+                adjectives,
+                quantity,
+            } => {
+                get_item(&id)?;
+                for adj in adjectives.iter() {
+                    get_adjective(adj)?;
+                }
+                RecipeOutput::Item {
+                    id,
+                    adjectives,
+                    quantity,
+                }
+            }
+            RecipeOutputBuilder::Adjective { adjective, slots } => RecipeOutput::Adjective {
+                adjective: get_adjective(&adjective)?,
+                slots,
+            },
+        };
+
+        let prereqs = match builder.prereqs {
+            None => None,
+            Some(prereqs) => Some(PrereqList::new(prereqs)?),
+        };
+
+        Ok(Recipe {
+            id: builder.id,
+            name: builder.name,
+            description: builder.description,
+            ingredients,
+            tools,
+            near_props: builder.near_props,
+            prop_distance: builder.prop_distance,
+            prereqs,
+            class_stats: builder.class_stats,
+            output,
+        })
+    }
+
+    /// Returns true if the specified item may be chosen as the target of
+    /// this recipe.  Always false for recipes that create a new item.
+    pub fn can_target(&self, item_state: &ItemState) -> bool {
+        let (adjective, slots) = match &self.output {
+            RecipeOutput::Item { .. } => return false,
+            RecipeOutput::Adjective { adjective, slots } => (adjective, slots),
+        };
+
+        let equippable = match &item_state.item.equippable {
+            None => return false,
+            Some(equippable) => equippable,
+        };
+
+        if item_state.item.added_adjectives.contains(adjective) {
+            return false;
+        }
+
+        slots.is_empty()
+            || slots.contains(&equippable.slot)
+            || equippable
+                .alternate_slot
+                .is_some_and(|slot| slots.contains(&slot))
+    }
+
+    /// Creates the output item for this recipe, with any adjectives applied.
+    /// Returns `None` for recipes which modify an existing item
+    pub fn create_output(&self) -> Option<(u32, ItemState)> {
+        match &self.output {
+            RecipeOutput::Adjective { .. } => None,
+            RecipeOutput::Item {
+                id,
+                adjectives,
+                quantity,
+            } => {
+                let item = Module::create_get_item(id, adjectives)?;
+                Some((*quantity, ItemState::new(item, None)))
+            }
+        }
+    }
+
+    /// Creates a copy of `item_state` with this recipe's adjective added.
+    /// Returns `None` for recipes which create a new item
+    pub fn apply_to(&self, item_state: &ItemState) -> Option<ItemState> {
+        let adjective = match &self.output {
+            RecipeOutput::Item { .. } => return None,
+            RecipeOutput::Adjective { adjective, .. } => adjective,
+        };
+
+        let mut adjectives: Vec<String> = item_state
+            .item
+            .added_adjectives
+            .iter()
+            .map(|adj| adj.id.to_string())
+            .collect();
+        adjectives.push(adjective.id.to_string());
+
+        let item = Module::create_get_item(&item_state.item.original_id, &adjectives)?;
+        let durability = item_state.durability();
+        Some(ItemState::new(item, item_state.variant).with_durability(durability))
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum RecipeOutputBuilder {
+    Item {
+        id: String,
+
+        #[serde(default)]
+        adjectives: Vec<String>,
+
+        #[serde(default = "one")]
+        quantity: u32,
+    },
+    Adjective {
+        adjective: String,
+
+        #[serde(default)]
+        slots: Vec<Slot>,
+    },
+}
+
+fn one() -> u32 {
+    1
+}
+
+fn default_prop_distance() -> f32 {
+    5.0
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct RecipeBuilder {
+    pub id: String,
+    pub name: String,
+
+    #[serde(default)]
+    pub description: String,
+
+    #[serde(default)]
+    pub ingredients: HashMap<String, u32>,
+
+    #[serde(default)]
+    pub tools: Vec<String>,
+
+    #[serde(default)]
+    pub near_props: Vec<String>,
+
+    #[serde(default = "default_prop_distance")]
+    pub prop_distance: f32,
+
+    pub prereqs: Option<PrereqListBuilder>,
+
+    #[serde(default)]
+    pub class_stats: HashMap<String, u32>,
+
+    pub output: RecipeOutputBuilder,
+}
@@ -52,6 +52,7 @@ pub mod stat_list;
 pub use self::stat_list::StatList;
 
 use crate::area::LocationKind;
+use crate::on_trigger::ScriptData;
 use sulis_core::ui::{color, Color};
 use sulis_core::util::{gen_rand, invalid_data_error};
 
@@ -69,6 +70,7 @@ pub struct Rules {
     pub attack_ap: u32,
     pub display_ap: u32,
     pub swap_weapons_ap: u32,
+    pub max_loadouts: usize,
     pub initiative_roll_max: i32,
     pub base_flanking_angle: i32,
     pub graze_percentile: u32,
@@ -78,6 +80,12 @@ pub struct Rules {
     pub flanking_accuracy_bonus: i32,
     pub hidden_accuracy_bonus: i32,
 
+    pub partial_cover_defense_bonus: i32,
+    pub full_cover_defense_bonus: i32,
+    pub full_cover_fraction: f32,
+    pub high_ground_accuracy_bonus: i32,
+    pub low_ground_accuracy_penalty: i32,
+
     pub graze_damage_multiplier: f32,
     pub crit_damage_multiplier: f32,
 
@@ -100,13 +108,50 @@ pub struct Rules {
     pub experience_for_level: Vec<u32>,
 
     pub combat_run_away_vis_factor: f32,
+
+    /// The fraction of threat against an entity retained each round
+    pub threat_decay_factor: f32,
+
+    /// Threat generated against a healer, per hit point healed
+    pub healing_threat_factor: f32,
+
     pub loot_drop_prop: String,
 
     pub item_weight_display_factor: f32,
     pub item_value_display_factor: f32,
 
+    /// The weight any character can carry, regardless of attributes
+    pub base_carry_weight: u32,
+
+    /// Additional carry weight granted per point of strength
+    pub carry_weight_per_strength: u32,
+
+    /// Penalties applied to characters carrying more than a fraction of their
+    /// capacity.  Should be sorted by ascending threshold.
+    pub encumbrance: Vec<EncumbranceLevel>,
+
     pub coins_item: String,
 
+    /// Adjectives applied to items as their durability is lost.  Should be
+    /// sorted by ascending threshold.
+    pub item_conditions: Vec<ItemCondition>,
+
+    /// Durability lost by a weapon each time it hits
+    pub weapon_durability_loss: u32,
+
+    /// Durability lost by a piece of armor each time its wearer is hit
+    pub armor_durability_loss: u32,
+
+    /// How merchant prices react to reputation, haggling, and stock
+    pub merchant_pricing: MerchantPricing,
+
+    /// Items sold to a merchant may be bought back at the sold price for
+    /// this long
+    pub merchant_buyback_time: Time,
+
+    /// The maximum number of sold items each merchant keeps for buyback
+    pub merchant_buyback_max: usize,
+
     armor_damage_reduction_cap: Vec<u32>,
 
     pub rounds_per_hour: u32,
@@ -118,6 +163,11 @@ pub struct Rules {
     pub hints: Vec<String>,
 
     pub main_menu_music: Option<String>,
+
+    /// Optional script used to resolve attack rolls in place of the built in
+    /// graze / hit / crit formula.
+    #[serde(default)]
+    pub attack_resolution_script: Option<ScriptData>,
 }
 
 impl Rules {
@@ -192,6 +242,27 @@ impl Rules {
             }
         }
 
+        let pricing = &self.merchant_pricing;
+        if pricing.min_reputation > pricing.max_reputation {
+            return invalid_data_error("Merchant min_reputation must not exceed max_reputation");
+        }
+
+        if !(0.0..1.0).contains(&pricing.max_modifier) {
+            return invalid_data_error("Merchant max_modifier must be at least 0 and less than 1");
+        }
+
+        for pair in self.item_conditions.windows(2) {
+            if pair[0].threshold >= pair[1].threshold {
+                return invalid_data_error("Item conditions must have ascending thresholds");
+            }
+        }
+
+        for pair in self.encumbrance.windows(2) {
+            if pair[0].threshold >= pair[1].threshold {
+                return invalid_data_error("Encumbrance levels must have ascending thresholds");
+            }
+        }
+
         Ok(())
     }
 
@@ -312,6 +383,39 @@ impl Rules {
         self.experience_for_level[(cur_level - 1) as usize]
     }
 
+    /// Returns the defense bonus granted to the target of a ranged attack
+    /// with the specified `cover`
+    pub fn cover_defense_bonus(&self, cover: Cover) -> i32 {
+        match cover {
+            Cover::None => 0,
+            Cover::Partial => self.partial_cover_defense_bonus,
+            Cover::Full => self.full_cover_defense_bonus,
+        }
+    }
+
+    /// Returns the maximum weight that can be carried without penalty
+    /// by a character with the specified attributes
+    pub fn carry_capacity(&self, attrs: &AttributeList) -> u32 {
+        self.base_carry_weight + self.carry_weight_per_strength * attrs.strength as u32
+    }
+
+    /// Returns the index of the highest encumbrance level that applies to a
+    /// character carrying `weight` with the given `capacity`, if any
+    pub fn encumbrance_level(&self, weight: u32, capacity: u32) -> Option<usize> {
+        let frac = if capacity == 0 {
+            if weight == 0 {
+                return None;
+            }
+            f32::INFINITY
+        } else {
+            weight as f32 / capacity as f32
+        };
+
+        self.encumbrance
+            .iter()
+            .rposition(|level| frac > level.threshold)
+    }
+
     pub fn concealment_roll(&self, concealment: i32) -> bool {
         if concealment == 0 {
             return true;
@@ -322,6 +426,77 @@ impl Rules {
     }
 }
 
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct EncumbranceLevel {
+    pub name: String,
+
+    /// The fraction of carry capacity that must be exceeded for this level
+    /// to apply
+    pub threshold: f32,
+
+    pub bonuses: BonusList,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ItemCondition {
+    /// The ID of the item adjective to apply
+    pub adjective: String,
+
+    /// The fraction of max durability at or below which this condition
+    /// applies
+    pub threshold: f32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MerchantPricing {
+    /// Price modifier per point of reputation with a merchant or faction
+    pub reputation_factor: f32,
+    pub min_reputation: i32,
+    pub max_reputation: i32,
+
+    /// The attribute of the party's best haggler that improves prices
+    pub haggle_attribute: Attribute,
+
+    /// Price modifier per point of the haggle attribute above the base
+    pub haggle_attribute_factor: f32,
+
+    /// An optional class stat that further improves prices
+    #[serde(default)]
+    pub haggle_class_stat: Option<String>,
+
+    #[serde(default)]
+    pub haggle_class_stat_factor: f32,
+
+    /// The maximum combined modifier from reputation and haggling
+    pub max_modifier: f32,
+
+    /// Fraction of the sell price lost for each copy of an item the
+    /// merchant already has in stock
+    pub scarcity_factor: f32,
+
+    /// The minimum fraction of the sell price paid regardless of stock
+    pub min_scarcity_frac: f32,
+}
+
+impl MerchantPricing {
+    pub fn clamp_reputation(&self, reputation: i32) -> i32 {
+        reputation.max(self.min_reputation).min(self.max_reputation)
+    }
+
+    pub fn reputation_modifier(&self, reputation: i32) -> f32 {
+        self.clamp_reputation(reputation) as f32 * self.reputation_factor
+    }
+
+    /// Returns the sell price multiplier for an item with `stock` copies
+    /// already held by the merchant
+    pub fn scarcity_frac(&self, stock: u32) -> f32 {
+        (1.0 - stock as f32 * self.scarcity_factor).max(self.min_scarcity_frac)
+    }
+}
+
 pub const ROUND_TIME_MILLIS: u32 = 5000;
 
 #[derive(Deserialize, Serialize, Debug, Copy, Clone, PartialEq, Eq)]
@@ -477,6 +652,7 @@ pub enum QuickSlot {
     Usable2,
     Usable3,
     Usable4,
+    Ammo,
 }
 
 impl QuickSlot {
@@ -491,8 +667,8 @@ impl QuickSlot {
 
 use self::QuickSlot::*;
 
-const QUICKSLOTS_LIST: [QuickSlot; 6] =
-    [AltHeldMain, AltHeldOff, Usable1, Usable2, Usable3, Usable4];
+const QUICKSLOTS_LIST: [QuickSlot; 7] =
+    [AltHeldMain, AltHeldOff, Usable1, Usable2, Usable3, Usable4, Ammo];
 
 const USABLE_QUICKSLOTS_LIST: [QuickSlot; 4] = [Usable1, Usable2, Usable3, Usable4];
 
@@ -520,6 +696,15 @@ impl Default for HitFlags {
     }
 }
 
+/// The amount of cover a target has against a ranged attack, based on
+/// the walls and props between the attacker and target.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Cover {
+    None,
+    Partial,
+    Full,
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord)]
 pub enum HitKind {
     Miss,
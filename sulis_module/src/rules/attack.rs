@@ -121,6 +121,30 @@ impl Attack {
         }
     }
 
+    /// Creates a copy of `other` with the specified attack bonuses, such as
+    /// those from ammunition, added
+    pub fn with_attack_bonuses(other: &Attack, bonuses_to_add: &AttackBonuses) -> Attack {
+        let mut bonuses = other.bonuses.clone();
+        bonuses.add(bonuses_to_add);
+
+        let mut bonus_damage = other.damage.clone().into_vec();
+        assert!(!bonus_damage.is_empty());
+
+        let base_damage = bonus_damage.remove(0);
+        if let Some(damage) = bonuses_to_add.damage {
+            bonus_damage.push(damage);
+        }
+
+        let damage = DamageList::new(base_damage, &bonus_damage);
+
+        Attack {
+            kind: other.kind.clone(),
+            bonuses,
+            damage,
+            sounds: other.sounds.clone(),
+        }
+    }
+
     pub fn new(builder: &AttackBuilder, stats: &StatList, weapon_kind: WeaponKind) -> Attack {
         let mut bonuses = builder.bonuses.clone();
         let mut bonus_damage = stats.bonus_damage.clone();
@@ -57,6 +57,7 @@ pub enum BonusKind {
     AttackCost(i32),
     FlankingAngle(i32),
     CasterLevel(i32),
+    Threat(i32),
     AbilityActionPointCost(i32),
     FreeAbilityGroupUse,
     MoveDisabled,
@@ -229,6 +230,7 @@ fn apply_modifiers(bonus: &mut Bonus, neg: f32, pos: f32) {
         AttackCost(val) => get_mod!(AttackCost(val): i32, neg, pos),
         FlankingAngle(val) => get_mod!(FlankingAngle(val): i32, neg, pos),
         CasterLevel(val) => get_mod!(CasterLevel(val): i32, neg, pos),
+        Threat(val) => get_mod!(Threat(val): i32, neg, pos),
         AbilityActionPointCost(val) => get_mod!(AbilityActionPointCost(val): i32, neg, pos),
         Damage(damage) => Damage(damage.mult_f32(pos)),
         ClassStat { ref id, amount } => ClassStat {
@@ -387,6 +389,7 @@ pub fn merge_if_dup(first: &Bonus, sec: &Bonus) -> Option<Bonus> {
         AttackCost(val) => merge_dup!(AttackCost(val): sec, when),
         FlankingAngle(val) => merge_dup!(FlankingAngle(val): sec, when),
         CasterLevel(val) => merge_dup!(CasterLevel(val): sec, when),
+        Threat(val) => merge_dup!(Threat(val): sec, when),
     }
 }
 
@@ -81,6 +81,7 @@ pub struct StatList {
     pub crit_immunity: bool,
     pub free_ability_group_use: bool,
     pub caster_level: i32,
+    pub threat: i32,
     has_shield: bool,
     group_uses_per_encounter: HashMap<String, ExtInt>,
     group_uses_per_day: HashMap<String, ExtInt>,
@@ -136,6 +137,7 @@ impl StatList {
             crit_immunity: false,
             free_ability_group_use: false,
             caster_level: 0,
+            threat: 0,
             has_shield: false,
             group_uses_per_encounter: HashMap::new(),
             group_uses_per_day: HashMap::new(),
@@ -374,6 +376,7 @@ impl StatList {
             AttackCost(amount) => self.attack_cost -= amount * times_i32,
             FlankingAngle(amount) => self.flanking_angle -= amount * times_i32,
             CasterLevel(amount) => self.caster_level += amount * times_i32,
+            Threat(amount) => self.threat += amount * times_i32,
             FreeAbilityGroupUse => self.free_ability_group_use = true,
             AbilitiesDisabled => self.abilities_disabled = true,
             MoveDisabled => self.move_disabled = true,
@@ -32,6 +32,7 @@ pub enum DisabledReason {
     RequiresShield,
     RequiresMelee,
     RequiresRanged,
+    NoAmmo,
     RequiresActiveMode,
     CombatOnly,
     OnCooldown,
@@ -115,7 +116,12 @@ impl AbilityState {
         self.ability.active.as_ref().unwrap().ap
     }
 
-    pub fn is_available(&self, stats: &StatList, current_modes: &[&str]) -> DisabledReason {
+    pub fn is_available(
+        &self,
+        stats: &StatList,
+        current_modes: &[&str],
+        has_ammo: bool,
+    ) -> DisabledReason {
         use DisabledReason::*;
 
         if self.requires_shield && !stats.has_shield() {
@@ -127,6 +133,9 @@ impl AbilityState {
         if self.requires_ranged && !stats.attack_is_ranged() {
             return RequiresRanged;
         }
+        if self.requires_ranged && !has_ammo {
+            return NoAmmo;
+        }
 
         if !self.requires_active_mode.is_empty() {
             let mut found = false;
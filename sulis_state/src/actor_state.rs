@@ -22,12 +22,15 @@ use std::rc::Rc;
 use crate::save_state::ActorSaveState;
 use crate::{
     ability_state::DisabledReason, AbilityState, ChangeListenerList, Effect, EntityState,
-    GameState, Inventory, PStats,
+    GameState, Inventory, Loadout, PStats, QuestEvent,
 };
 use sulis_core::image::{Image, LayeredImage};
 use sulis_core::io::GraphicsRenderer;
 use sulis_core::util::{invalid_data_error, ExtInt, Offset, Scale};
-use sulis_module::{Ability, Actor, ActorBuilder, Faction, ImageLayer, Module};
+use sulis_module::{
+    bark::BarkEvent, on_trigger::ScriptData, Ability, Actor, ActorBuilder, Faction, ImageLayer,
+    ItemSet, Module,
+};
 use sulis_module::{BonusList, ItemKind, ItemState, QuickSlot, Slot, StatList};
 
 pub struct ActorState {
@@ -35,6 +38,9 @@ pub struct ActorState {
     pub stats: StatList,
     pub listeners: ChangeListenerList<ActorState>,
     inventory: Inventory,
+    loadouts: Vec<Loadout>,
+    active_set_bonuses: Option<Vec<(Rc<ItemSet>, usize)>>,
+    pending_set_scripts: Vec<ScriptData>,
     effects: Vec<(usize, BonusList)>,
     image: LayeredImage,
     pub(crate) ability_states: HashMap<String, AbilityState>,
@@ -42,10 +48,18 @@ pub struct ActorState {
     anim_image_layers: HashMap<ImageLayer, Rc<dyn Image>>,
     p_stats: PStats,
     started_turn_with_no_ap_for_actions: bool,
+    stash_weight: u32,
+    carry_capacity: u32,
+    encumbrance: Option<usize>,
+
+    // only party members use up ammunition, so only they need it loaded
+    unlimited_ammo: bool,
 }
 
 impl ActorState {
     pub fn load(mut save: ActorSaveState, base: Option<ActorBuilder>) -> Result<ActorState, Error> {
+        // only party members are saved with a base actor
+        let unlimited_ammo = base.is_none();
         let actor = match base {
             None => match Module::actor(&save.id) {
                 None => invalid_data_error(&format!("No actor with id '{}'", save.id)),
@@ -106,6 +120,9 @@ impl ActorState {
         Ok(ActorState {
             actor,
             inventory,
+            loadouts: save.loadouts,
+            active_set_bonuses: None,
+            pending_set_scripts: Vec::new(),
             stats: StatList::new(attrs),
             listeners: ChangeListenerList::default(),
             image,
@@ -115,6 +132,10 @@ impl ActorState {
             p_stats: save.p_stats,
             anim_image_layers: HashMap::new(),
             started_turn_with_no_ap_for_actions: false,
+            stash_weight: 0,
+            carry_capacity: 0,
+            encumbrance: None,
+            unlimited_ammo,
         })
     }
 
@@ -143,6 +164,9 @@ impl ActorState {
         let mut actor_state = ActorState {
             actor: Rc::clone(&actor),
             inventory,
+            loadouts: Vec::new(),
+            active_set_bonuses: None,
+            pending_set_scripts: Vec::new(),
             stats: StatList::new(attrs),
             listeners: ChangeListenerList::default(),
             image,
@@ -152,6 +176,10 @@ impl ActorState {
             p_stats: PStats::new(&actor),
             anim_image_layers: HashMap::new(),
             started_turn_with_no_ap_for_actions: false,
+            stash_weight: 0,
+            carry_capacity: 0,
+            encumbrance: None,
+            unlimited_ammo: true,
         };
 
         actor_state.compute_stats();
@@ -180,6 +208,9 @@ impl ActorState {
             }
         }
 
+        // starting equipment should not fire set scripts
+        actor_state.active_set_bonuses = None;
+
         actor_state
     }
 
@@ -256,8 +287,13 @@ impl ActorState {
         }
     }
 
+    /// Returns the current faction, which for actors affiliated with a
+    /// named campaign faction depends on the party's reputation
     pub fn faction(&self) -> Faction {
-        self.p_stats.faction
+        match &self.actor.affiliation {
+            None => self.p_stats.faction,
+            Some(id) => GameState::effective_faction(id, self.p_stats.faction),
+        }
     }
 
     pub fn set_faction(&mut self, faction: Faction) {
@@ -387,7 +423,11 @@ impl ActorState {
                     return NotEnoughClassStat;
                 }
 
-                state.is_available(&self.stats, &self.current_active_modes())
+                state.is_available(
+                    &self.stats,
+                    &self.current_active_modes(),
+                    self.has_ammo_for_attack(),
+                )
             }
         }
     }
@@ -412,8 +452,11 @@ impl ActorState {
                     return false;
                 }
 
-                state.is_available(&self.stats, &self.current_active_modes())
-                    == DisabledReason::Enabled
+                state.is_available(
+                    &self.stats,
+                    &self.current_active_modes(),
+                    self.has_ammo_for_attack(),
+                ) == DisabledReason::Enabled
             }
         }
     }
@@ -551,6 +594,16 @@ impl ActorState {
         self.p_stats.ap() >= self.stats.attack_cost as u32
     }
 
+    /// Returns false if this actor's weapon requires ammunition and none
+    /// is loaded.  Actors outside the party never run out of ammunition
+    pub fn has_ammo_for_attack(&self) -> bool {
+        self.unlimited_ammo || self.inventory.has_ammo_for_attack()
+    }
+
+    pub(crate) fn set_unlimited_ammo(&mut self, unlimited: bool) {
+        self.unlimited_ammo = unlimited;
+    }
+
     pub fn has_ap_for_any_action(&self) -> bool {
         self.ap() >= self.get_move_ap_cost(1) || self.has_ap_to_attack()
     }
@@ -560,7 +613,7 @@ impl ActorState {
     #[must_use]
     pub fn set_quick(&mut self, item: ItemState, slot: QuickSlot) -> Option<ItemState> {
         let item = self.inventory.set_quick(item, slot);
-        self.listeners.notify(&self);
+        self.update_encumbrance();
         item
     }
 
@@ -569,7 +622,7 @@ impl ActorState {
     #[must_use]
     pub fn clear_quick(&mut self, slot: QuickSlot) -> Option<ItemState> {
         let item = self.inventory.clear_quick(slot);
-        self.listeners.notify(&self);
+        self.update_encumbrance();
         item
     }
 
@@ -638,15 +691,155 @@ impl ActorState {
         &self.inventory
     }
 
+    pub fn loadouts(&self) -> &[Loadout] {
+        &self.loadouts
+    }
+
+    /// Saves the currently equipped and quick slot items as a loadout with the
+    /// given name, replacing any existing loadout of the same name.  Returns
+    /// false if the maximum number of loadouts has already been reached
+    pub fn save_loadout(&mut self, name: &str) -> bool {
+        let loadout = Loadout::new(name, &self.inventory);
+        match self.loadouts.iter().position(|l| l.name == name) {
+            Some(index) => self.loadouts[index] = loadout,
+            None => {
+                if self.loadouts.len() >= Module::rules().max_loadouts {
+                    return false;
+                }
+                self.loadouts.push(loadout);
+            }
+        }
+
+        self.listeners.notify(&self);
+        true
+    }
+
+    pub fn delete_loadout(&mut self, index: usize) {
+        if index >= self.loadouts.len() {
+            return;
+        }
+
+        self.loadouts.remove(index);
+        self.listeners.notify(&self);
+    }
+
+    /// Returns true if a loadout can currently be applied.  In combat, this
+    /// requires enough AP to swap weapons, as only weapon slots are changed
+    pub fn can_apply_loadout(&self) -> bool {
+        if GameState::is_combat_active() {
+            self.can_swap_weapons()
+        } else {
+            !self.p_stats.is_inventory_locked()
+        }
+    }
+
+    pub fn can_set_quick(&mut self, item: &ItemState, slot: QuickSlot) -> bool {
+        self.inventory.can_set_quick(item, slot, &self.actor)
+    }
+
+    /// Removes the specified durability from the item in the given slot,
+    /// recomputing stats if its condition changed
+    pub fn damage_item(&mut self, slot: Slot, amount: u32) {
+        if self.inventory.damage_item(slot, amount) {
+            self.compute_stats();
+            self.texture_cache_invalid = true;
+        }
+    }
+
+    /// Repairs the item in the given slot, recomputing stats if its
+    /// condition changed
+    pub fn repair_item(&mut self, slot: Slot) {
+        if self.inventory.repair_item(slot) {
+            self.compute_stats();
+            self.texture_cache_invalid = true;
+        } else {
+            self.listeners.notify(&self);
+        }
+    }
+
+    /// Repairs all equipped and quick slot items
+    pub fn repair_all_items(&mut self) {
+        if self.inventory.repair_all() {
+            self.compute_stats();
+            self.texture_cache_invalid = true;
+        } else {
+            self.listeners.notify(&self);
+        }
+    }
+
+    /// Returns the total weight this actor is carrying, including both its
+    /// own inventory and its share of the party stash
+    pub fn carried_weight(&self) -> u32 {
+        self.inventory.weight() + self.stash_weight
+    }
+
+    /// Returns this actor's share of the party stash weight
+    pub fn stash_weight(&self) -> u32 {
+        self.stash_weight
+    }
+
+    /// Returns the maximum weight this actor may carry before becoming
+    /// encumbered, based on its current attributes
+    pub fn carry_capacity(&self) -> u32 {
+        self.carry_capacity
+    }
+
+    /// Returns the index of the rules encumbrance level currently applying
+    /// to this actor, if any
+    pub fn encumbrance(&self) -> Option<usize> {
+        self.encumbrance
+    }
+
+    pub(crate) fn set_stash_weight(&mut self, weight: u32) {
+        if self.stash_weight == weight {
+            return;
+        }
+
+        self.stash_weight = weight;
+        self.update_encumbrance();
+    }
+
+    fn update_encumbrance(&mut self) {
+        let level = Module::rules().encumbrance_level(self.carried_weight(), self.carry_capacity);
+        if level != self.encumbrance {
+            self.compute_stats();
+        } else {
+            self.listeners.notify(&self);
+        }
+    }
+
     pub fn is_dead(&self) -> bool {
         self.hp() <= 0
     }
 
+    /// Returns true if this actor's hit points are below the low hit point
+    /// fraction used for barks
+    pub fn is_low_hp(&self) -> bool {
+        let threshold = Module::campaign().barks.low_hp * self.stats.max_hp as f32;
+        (self.hp() as f32) < threshold
+    }
+
     pub fn check_death(parent: &Rc<RefCell<EntityState>>, target: &Rc<RefCell<EntityState>>) {
         if target.borrow().actor.hp() > 0 {
             return;
         }
 
+        let id = target.borrow().actor.actor.id.to_string();
+        GameState::add_quest_event(QuestEvent::Killed(id));
+
+        if !Rc::ptr_eq(parent, target) {
+            GameState::add_bark_event(BarkEvent::KillingBlow, Some(parent));
+        }
+
+        if parent.borrow().is_party_member() {
+            let affiliation = target.borrow().actor.actor.affiliation.clone();
+            if let Some(faction_id) = affiliation {
+                if let Some(faction) = Module::campaign().factions.get(&faction_id) {
+                    GameState::add_faction_reputation(&faction_id, -faction.kill_penalty);
+                }
+            }
+        }
+
         let area_state = GameState::area_state();
 
         let reward = {
@@ -834,49 +1027,96 @@ impl ActorState {
         self.listeners.notify(&self);
     }
 
-    pub fn compute_stats(&mut self) {
-        debug!("Compute stats for '{}'", self.actor.name);
-        self.stats = StatList::new(self.actor.attributes);
+    /// Returns any item set scripts that need to be fired as a result of
+    /// set bonuses becoming active or inactive, clearing the list
+    pub(crate) fn take_pending_set_scripts(&mut self) -> Vec<ScriptData> {
+        self.pending_set_scripts.drain(..).collect()
+    }
 
-        let mut layers_override = self.inventory().get_image_layers();
-        for (layer, image) in self.anim_image_layers.iter() {
-            layers_override.insert(*layer, Rc::clone(image));
+    pub(crate) fn has_pending_set_scripts(&self) -> bool {
+        !self.pending_set_scripts.is_empty()
+    }
+
+    fn update_set_bonuses(&mut self) {
+        let mut active = Vec::new();
+        for (set, pieces) in self.inventory.equipped_sets() {
+            let count = set.active_bonuses(pieces).count();
+            for index in 0..count {
+                active.push((Rc::clone(&set), index));
+            }
         }
 
-        let layers = self.actor.image_layers().get_list_with(
-            self.actor.sex,
-            &self.actor.race,
-            self.actor.hair_color,
-            self.actor.skin_color,
-            layers_override,
-        );
-        self.image = LayeredImage::new(layers, self.actor.hue);
+        let prev = match self.active_set_bonuses.replace(active) {
+            // the first computation just records the current state
+            None => return,
+            Some(prev) => prev,
+        };
+
+        let contains = |list: &[(Rc<ItemSet>, usize)], set: &ItemSet, index: usize| {
+            list.iter().any(|(other, i)| other.id == set.id && *i == index)
+        };
 
-        self.stats.add(&self.actor.race.base_stats);
+        let active = self.active_set_bonuses.as_ref().unwrap();
+        for (set, index) in prev.iter() {
+            if contains(active, set, *index) {
+                continue;
+            }
+
+            if let Some(script) = &set.bonuses[*index].on_deactivate {
+                self.pending_set_scripts.push(script.clone());
+            }
+        }
+
+        for (set, index) in active.iter() {
+            if contains(&prev, set, *index) {
+                continue;
+            }
+
+            if let Some(script) = &set.bonuses[*index].on_activate {
+                self.pending_set_scripts.push(script.clone());
+            }
+        }
+    }
+
+    /// Computes the stats this actor would have if the specified item were
+    /// equipped, replacing whatever currently occupies its slot.  Used to
+    /// compare items against the actor's current equipment
+    pub fn stats_with_item(&self, item_state: &ItemState) -> StatList {
+        let mut inventory = self.inventory.clone();
+        let _ = inventory.equip(item_state.clone(), None);
+        let (stats, _, _) = self.stats_for(&inventory);
+        stats
+    }
+
+    /// Computes the stats, carry capacity, and encumbrance level this actor
+    /// would have with the specified inventory
+    fn stats_for(&self, inventory: &Inventory) -> (StatList, u32, Option<usize>) {
+        let mut stats = StatList::new(self.actor.attributes);
+
+        stats.add(&self.actor.race.base_stats);
 
         for &(ref class, level) in self.actor.levels.iter() {
-            self.stats.add_multiple(&class.bonuses_per_level, level);
+            stats.add_multiple(&class.bonuses_per_level, level);
             for (ref group_id, amount) in class.group_uses_per_encounter(level).iter() {
-                self.stats
-                    .add_single_group_uses_per_encounter(group_id, *amount);
+                stats.add_single_group_uses_per_encounter(group_id, *amount);
             }
 
             for (ref group_id, amount) in class.group_uses_per_day(level).iter() {
-                self.stats.add_single_group_uses_per_day(group_id, *amount);
+                stats.add_single_group_uses_per_day(group_id, *amount);
             }
 
             for (stat_id, amount) in class.stats_max(level) {
-                self.stats.add_single_class_stat_max(stat_id, amount);
+                stats.add_single_class_stat_max(stat_id, amount);
             }
         }
 
         for ability in self.actor.abilities.iter() {
             let level = ability.level;
-            ability.ability.add_bonuses_to(level, &mut self.stats);
+            ability.ability.add_bonuses_to(level, &mut stats);
         }
 
         let mut attacks_list = Vec::new();
-        for item_state in self.inventory.equipped_iter() {
+        for item_state in inventory.equipped_iter() {
             let equippable = match &item_state.item.equippable {
                 None => continue,
                 Some(equippable) => {
@@ -897,26 +1137,40 @@ impl ActorState {
                 }
             };
 
-            self.stats.add(&equippable.bonuses);
+            stats.add(&equippable.bonuses);
+        }
+
+        for (set, pieces) in inventory.equipped_sets() {
+            for bonus in set.active_bonuses(pieces) {
+                stats.add(&bonus.bonuses);
+            }
         }
 
         for (_, ref bonuses) in self.effects.iter() {
-            self.stats.add(bonuses);
+            stats.add(bonuses);
+        }
+
+        let rules = Module::rules();
+        let carry_capacity = rules.carry_capacity(&stats.attributes);
+        let weight = inventory.weight() + self.stash_weight;
+        let encumbrance = rules.encumbrance_level(weight, carry_capacity);
+        if let Some(index) = encumbrance {
+            stats.add(&rules.encumbrance[index].bonuses);
         }
 
         let mut equipped_armor = HashMap::new();
         for slot in Slot::iter() {
-            if let Some(ref item_state) = self.inventory.equipped(*slot) {
+            if let Some(ref item_state) = inventory.equipped(*slot) {
                 if let ItemKind::Armor { kind } = item_state.item.kind {
                     equipped_armor.insert(*slot, kind);
                 }
             }
         }
 
-        let weapon_style = self.inventory.weapon_style();
+        let weapon_style = inventory.weapon_style();
         let is_threatened = self.is_threatened();
 
-        self.stats.finalize(
+        stats.finalize(
             &self.actor,
             attacks_list,
             equipped_armor,
@@ -924,6 +1178,33 @@ impl ActorState {
             is_threatened,
         );
 
+        (stats, carry_capacity, encumbrance)
+    }
+
+    pub fn compute_stats(&mut self) {
+        debug!("Compute stats for '{}'", self.actor.name);
+
+        let mut layers_override = self.inventory().get_image_layers();
+        for (layer, image) in self.anim_image_layers.iter() {
+            layers_override.insert(*layer, Rc::clone(image));
+        }
+
+        let layers = self.actor.image_layers().get_list_with(
+            self.actor.sex,
+            &self.actor.race,
+            self.actor.hair_color,
+            self.actor.skin_color,
+            layers_override,
+        );
+        self.image = LayeredImage::new(layers, self.actor.hue);
+
+        let (stats, carry_capacity, encumbrance) = self.stats_for(&self.inventory);
+        self.stats = stats;
+        self.carry_capacity = carry_capacity;
+        self.encumbrance = encumbrance;
+
+        self.update_set_bonuses();
+
         self.p_stats.recompute_level_up(&self.actor);
 
         self.listeners.notify(&self);
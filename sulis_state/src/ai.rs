@@ -18,6 +18,7 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use crate::script::script_callback;
+use crate::morale::{self, MoraleState};
 use crate::{animation::Anim, EntityState, GameState, Script};
 use sulis_core::config::Config;
 
@@ -152,6 +153,25 @@ impl EntityAI {
 
         self.actions_taken_this_turn += 1;
 
-        Script::ai(&self.entity, "ai_action")
+        match morale::update(&self.entity) {
+            MoraleState::Steady => Script::ai(&self.entity, "ai_action"),
+            MoraleState::Fleeing => self.run_away(),
+            MoraleState::Surrendered => State::End,
+        }
+    }
+
+    fn run_away(&mut self) -> State {
+        if morale::has_escaped(&self.entity.borrow()) {
+            info!("'{}' has fled the area", self.entity.borrow().actor.actor.name);
+            self.entity.borrow_mut().marked_for_removal = true;
+            return State::End;
+        }
+
+        // only attempt a single flee move per turn
+        if self.actions_taken_this_turn > 1 || !morale::flee(&self.entity) {
+            return State::End;
+        }
+
+        State::Run
     }
 }
@@ -0,0 +1,77 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use std::collections::HashMap;
+
+use sulis_module::{Module, OnTrigger};
+
+/// Tracks each companion's approval of the party, along with the
+/// approval thresholds that have already been reached.  Each threshold
+/// only fires once
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ApprovalState {
+    values: HashMap<String, i32>,
+
+    #[serde(default)]
+    reached: HashMap<String, Vec<usize>>,
+}
+
+impl ApprovalState {
+    /// Returns the approval of the specified companion, which is the
+    /// companion's initial approval if it has not changed
+    pub fn get(&self, id: &str) -> i32 {
+        if let Some(value) = self.values.get(id) {
+            return *value;
+        }
+
+        match Module::campaign().companions.get(id) {
+            None => {
+                warn!("Invalid companion '{}' when getting approval", id);
+                0
+            }
+            Some(companion) => companion.initial_approval,
+        }
+    }
+
+    /// Sets the approval of the specified companion, returning the
+    /// triggers for any thresholds newly reached by the new value
+    pub fn set(&mut self, id: &str, value: i32) -> Vec<OnTrigger> {
+        let campaign = Module::campaign();
+        let companion = match campaign.companions.get(id) {
+            None => {
+                warn!("Invalid companion '{}' when setting approval", id);
+                return Vec::new();
+            }
+            Some(companion) => companion,
+        };
+
+        self.values.insert(id.to_string(), value);
+
+        let reached = self.reached.entry(id.to_string()).or_default();
+        let mut triggers = Vec::new();
+        for (index, threshold) in companion.thresholds.iter().enumerate() {
+            if reached.contains(&index) || !threshold.is_reached(value) {
+                continue;
+            }
+
+            reached.push(index);
+            triggers.extend(threshold.on_reached.iter().cloned());
+        }
+
+        triggers
+    }
+}
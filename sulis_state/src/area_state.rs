@@ -30,7 +30,7 @@ use sulis_core::io::Audio;
 use sulis_core::config::Config;
 use sulis_core::util::{self, gen_rand, invalid_data_error, Point, Size};
 use sulis_module::area::{Transition, TriggerKind, Trigger};
-use sulis_module::{Actor, Area, LootList, Module, ObjectSize, Time};
+use sulis_module::{Actor, Area, Cover, LootList, Module, ObjectSize, Time};
 
 pub struct TriggerState {
     pub(crate) fired: bool,
@@ -284,6 +284,12 @@ impl AreaState {
 
         self.add_transitions_from_area();
 
+        self.spawn_auto_encounters();
+    }
+
+    /// Spawns all encounters in this area that are set to spawn
+    /// automatically
+    pub fn spawn_auto_encounters(&mut self) {
         let mut auto_spawn = Vec::with_capacity(self.area.encounters.len());
         for encounter in &self.area.encounters {
             auto_spawn.push(encounter.encounter.auto_spawn);
@@ -302,6 +308,7 @@ impl AreaState {
         loot_list: &Rc<LootList>,
         buy_frac: f32,
         sell_frac: f32,
+        repair_frac: Option<f32>,
         refresh_time: Time,
     ) -> &mut MerchantState {
         let mut index = None;
@@ -320,7 +327,14 @@ impl AreaState {
             None => {
                 info!("Creating merchant '{}'", id);
                 let len = self.merchants.len();
-                let merchant = MerchantState::new(id, loot_list, buy_frac, sell_frac, refresh_time);
+                let merchant = MerchantState::new(
+                    id,
+                    loot_list,
+                    buy_frac,
+                    sell_frac,
+                    repair_frac,
+                    refresh_time,
+                );
                 self.merchants.push(merchant);
                 &mut self.merchants[len]
             }
@@ -432,9 +446,15 @@ impl AreaState {
             if !self.triggers[*trigger_index].can_fire(&trigger) {
                 continue;
             }
+
+            let is_activated = matches!(trigger.kind, TriggerKind::OnEncounterActivated { .. });
+
+            if is_activated && !condition::trigger_condition_met(trigger, &player, target) {
+                continue;
+            }
             self.triggers[*trigger_index].fired = true;
 
-            if let TriggerKind::OnEncounterActivated { .. } = trigger.kind {
+            if is_activated {
                 info!("    Calling OnEncounterActivated");
                 GameState::add_ui_callback(trigger.on_activate.clone(), &player, target);
             }
@@ -450,6 +470,10 @@ impl AreaState {
             self.triggers[*trigger_index].fired = true;
 
             if let TriggerKind::OnEncounterCleared { .. } = trigger.kind {
+                if !condition::trigger_condition_met(trigger, &player, target) {
+                    continue;
+                }
+
                 info!("    Calling OnEncounterCleared");
                 GameState::add_ui_callback(trigger.on_activate.clone(), &player, target);
             }
@@ -666,6 +690,18 @@ impl AreaState {
         has_visibility(&self.area, &self.props.entire_vis_grid(), parent, target)
     }
 
+    pub fn cover(&self, parent: &EntityState, target: &EntityState) -> Cover {
+        let fraction = Module::rules().full_cover_fraction;
+        cover(&self.area, &self.props.entire_vis_grid(), parent, target, fraction)
+    }
+
+    /// Returns the elevation at the center of the specified entity
+    pub fn elevation_at(&self, entity: &EntityState) -> u8 {
+        let x = entity.location.x + entity.size.width / 2;
+        let y = entity.location.y + entity.size.height / 2;
+        self.area.layer_set.elevation(x, y)
+    }
+
     pub fn compute_pc_visibility(
         &mut self,
         entity: &Rc<RefCell<EntityState>>,
@@ -743,6 +779,11 @@ impl AreaState {
             return;
         }
 
+        let trigger = &self.area.area.triggers[index];
+        if !condition::trigger_condition_met(trigger, entity, entity) {
+            return;
+        }
+
         self.triggers[index].fired = true;
         GameState::add_ui_callback(
             self.area.area.triggers[index].on_activate.clone(),
@@ -1066,6 +1107,11 @@ impl AreaState {
             self.update_view_visibility();
 
             self.check_trigger_grid(&entity);
+
+            GameState::add_quest_event(QuestEvent::Reached {
+                area: self.area.area.id.to_string(),
+                location: entity.borrow().location.to_point(),
+            });
         }
 
         mgr.fire_on_moved_next_update(entity_index);
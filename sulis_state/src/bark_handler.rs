@@ -0,0 +1,379 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use sulis_core::util::{gen_rand, Point};
+use sulis_module::bark::{Banter, BarkEvent};
+use sulis_module::{Campaign, Module};
+
+use crate::area_feedback_text::ColorKind;
+use crate::{condition, AreaFeedbackText, EntityState, GameState};
+
+thread_local! {
+    static BARKS: RefCell<BarkState> = RefCell::new(BarkState::default());
+}
+
+struct ActiveBanter {
+    id: String,
+    next_line: usize,
+    remaining_delay: u32,
+}
+
+#[derive(Default)]
+struct BarkState {
+    elapsed: u64,
+    events: Vec<(BarkEvent, Option<Rc<RefCell<EntityState>>>)>,
+
+    // time of the last bark, keyed by entity index
+    last_bark: HashMap<usize, u64>,
+
+    idle_time: u32,
+    player_pos: Point,
+
+    banter_time: u32,
+    active_banter: Option<ActiveBanter>,
+    played_banter: HashSet<String>,
+}
+
+/// Clears all bark state, keeping only the set of banter that has already
+/// been played
+pub(crate) fn reset(played_banter: HashSet<String>) {
+    BARKS.with(|barks| {
+        *barks.borrow_mut() = BarkState {
+            played_banter,
+            ..Default::default()
+        }
+    });
+}
+
+pub(crate) fn played_banter() -> Vec<String> {
+    BARKS.with(|barks| barks.borrow().played_banter.iter().cloned().collect())
+}
+
+/// Queues the event to be handled on the next update.  If no `speaker` is
+/// specified, one is chosen from the entities relevant to the event
+pub(crate) fn add_event(event: BarkEvent, speaker: Option<&Rc<RefCell<EntityState>>>) {
+    let speaker = speaker.map(Rc::clone);
+    BARKS.with(|barks| barks.borrow_mut().events.push((event, speaker)));
+}
+
+pub(crate) fn update(millis: u32) {
+    let events: Vec<_> = BARKS.with(|barks| {
+        let mut barks = barks.borrow_mut();
+        barks.elapsed += millis as u64;
+        barks.events.drain(..).collect()
+    });
+
+    let campaign = Module::campaign();
+    for (event, speaker) in events {
+        let candidates = match speaker {
+            Some(speaker) => vec![speaker],
+            None => candidates(event),
+        };
+        bark(&campaign, event, &candidates);
+    }
+
+    if GameState::is_combat_active() {
+        BARKS.with(|barks| {
+            let mut barks = barks.borrow_mut();
+            barks.idle_time = 0;
+            barks.banter_time = 0;
+            barks.active_banter = None;
+        });
+        return;
+    }
+
+    update_banter(&campaign, millis);
+    update_idle(&campaign, millis);
+}
+
+fn candidates(event: BarkEvent) -> Vec<Rc<RefCell<EntityState>>> {
+    use BarkEvent::*;
+    match event {
+        EnterArea | Idle => GameState::party(),
+        CombatStart => {
+            let mut candidates = GameState::party();
+            let player = GameState::player();
+            let area = GameState::area_state();
+            let area = area.borrow();
+            let mgr = GameState::turn_manager();
+            let mgr = mgr.borrow();
+            for index in area.entity_iter() {
+                let entity = mgr.entity(*index);
+                {
+                    let entity = entity.borrow();
+                    if entity.is_party_member() || !entity.is_hostile(&player.borrow()) {
+                        continue;
+                    }
+
+                    if !area.is_pc_visible(entity.location.x, entity.location.y) {
+                        continue;
+                    }
+                }
+                candidates.push(entity);
+            }
+            candidates
+        }
+        LowHp | KillingBlow => Vec::new(),
+    }
+}
+
+/// Barks a line for the first of the `candidates`, starting from a random
+/// one, with a line available for the event
+fn bark(campaign: &Campaign, event: BarkEvent, candidates: &[Rc<RefCell<EntityState>>]) {
+    if candidates.is_empty() {
+        return;
+    }
+
+    let start = gen_rand(0, candidates.len());
+    for i in 0..candidates.len() {
+        let speaker = &candidates[(start + i) % candidates.len()];
+        if try_bark(campaign, event, speaker) {
+            return;
+        }
+    }
+}
+
+fn try_bark(campaign: &Campaign, event: BarkEvent, speaker: &Rc<RefCell<EntityState>>) -> bool {
+    let (index, id) = {
+        let speaker = speaker.borrow();
+        if speaker.actor.is_dead() {
+            return false;
+        }
+        (speaker.index(), speaker.actor.actor.id.to_string())
+    };
+
+    let bark_set = match campaign.barks.actors.get(&id) {
+        None => return false,
+        Some(bark_set) => bark_set,
+    };
+
+    let lines = match bark_set.lines.get(&event) {
+        None => return false,
+        Some(lines) => lines,
+    };
+
+    let on_cooldown = BARKS.with(|barks| {
+        let barks = barks.borrow();
+        match barks.last_bark.get(&index) {
+            None => false,
+            Some(time) => barks.elapsed < time + bark_set.cooldown as u64,
+        }
+    });
+
+    if on_cooldown || gen_rand(0, 100) >= bark_set.chance {
+        return false;
+    }
+
+    let player = GameState::player();
+    let lines: Vec<_> = lines
+        .iter()
+        .filter(|line| match &line.condition {
+            None => true,
+            Some(condition) => condition::is_met(condition, &player, speaker),
+        })
+        .map(|line| (line, line.weight))
+        .collect();
+
+    match choose_weighted(lines) {
+        None => false,
+        Some(line) => {
+            say_line(speaker, &line.text);
+            true
+        }
+    }
+}
+
+fn update_idle(campaign: &Campaign, millis: u32) {
+    let player_pos = GameState::player().borrow().location.to_point();
+    let fire = BARKS.with(|barks| {
+        let mut barks = barks.borrow_mut();
+        if barks.player_pos != player_pos || barks.active_banter.is_some() {
+            barks.player_pos = player_pos;
+            barks.idle_time = 0;
+            return false;
+        }
+
+        barks.idle_time += millis;
+        if barks.idle_time < campaign.barks.idle_time {
+            return false;
+        }
+
+        barks.idle_time = 0;
+        true
+    });
+
+    if fire {
+        bark(campaign, BarkEvent::Idle, &GameState::party());
+    }
+}
+
+fn update_banter(campaign: &Campaign, millis: u32) {
+    let (next_line, try_start) = BARKS.with(|barks| {
+        let mut barks = barks.borrow_mut();
+        match barks.active_banter.as_mut() {
+            Some(active) => {
+                active.remaining_delay = active.remaining_delay.saturating_sub(millis);
+                if active.remaining_delay == 0 {
+                    (Some((active.id.to_string(), active.next_line)), false)
+                } else {
+                    (None, false)
+                }
+            }
+            None => {
+                barks.banter_time += millis;
+                if barks.banter_time < campaign.barks.banter_interval {
+                    return (None, false);
+                }
+
+                barks.banter_time = 0;
+                (None, gen_rand(0, 100) < campaign.barks.banter_chance)
+            }
+        }
+    });
+
+    let (id, index) = match next_line {
+        Some(next_line) => next_line,
+        None if try_start => match choose_banter(campaign) {
+            None => return,
+            Some(id) => (id, 0),
+        },
+        None => return,
+    };
+
+    let banter = &campaign.barks.banter[&id];
+    let line = match banter.lines.get(index) {
+        None => {
+            complete_banter(banter);
+            return;
+        }
+        Some(line) => line,
+    };
+
+    let speaker = match party_member(&line.speaker) {
+        None => {
+            info!(
+                "Ending banter '{}' as '{}' is not available",
+                id, line.speaker
+            );
+            BARKS.with(|barks| barks.borrow_mut().active_banter = None);
+            return;
+        }
+        Some(speaker) => speaker,
+    };
+
+    say_line(&speaker, &line.text);
+    BARKS.with(|barks| {
+        let mut barks = barks.borrow_mut();
+        barks.played_banter.insert(id.to_string());
+        barks.active_banter = Some(ActiveBanter {
+            id,
+            next_line: index + 1,
+            remaining_delay: line.delay,
+        });
+    });
+}
+
+/// Randomly chooses banter that has not yet been played, or is
+/// repeatable, and whose speakers are all in the party
+fn choose_banter(campaign: &Campaign) -> Option<String> {
+    let played = BARKS.with(|barks| barks.borrow().played_banter.clone());
+    let player = GameState::player();
+    let available: Vec<_> = campaign
+        .barks
+        .banter
+        .iter()
+        .filter(|(id, banter)| banter.repeatable || !played.contains(*id))
+        .filter(|(_, banter)| is_available(banter, &player))
+        .map(|(id, banter)| (id, banter.weight))
+        .collect();
+
+    let id = choose_weighted(available)?;
+    info!("Starting banter '{}'", id);
+    Some(id.to_string())
+}
+
+fn is_available(banter: &Banter, player: &Rc<RefCell<EntityState>>) -> bool {
+    let mut speakers = Vec::new();
+    for id in banter.speakers() {
+        match party_member(id) {
+            None => return false,
+            Some(speaker) => speakers.push(speaker),
+        }
+    }
+
+    match &banter.condition {
+        None => true,
+        Some(condition) => condition::is_met(condition, player, &speakers[0]),
+    }
+}
+
+fn complete_banter(banter: &Banter) {
+    BARKS.with(|barks| barks.borrow_mut().active_banter = None);
+
+    if banter.on_complete.is_empty() {
+        return;
+    }
+
+    let player = GameState::player();
+    let target = party_member(&banter.lines[0].speaker).unwrap_or_else(|| Rc::clone(&player));
+    GameState::add_ui_callback(banter.on_complete.clone(), &player, &target);
+}
+
+/// Returns the living party member with the specified actor ID
+fn party_member(id: &str) -> Option<Rc<RefCell<EntityState>>> {
+    GameState::party().into_iter().find(|entity| {
+        let entity = entity.borrow();
+        entity.actor.actor.id == id && !entity.actor.is_dead()
+    })
+}
+
+fn say_line(speaker: &Rc<RefCell<EntityState>>, text: &str) {
+    let area = GameState::area_state();
+    if !speaker.borrow().location.is_in(&area.borrow()) {
+        return;
+    }
+
+    let mut feedback = AreaFeedbackText::with_target(&speaker.borrow(), &area.borrow());
+    feedback.add_entry(text.to_string(), ColorKind::Info);
+    area.borrow_mut().add_feedback_text(feedback);
+
+    BARKS.with(|barks| {
+        let mut barks = barks.borrow_mut();
+        let elapsed = barks.elapsed;
+        barks.last_bark.insert(speaker.borrow().index(), elapsed);
+    });
+}
+
+fn choose_weighted<T>(entries: Vec<(T, u32)>) -> Option<T> {
+    let total: u32 = entries.iter().map(|(_, weight)| weight).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut roll = gen_rand(0, total);
+    for (entry, weight) in entries {
+        if roll < weight {
+            return Some(entry);
+        }
+        roll -= weight;
+    }
+
+    None
+}
@@ -0,0 +1,132 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::{EntityState, GameState};
+use sulis_module::area::Trigger;
+use sulis_module::condition::ConditionTarget;
+use sulis_module::{Condition, Module};
+
+/// Evaluates the condition against the current game state.  `pc` and
+/// `target` are the entities referred to by `ConditionTarget`
+pub fn is_met(
+    condition: &Condition,
+    pc: &Rc<RefCell<EntityState>>,
+    target: &Rc<RefCell<EntityState>>,
+) -> bool {
+    use sulis_module::Condition::*;
+
+    let entity = |kind: &ConditionTarget| match kind {
+        ConditionTarget::Player => Rc::clone(pc),
+        ConditionTarget::Target => Rc::clone(target),
+    };
+
+    match condition {
+        All(conditions) => conditions.iter().all(|c| is_met(c, pc, target)),
+        Any(conditions) => conditions.iter().any(|c| is_met(c, pc, target)),
+        Not(condition) => !is_met(condition, pc, target),
+        Flag { target, flag } => entity(target).borrow().has_custom_flag(flag),
+        NumFlag {
+            target,
+            flag,
+            op,
+            value,
+        } => op.compare(entity(target).borrow().get_num_flag(flag), *value),
+        Attribute {
+            target,
+            attribute,
+            op,
+            value,
+        } => {
+            let cur = entity(target)
+                .borrow()
+                .actor
+                .stats
+                .attributes
+                .get(*attribute);
+            op.compare(cur as i32, *value)
+        }
+        Level {
+            target,
+            class,
+            op,
+            value,
+        } => {
+            let entity = entity(target);
+            let actor = &entity.borrow().actor.actor;
+            let level = match class {
+                None => actor.total_level,
+                Some(id) => match Module::class(id) {
+                    None => {
+                        warn!("Invalid class '{}' in condition", id);
+                        return false;
+                    }
+                    Some(class) => actor.levels(&class),
+                },
+            };
+            op.compare(level, *value)
+        }
+        Race { target, race } => entity(target).borrow().actor.actor.race.id == *race,
+        Ability { target, ability } => entity(target)
+            .borrow()
+            .actor
+            .actor
+            .has_ability_with_id(ability),
+        PartyMember(id) => GameState::has_party_member(id),
+        PartySize { op, value } => op.compare(GameState::party().len() as u32, *value),
+        ItemCount { item, op, value } => {
+            let stash = GameState::party_stash();
+            let quantity = stash.borrow().quantity_of(item);
+            op.compare(quantity, *value)
+        }
+        Coins { op, value } => op.compare(GameState::party_coins(), *value),
+        QuestState {
+            quest,
+            entry,
+            state,
+        } => {
+            let cur = match entry {
+                None => GameState::get_quest_state(quest.to_string()),
+                Some(entry) => {
+                    GameState::get_quest_entry_state(quest.to_string(), entry.to_string())
+                }
+            };
+            cur == *state
+        }
+        Reputation { faction, op, value } => {
+            op.compare(GameState::faction_reputation(faction), *value)
+        }
+        Approval {
+            companion,
+            op,
+            value,
+        } => op.compare(GameState::approval(companion), *value),
+    }
+}
+
+/// Returns true if the trigger has no condition or its condition is met
+pub fn trigger_condition_met(
+    trigger: &Trigger,
+    pc: &Rc<RefCell<EntityState>>,
+    target: &Rc<RefCell<EntityState>>,
+) -> bool {
+    match &trigger.condition {
+        None => true,
+        Some(condition) => is_met(condition, pc, target),
+    }
+}
@@ -0,0 +1,100 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use std::collections::{vec_deque, VecDeque};
+
+use sulis_module::Time;
+
+/// The maximum number of conversations kept in the log.  Once this is
+/// exceeded, the oldest conversations are discarded
+const MAX_CONVERSATIONS: usize = 200;
+
+/// A single line of a conversation, either a node spoken by an NPC or a
+/// response chosen by the player
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ConversationLine {
+    pub speaker: String,
+    pub text: String,
+}
+
+/// The transcript of a single conversation
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ConversationRecord {
+    pub convo: String,
+
+    /// The name of the entity the conversation was started with
+    pub target: String,
+
+    /// The game time when the conversation was started
+    pub time: Time,
+    pub lines: Vec<ConversationLine>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ConversationLog {
+    records: VecDeque<ConversationRecord>,
+}
+
+impl ConversationLog {
+    pub fn start(&mut self, convo: &str, target: &str, time: Time) {
+        self.records.push_back(ConversationRecord {
+            convo: convo.to_string(),
+            target: target.to_string(),
+            time,
+            lines: Vec::new(),
+        });
+
+        while self.records.len() > MAX_CONVERSATIONS {
+            self.records.pop_front();
+        }
+    }
+
+    /// Adds a line to the most recently started conversation
+    pub fn add_line(&mut self, speaker: &str, text: &str) {
+        let record = match self.records.back_mut() {
+            None => {
+                warn!("Attempted to log conversation line with no conversation");
+                return;
+            }
+            Some(record) => record,
+        };
+
+        record.lines.push(ConversationLine {
+            speaker: speaker.to_string(),
+            text: text.to_string(),
+        });
+    }
+
+    /// Iterates over the recorded conversations, oldest first
+    pub fn iter(&self) -> vec_deque::Iter<'_, ConversationRecord> {
+        self.records.iter()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&ConversationRecord> {
+        self.records.get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
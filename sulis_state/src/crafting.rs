@@ -0,0 +1,175 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::{is_within, EntityState, GameState};
+use sulis_module::{Recipe, RecipeOutput};
+
+/// Returns true if the party stash contains all of the ingredients
+/// for the recipe
+pub fn has_ingredients(recipe: &Recipe) -> bool {
+    let stash = GameState::party_stash();
+    let stash = stash.borrow();
+    recipe
+        .ingredients
+        .iter()
+        .all(|ingredient| stash.quantity_of(&ingredient.item.id) >= ingredient.quantity)
+}
+
+/// Returns true if the party stash contains all of the tools for the recipe
+pub fn has_tools(recipe: &Recipe) -> bool {
+    let stash = GameState::party_stash();
+    let stash = stash.borrow();
+    recipe.tools.iter().all(|tool| stash.has_item(&tool.id))
+}
+
+/// Returns true if the recipe does not require a nearby prop, or if the
+/// crafter is within the recipe's distance of one of the required props
+pub fn is_near_props(recipe: &Recipe, crafter: &Rc<RefCell<EntityState>>) -> bool {
+    if recipe.near_props.is_empty() {
+        return true;
+    }
+
+    let area = GameState::area_state();
+    let area = area.borrow();
+    let crafter = crafter.borrow();
+    area.props().iter().any(|prop| {
+        recipe.near_props.contains(&prop.prop.id)
+            && is_within(&*crafter, prop, recipe.prop_distance)
+    })
+}
+
+/// Returns true if the crafter meets the prereqs and has the class stats
+/// needed for the recipe
+pub fn meets_requirements(recipe: &Recipe, crafter: &Rc<RefCell<EntityState>>) -> bool {
+    let crafter = crafter.borrow();
+    if let Some(prereqs) = &recipe.prereqs {
+        if !prereqs.meets(&crafter.actor.actor) {
+            return false;
+        }
+    }
+
+    recipe
+        .class_stats
+        .iter()
+        .all(|(stat, amount)| !crafter.actor.current_class_stat(stat).less_than(*amount))
+}
+
+pub fn can_craft(recipe: &Recipe, crafter: &Rc<RefCell<EntityState>>) -> bool {
+    has_ingredients(recipe)
+        && has_tools(recipe)
+        && is_near_props(recipe, crafter)
+        && meets_requirements(recipe, crafter)
+}
+
+/// Returns the indices of all items in the party stash that may be chosen
+/// as the target of the specified recipe
+pub fn valid_targets(recipe: &Recipe) -> Vec<usize> {
+    let stash = GameState::party_stash();
+    let stash = stash.borrow();
+    stash
+        .items()
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, item))| recipe.can_target(item))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Crafts the recipe using the specified crafter.  Ingredients are removed
+/// from the party stash.  For recipes that add an adjective to an existing
+/// item, `target` must be the stash index of a valid target.  Returns true
+/// if the recipe was crafted successfully.
+pub fn craft(recipe: &Recipe, crafter: &Rc<RefCell<EntityState>>, target: Option<usize>) -> bool {
+    if !can_craft(recipe, crafter) {
+        warn!(
+            "Unable to craft '{}' with '{}': requirements not met",
+            recipe.id,
+            crafter.borrow().actor.actor.name
+        );
+        return false;
+    }
+
+    let stash = GameState::party_stash();
+
+    let new_item = match &recipe.output {
+        RecipeOutput::Item { .. } => {
+            let output = recipe.create_output();
+            if output.is_none() {
+                warn!("Unable to create output item for recipe '{}'", recipe.id);
+                return false;
+            }
+            output
+        }
+        RecipeOutput::Adjective { .. } => {
+            let index = match target {
+                None => {
+                    warn!("No target item specified for recipe '{}'", recipe.id);
+                    return false;
+                }
+                Some(index) => index,
+            };
+
+            let item_state = match stash.borrow().items().get(index) {
+                None => {
+                    warn!("Invalid target index {} for recipe '{}'", index, recipe.id);
+                    return false;
+                }
+                Some((_, item_state)) => item_state.clone(),
+            };
+
+            if !recipe.can_target(&item_state) {
+                warn!(
+                    "Item '{}' is not a valid target for recipe '{}'",
+                    item_state.item.id, recipe.id
+                );
+                return false;
+            }
+
+            let new_item = match recipe.apply_to(&item_state) {
+                None => {
+                    warn!(
+                        "Unable to apply recipe '{}' to '{}'",
+                        recipe.id, item_state.item.id
+                    );
+                    return false;
+                }
+                Some(new_item) => new_item,
+            };
+
+            let _ = stash.borrow_mut().remove_item(index);
+            Some((1, new_item))
+        }
+    };
+
+    for ingredient in recipe.ingredients.iter() {
+        stash
+            .borrow_mut()
+            .remove_quantity_of(&ingredient.item.id, ingredient.quantity);
+    }
+
+    for (stat, amount) in recipe.class_stats.iter() {
+        crafter.borrow_mut().actor.remove_class_stat(stat, *amount);
+    }
+
+    if let Some((quantity, item_state)) = new_item {
+        stash.borrow_mut().add_item(quantity, item_state);
+    }
+
+    true
+}
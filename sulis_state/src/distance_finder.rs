@@ -143,6 +143,10 @@ pub fn can_attack(attacker: &EntityState, defender: &EntityState) -> bool {
         return false;
     }
 
+    if !a.actor.has_ammo_for_attack() {
+        return false;
+    }
+
     if !a.is_hostile(d) || d.actor.stats.hidden {
         return false;
     }
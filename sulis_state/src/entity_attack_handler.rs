@@ -18,9 +18,69 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use sulis_core::io::Audio;
+use sulis_core::util::gen_rand;
+use crate::script::{
+    add_another_to_quickbar, Script, ScriptAttackRoll, ScriptEntity, ScriptHitKind,
+};
 use crate::{center, is_threat, ActorState, EntityState, GameState};
-use sulis_module::{AccuracyKind, Attack, AttackKind, DamageKind, HitFlags, HitKind, Module,
-    OnTrigger};
+use sulis_module::{AccuracyKind, Attack, AttackKind, Cover, DamageKind, HitFlags, HitKind, Module,
+    OnTrigger, QuickSlot, Slot};
+
+/// The terrain based modifiers applying to a ranged attack from a parent
+/// against a target.  The target may be in cover from walls and props,
+/// and either entity may have the high ground.
+#[derive(Debug, Clone, Copy)]
+pub struct RangedModifiers {
+    pub cover: Cover,
+    pub high_ground: bool,
+    pub low_ground: bool,
+}
+
+impl RangedModifiers {
+    pub fn compute(parent: &EntityState, target: &EntityState) -> RangedModifiers {
+        let area = match GameState::get_area_state(&parent.location.area_id) {
+            None => return RangedModifiers::default(),
+            Some(area) => area,
+        };
+        let area = area.borrow();
+
+        let parent_elev = area.elevation_at(parent);
+        let target_elev = area.elevation_at(target);
+
+        RangedModifiers {
+            cover: area.cover(parent, target),
+            high_ground: parent_elev > target_elev,
+            low_ground: parent_elev < target_elev,
+        }
+    }
+
+    /// The accuracy modifier the parent receives from elevation
+    pub fn accuracy(&self) -> i32 {
+        let rules = Module::rules();
+        if self.high_ground {
+            rules.high_ground_accuracy_bonus
+        } else if self.low_ground {
+            -rules.low_ground_accuracy_penalty
+        } else {
+            0
+        }
+    }
+
+    /// The defense modifier the target receives from cover
+    pub fn defense(&self) -> i32 {
+        Module::rules().cover_defense_bonus(self.cover)
+    }
+}
+
+impl Default for RangedModifiers {
+    fn default() -> Self {
+        RangedModifiers {
+            cover: Cover::None,
+            high_ground: false,
+            low_ground: false,
+        }
+    }
+}
 
 fn is_sneak_attack(parent: &EntityState, target: &EntityState) -> bool {
     parent.actor.stats.hidden && !target.actor.stats.sneak_attack_immunity
@@ -76,6 +136,58 @@ fn is_flanking(parent: &EntityState, target: &EntityState) -> bool {
     false
 }
 
+// the slots of the weapons making each of the parent's attacks, in the
+// same order as the attacks are added in compute_stats
+fn weapon_slots(parent: &EntityState) -> Vec<Slot> {
+    let inventory = parent.actor.inventory();
+    Slot::iter()
+        .filter(|slot| match inventory.equipped(**slot) {
+            None => false,
+            Some(item_state) => match &item_state.item.equippable {
+                None => false,
+                Some(equippable) => equippable.attack.is_some(),
+            },
+        })
+        .cloned()
+        .collect()
+}
+
+/// Uses up one of the parent's loaded ammunition, reloading the ammo slot
+/// from the party stash if possible.  Only party members use up ammunition.
+fn consume_ammo(parent: &Rc<RefCell<EntityState>>) {
+    if !parent.borrow().is_party_member() {
+        return;
+    }
+
+    let ammo = parent.borrow_mut().actor.clear_quick(QuickSlot::Ammo);
+    add_another_to_quickbar(parent, ammo, QuickSlot::Ammo);
+}
+
+fn wear_equipment(
+    parent: &Rc<RefCell<EntityState>>,
+    target: &Rc<RefCell<EntityState>>,
+    weapon_slot: Option<Slot>,
+) {
+    let rules = Module::rules();
+    if let Some(slot) = weapon_slot {
+        parent
+            .borrow_mut()
+            .actor
+            .damage_item(slot, rules.weapon_durability_loss);
+    }
+
+    let armor_slots = target.borrow().actor.inventory().durable_armor_slots();
+    if armor_slots.is_empty() {
+        return;
+    }
+
+    let slot = armor_slots[gen_rand(0, armor_slots.len())];
+    target
+        .borrow_mut()
+        .actor
+        .damage_item(slot, rules.armor_durability_loss);
+}
+
 type AttackResult = Vec<(HitKind, HitFlags, Vec<(DamageKind, u32)>)>;
 
 pub fn weapon_attack(
@@ -93,19 +205,33 @@ pub fn weapon_attack(
     );
 
     let attacks = parent.borrow().actor.stats.attacks.clone();
+    let weapon_slots = weapon_slots(&parent.borrow());
+    let ammo = parent.borrow().actor.inventory().loaded_ammo().cloned();
+    let mut used_ammo = false;
 
     let is_flanking = is_flanking(&parent.borrow(), &target.borrow());
     let is_sneak_attack = is_sneak_attack(&parent.borrow(), &target.borrow());
 
     let mut had_crit = false;
     let mut result = Vec::new();
-    for attack in attacks {
+    for (index, attack) in attacks.into_iter().enumerate() {
         let mut attack = if is_flanking {
             Attack::from(&attack, &parent.borrow().actor.stats.flanking_bonuses)
         } else {
             attack
         };
 
+        let is_main_ranged = attack.is_ranged() && weapon_slots.get(index) == Some(&Slot::HeldMain);
+        let ammo = match &ammo {
+            Some(ammo) if is_main_ranged => ammo.item.ammo.as_ref(),
+            _ => None,
+        };
+
+        if let Some(ammo) = ammo {
+            attack = Attack::with_attack_bonuses(&attack, &ammo.bonuses);
+            used_ammo = true;
+        }
+
         let (hit_kind, hit_flags, damage) =
             attack_internal(parent, target, &mut attack, is_flanking, is_sneak_attack);
 
@@ -113,6 +239,19 @@ pub fn weapon_attack(
             had_crit = true;
         }
 
+        if hit_kind != HitKind::Miss {
+            wear_equipment(parent, target, weapon_slots.get(index).cloned());
+
+            if let Some(script) = ammo.and_then(|ammo| ammo.on_hit.as_ref()) {
+                let arg = (
+                    ScriptEntity::from(parent),
+                    ScriptEntity::from(target),
+                    ScriptHitKind::new(hit_kind, damage.clone()),
+                );
+                Script::trigger(&script.id, &script.func, arg);
+            }
+        }
+
         let sound = attack.sounds.sound(hit_kind);
         if let Some(sound_id) = sound {
             Audio::play_sfx(sound_id, 1.0);
@@ -121,6 +260,10 @@ pub fn weapon_attack(
         result.push((hit_kind, hit_flags, damage));
     }
 
+    if used_ammo {
+        consume_ammo(parent);
+    }
+
     if had_crit {
         GameState::add_ui_callback(vec![OnTrigger::ScreenShake], &parent, &target);
     }
@@ -181,7 +324,7 @@ fn attack_internal(
         );
     }
 
-    let (accuracy_kind, defense) = {
+    let (accuracy_kind, mut defense) = {
         let target_stats = &target.borrow().actor.stats;
         match attack.kind {
             AttackKind::Fortitude { accuracy } => (accuracy, target_stats.fortitude),
@@ -196,6 +339,13 @@ fn attack_internal(
     };
     let crit_immunity = target.borrow().actor.stats.crit_immunity;
 
+    if attack.is_ranged() {
+        let modifiers = RangedModifiers::compute(&parent.borrow(), &target.borrow());
+        debug!("Ranged attack modifiers: {:?}", modifiers);
+        attack.bonuses.ranged_accuracy += modifiers.accuracy();
+        defense += modifiers.defense();
+    }
+
     if flanking {
         attack.bonuses.melee_accuracy += rules.flanking_accuracy_bonus;
         attack.bonuses.ranged_accuracy += rules.flanking_accuracy_bonus;
@@ -212,23 +362,36 @@ fn attack_internal(
         concealment: false,
     };
 
-    let (hit_kind, damage_multiplier) = {
-        let parent_stats = &parent.borrow().actor.stats;
-        let hit_kind =
-            parent_stats.attack_roll(accuracy_kind, crit_immunity, defense, &attack.bonuses);
-        let damage_multiplier = match hit_kind {
-            HitKind::Miss => {
-                debug!("Miss");
-                return (HitKind::Miss, hit_flags, Vec::new());
-            }
-            HitKind::Graze => parent_stats.graze_multiplier + attack.bonuses.graze_multiplier,
-            HitKind::Hit => parent_stats.hit_multiplier + attack.bonuses.hit_multiplier,
-            HitKind::Crit => parent_stats.crit_multiplier + attack.bonuses.crit_multiplier,
-            HitKind::Auto => panic!(),
-        };
-        (hit_kind, damage_multiplier)
+    let roll = ScriptAttackRoll::new(
+        &parent.borrow().actor.stats,
+        &attack.kind,
+        accuracy_kind,
+        defense,
+        crit_immunity,
+        &attack.bonuses,
+        hit_flags,
+    );
+
+    let scripted_result = match &rules.attack_resolution_script {
+        None => None,
+        Some(script) => Script::attack_resolution(parent, target, roll.clone(), script),
+    };
+
+    let (hit_kind, damage_multiplier) = match scripted_result {
+        Some(result) => result,
+        None => {
+            let parent_stats = &parent.borrow().actor.stats;
+            let hit_kind =
+                parent_stats.attack_roll(accuracy_kind, crit_immunity, defense, &attack.bonuses);
+            (hit_kind, roll.multiplier(hit_kind))
+        }
     };
 
+    if hit_kind == HitKind::Miss {
+        debug!("Miss");
+        return (HitKind::Miss, hit_flags, Vec::new());
+    }
+
     let damage = {
         let target = &target.borrow().actor.stats;
         let damage = &attack.damage;
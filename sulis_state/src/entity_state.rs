@@ -24,19 +24,21 @@ use std::usize;
 use sulis_core::config::Config;
 
 use crate::animation::{self, Anim};
+use crate::morale::MoraleState;
 use crate::save_state::EntitySaveState;
 use crate::script::{self, CallbackData, ScriptEntitySet};
 use crate::{
     entity_attack_handler::weapon_attack, entity_texture_cache::Slot, is_within_attack_dist,
-    ActorState, AreaState, ChangeListenerList, EntityTextureCache, EntityTextureSlot, GameState,
-    Location, ScriptCallback, TurnManager,
+    loadout, threat_table, ActorState, AreaState, ChangeListenerList, EntityTextureCache,
+    EntityTextureSlot, GameState, Location, ScriptCallback, ThreatTable, TurnManager,
 };
 use sulis_core::io::GraphicsRenderer;
 use sulis_core::ui::{color, Color};
 use sulis_core::util::{invalid_data_error, Offset, Scale};
 use sulis_module::area::MAX_AREA_SIZE;
 use sulis_module::{
-    actor::Faction, ai, Actor, DamageKind, HitKind, Module, ObjectSize, ObjectSizeIterator,
+    actor::Faction, ai, bark::BarkEvent, Actor, DamageKind, HitKind, Module, ObjectSize,
+    ObjectSizeIterator,
 };
 
 enum AIState {
@@ -61,6 +63,10 @@ pub struct EntityState {
 
     custom_flags: HashMap<String, String>,
 
+    morale_state: MoraleState,
+    morale_bonus: i32,
+    threat_table: ThreatTable,
+
     index: usize,      // index in vec of the owning manager
     unique_id: String, // assigned when setting the index and persisted on save
 
@@ -124,6 +130,9 @@ impl EntityState {
             marked_for_removal: false,
             texture_cache_slot: None,
             custom_flags: save.custom_flags,
+            morale_state: save.morale_state,
+            morale_bonus: save.morale_bonus,
+            threat_table: save.threat_table,
             collapsed_groups: save.collapsed_groups,
         })
     }
@@ -155,7 +164,8 @@ impl EntityState {
 
         debug!("Creating new entity state for {}", actor.id);
         let size = Rc::clone(&actor.race.size);
-        let actor_state = ActorState::new(actor);
+        let mut actor_state = ActorState::new(actor);
+        actor_state.set_unlimited_ammo(!is_pc);
         EntityState {
             actor: actor_state,
             ai_callbacks: None,
@@ -172,6 +182,9 @@ impl EntityState {
             ai_state,
             texture_cache_slot: None,
             custom_flags: HashMap::new(),
+            morale_state: MoraleState::Steady,
+            morale_bonus: 0,
+            threat_table: ThreatTable::default(),
             collapsed_groups: Vec::new(),
         }
     }
@@ -328,6 +341,7 @@ impl EntityState {
     }
 
     pub fn add_to_party(&mut self, show_portrait: bool) {
+        self.actor.set_unlimited_ammo(false);
         let dim = (MAX_AREA_SIZE * MAX_AREA_SIZE) as usize;
         self.ai_state = AIState::Player {
             vis: vec![false; dim],
@@ -336,6 +350,7 @@ impl EntityState {
     }
 
     pub fn remove_from_party(&mut self) {
+        self.actor.set_unlimited_ammo(true);
         self.ai_state = AIState::AI {
             group: None,
             active: false,
@@ -386,6 +401,31 @@ impl EntityState {
         self_faction.is_friendly(other_faction)
     }
 
+    pub fn morale_state(&self) -> MoraleState {
+        self.morale_state
+    }
+
+    pub(crate) fn set_morale_state(&mut self, state: MoraleState) {
+        self.morale_state = state;
+    }
+
+    /// Returns the bonus (or penalty) to morale applied by scripts
+    pub fn morale_bonus(&self) -> i32 {
+        self.morale_bonus
+    }
+
+    pub fn add_morale_bonus(&mut self, amount: i32) {
+        self.morale_bonus += amount;
+    }
+
+    pub fn threat_table(&self) -> &ThreatTable {
+        &self.threat_table
+    }
+
+    pub fn threat_table_mut(&mut self) -> &mut ThreatTable {
+        &mut self.threat_table
+    }
+
     pub(crate) fn is_marked_for_removal(&self) -> bool {
         self.marked_for_removal
     }
@@ -407,6 +447,48 @@ impl EntityState {
         cbs.iter().for_each(|cb| cb.on_held_changed());
     }
 
+    /// Switches this entity to the loadout at the specified index, taking
+    /// items from and returning them to the party stash.  In combat, only the
+    /// weapon slots are changed, at the same AP cost as swapping weapons.
+    /// Returns false if the loadout could not be applied
+    pub fn apply_loadout(entity: &Rc<RefCell<EntityState>>, index: usize) -> bool {
+        let loadout = match entity.borrow().actor.loadouts().get(index) {
+            None => return false,
+            Some(loadout) => loadout.clone(),
+        };
+
+        if !entity.borrow().actor.can_apply_loadout() {
+            return false;
+        }
+
+        let combat = GameState::is_combat_active();
+        if combat {
+            let slots = [sulis_module::Slot::HeldMain, sulis_module::Slot::HeldOff];
+            if loadout::apply(entity, &loadout, &slots, &[]) {
+                entity
+                    .borrow_mut()
+                    .actor
+                    .remove_ap(Module::rules().swap_weapons_ap);
+            }
+        } else {
+            let slots: Vec<_> = sulis_module::Slot::iter().copied().collect();
+            let quick_slots: Vec<_> = sulis_module::QuickSlot::iter().copied().collect();
+            loadout::apply(entity, &loadout, &slots, &quick_slots);
+        }
+
+        if entity.borrow().is_party_member() && combat {
+            let area = GameState::area_state();
+            let mut area = area.borrow_mut();
+            area.range_indicators().remove_attack();
+            area.range_indicators().add_attack(entity);
+        }
+
+        let mgr = GameState::turn_manager();
+        let cbs = entity.borrow().callbacks(&mgr.borrow());
+        cbs.iter().for_each(|cb| cb.on_held_changed());
+        true
+    }
+
     /// Returns true if this entity has enough AP to move at least 1 square,
     /// false otherwise
     pub fn can_move(&self) -> bool {
@@ -470,7 +552,9 @@ impl EntityState {
         damage: Vec<(DamageKind, u32)>,
     ) {
         let hp_amount = damage.iter().map(|(_, amount)| amount).sum();
+        let was_low_hp = entity.borrow().actor.is_low_hp();
         entity.borrow_mut().actor.remove_hp(hp_amount);
+        threat_table::add(entity, attacker, hp_amount as f32);
 
         let targets = ScriptEntitySet::from_pair(entity, attacker);
 
@@ -490,6 +574,10 @@ impl EntityState {
             GameState::add_animation(anim);
         } else {
             GameState::create_damage_animation(&entity);
+
+            if !was_low_hp && entity.borrow().actor.is_low_hp() {
+                GameState::add_bark_event(BarkEvent::LowHp, Some(entity));
+            }
         }
     }
 
@@ -15,7 +15,7 @@
 //  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
 
 use std::cell::{Cell, RefCell};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::{Error, ErrorKind};
 use std::rc::Rc;
 
@@ -25,14 +25,21 @@ use sulis_core::util::{invalid_data_error, ExtInt, Offset, Point, Scale};
 use sulis_module::on_trigger::QuestEntryState;
 use sulis_module::{
     area::{Destination, PathFinder, Trigger, TriggerKind},
-    Actor, ItemState, Module, OnTrigger, Time, MOVE_TO_THRESHOLD,
+    bark::BarkEvent,
+    campaign::WorldMapLocation,
+    quest::QuestRewards,
+    Actor, Faction, ItemState, Module, OnTrigger, Time, MOVE_TO_THRESHOLD,
 };
 
 use crate::animation::{particle_generator::Param, Anim, AnimSaveState, AnimState};
-use crate::script::{script_cache, script_callback, Script, ScriptCallback, ScriptEntity};
+use crate::quest_state::{self, QuestEvent};
+use crate::script::{
+    entity_with_id, script_cache, script_callback, Script, ScriptCallback, ScriptEntity,
+};
 use crate::{
-    path_finder, transition_handler, AreaState, ChangeListener, ChangeListenerList, Effect,
-    EntityState, Formation, ItemList, Location, PartyStash, QuestStateSet, SaveState, TurnManager,
+    bark_handler, condition, path_finder, transition_handler, travel_handler, ApprovalState,
+    AreaState, ChangeListener, ChangeListenerList, ConversationLog, Effect, EntityState, Formation,
+    ItemList, ItemSortMode, Location, PartyStash, QuestStateSet, SaveState, TurnManager,
     UICallback, WorldMapState, AI,
 };
 
@@ -45,6 +52,10 @@ thread_local! {
     static ANIMATIONS: RefCell<AnimState> = RefCell::new(AnimState::new());
     static ANIMS_TO_ADD: RefCell<Vec<Anim>> = RefCell::new(Vec::new());
     static COMBAT_INACTIVE_TIME: Cell<u32> = Cell::new(0);
+    static QUEST_EVENTS: RefCell<Vec<QuestEvent>> = RefCell::new(Vec::new());
+
+    // kept outside of STATE as it is needed for every faction check
+    static FACTION_REPUTATION: RefCell<HashMap<String, i32>> = RefCell::new(HashMap::new());
 }
 
 pub struct GameState {
@@ -58,6 +69,9 @@ pub struct GameState {
     party_formation: Rc<RefCell<Formation>>,
     party_coins: i32,
     party_stash: Rc<RefCell<PartyStash>>,
+    merchant_reputation: HashMap<String, i32>,
+    conversation_log: ConversationLog,
+    approval: ApprovalState,
 
     // listener returns the first selected party member
     party_listeners: ChangeListenerList<Option<Rc<RefCell<EntityState>>>>,
@@ -81,6 +95,9 @@ impl GameState {
         CLEAR_ANIMS.with(|c| c.set(false));
         MODAL_LOCKED.with(|c| c.set(false));
         ANIMS_TO_ADD.with(|anims| anims.borrow_mut().clear());
+        QUEST_EVENTS.with(|events| events.borrow_mut().clear());
+        FACTION_REPUTATION.with(|r| *r.borrow_mut() = save_state.faction_reputation.clone());
+        bark_handler::reset(save_state.played_banter.iter().cloned().collect());
         AI.with(|ai| *ai.borrow_mut() = AI::new());
         script_cache::setup().map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
 
@@ -143,6 +160,15 @@ impl GameState {
                     .load_entity(entity, location, is_dead)?;
             }
 
+            // threat tables are keyed by entity index, which has changed with the load
+            let indices: HashMap<usize, usize> = entities
+                .iter()
+                .map(|(old, entity)| (*old, entity.borrow().index()))
+                .collect();
+            for entity in entities.values() {
+                entity.borrow_mut().threat_table_mut().remap(&indices);
+            }
+
             let mut effects = HashMap::new();
 
             let mgr = GameState::turn_manager();
@@ -230,7 +256,8 @@ impl GameState {
                     Some(item) => Ok(item),
                 }?;
 
-                let item = ItemState::new(item, item_save.item.variant);
+                let item = ItemState::new(item, item_save.item.variant)
+                    .with_durability(item_save.item.durability);
 
                 stash.add_quantity(item_save.quantity, item);
             }
@@ -252,12 +279,19 @@ impl GameState {
                 user_zoom: save_state.zoom,
                 party_formation: Rc::new(RefCell::new(formation)),
                 party_coins,
-                party_stash: Rc::new(RefCell::new(PartyStash::new(stash))),
+                party_stash: Rc::new(RefCell::new(PartyStash::new(
+                    stash,
+                    save_state.stash_sort,
+                    save_state.junk,
+                ))),
                 party_listeners: ChangeListenerList::default(),
                 party_death_listeners: ChangeListenerList::default(),
                 ui_callbacks: Vec::new(),
                 world_map,
                 quests,
+                merchant_reputation: save_state.merchant_reputation,
+                conversation_log: save_state.conversation_log,
+                approval: save_state.approval,
             })
         };
 
@@ -265,6 +299,7 @@ impl GameState {
         STATE.with(|state| {
             *state.borrow_mut() = Some(game_state);
         });
+        GameState::party_stash().borrow().update_encumbrance();
 
         let pc = GameState::player();
         let area_state = GameState::area_state();
@@ -284,6 +319,9 @@ impl GameState {
         CLEAR_ANIMS.with(|c| c.set(false));
         MODAL_LOCKED.with(|c| c.set(false));
         ANIMS_TO_ADD.with(|anims| anims.borrow_mut().clear());
+        QUEST_EVENTS.with(|events| events.borrow_mut().clear());
+        FACTION_REPUTATION.with(|r| r.borrow_mut().clear());
+        bark_handler::reset(HashSet::new());
         AI.with(|ai| *ai.borrow_mut() = AI::new());
 
         TURN_MANAGER.with(|mgr| {
@@ -297,6 +335,7 @@ impl GameState {
         STATE.with(|state| {
             *state.borrow_mut() = Some(game_state);
         });
+        GameState::party_stash().borrow().update_encumbrance();
 
         let pc = GameState::player();
         let area_state = GameState::area_state();
@@ -423,12 +462,19 @@ impl GameState {
             party,
             party_formation: Rc::new(RefCell::new(Formation::default())),
             party_coins,
-            party_stash: Rc::new(RefCell::new(PartyStash::new(party_stash))),
+            party_stash: Rc::new(RefCell::new(PartyStash::new(
+                party_stash,
+                ItemSortMode::default(),
+                Vec::new(),
+            ))),
             party_listeners: ChangeListenerList::default(),
             party_death_listeners: ChangeListenerList::default(),
             ui_callbacks: Vec::new(),
             world_map: WorldMapState::new(),
             quests: QuestStateSet::default(),
+            merchant_reputation: HashMap::new(),
+            conversation_log: ConversationLog::default(),
+            approval: ApprovalState::default(),
         })
     }
 
@@ -450,6 +496,15 @@ impl GameState {
         })
     }
 
+    pub(crate) fn set_travel_destination(location: Option<String>) {
+        STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            let state = state.as_mut().unwrap();
+
+            state.world_map.travel_destination = location;
+        })
+    }
+
     pub fn world_map() -> WorldMapState {
         STATE.with(|state| {
             let state = state.borrow();
@@ -509,6 +564,88 @@ impl GameState {
         })
     }
 
+    /// Queues an event to be checked against quest objectives on the next
+    /// update
+    pub fn add_quest_event(event: QuestEvent) {
+        QUEST_EVENTS.with(|events| events.borrow_mut().push(event));
+    }
+
+    /// Queues a bark event, handled on the next update.  If no `speaker` is
+    /// specified, one is chosen from the entities relevant to the event
+    pub fn add_bark_event(event: BarkEvent, speaker: Option<&Rc<RefCell<EntityState>>>) {
+        bark_handler::add_event(event, speaker);
+    }
+
+    pub fn played_banter() -> Vec<String> {
+        bark_handler::played_banter()
+    }
+
+    fn process_quest_events() {
+        let events: Vec<QuestEvent> =
+            QUEST_EVENTS.with(|events| events.borrow_mut().drain(..).collect());
+        if events.is_empty() {
+            return;
+        }
+
+        let stash = GameState::party_stash();
+        let events: Vec<QuestEvent> = events
+            .into_iter()
+            .flat_map(|event| match event {
+                QuestEvent::EntryActivated { quest, entry } => {
+                    quest_state::held_item_events(&quest, &entry, &stash.borrow())
+                }
+                event => vec![event],
+            })
+            .collect();
+
+        let completed = STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            let state = state.as_mut().unwrap();
+
+            let mut completed = Vec::new();
+            for event in events.iter() {
+                completed.append(&mut state.quests.handle_event(event));
+            }
+            completed
+        });
+
+        for (quest, entry_id) in completed {
+            let entry = match quest.entries.get(&entry_id) {
+                None => continue,
+                Some(entry) => entry,
+            };
+            GameState::give_quest_rewards(&entry.rewards);
+        }
+    }
+
+    fn give_quest_rewards(rewards: &QuestRewards) {
+        if rewards.is_empty() {
+            return;
+        }
+
+        if rewards.xp > 0 {
+            for member in GameState::party().iter() {
+                member.borrow_mut().add_xp(rewards.xp);
+            }
+        }
+
+        if rewards.coins != 0 {
+            GameState::add_party_coins(rewards.coins);
+        }
+
+        let stash = GameState::party_stash();
+        for (id, quantity) in rewards.items.iter() {
+            match Module::create_get_item(id, &[]) {
+                None => warn!("Invalid quest reward item '{}'", id),
+                Some(item) => {
+                    stash
+                        .borrow_mut()
+                        .add_item(*quantity, ItemState::new(item, None));
+                }
+            }
+        }
+    }
+
     pub fn set_user_zoom(mut zoom: f32) {
         STATE.with(|state| {
             let mut state = state.borrow_mut();
@@ -617,6 +754,9 @@ impl GameState {
             state.party_listeners.notify(&entity);
         });
 
+        entity.borrow_mut().actor.set_stash_weight(0);
+        GameState::party_stash().borrow().update_encumbrance();
+
         let area_state = GameState::area_state();
         area_state.borrow_mut().update_view_visibility();
         area_state.borrow_mut().pc_vis_full_redraw();
@@ -735,6 +875,23 @@ impl GameState {
         }
     }
 
+    /// Fires the activate and deactivate scripts for any item set bonuses
+    /// which have changed since the last update
+    fn fire_item_set_scripts() {
+        let entities: Vec<_> = GameState::turn_manager()
+            .borrow()
+            .entity_iter()
+            .filter(|entity| entity.borrow().actor.has_pending_set_scripts())
+            .collect();
+
+        for entity in entities {
+            let scripts = entity.borrow_mut().actor.take_pending_set_scripts();
+            for script in scripts {
+                Script::trigger(&script.id, &script.func, ScriptEntity::from(&entity));
+            }
+        }
+    }
+
     pub fn has_party_member(id: &str) -> bool {
         for entity in GameState::party() {
             if entity.borrow().actor.actor.id == id {
@@ -770,6 +927,8 @@ impl GameState {
             state.party_listeners.notify(&entity);
         });
 
+        GameState::party_stash().borrow().update_encumbrance();
+
         let area_state = GameState::area_state();
         area_state.borrow_mut().update_view_visibility();
     }
@@ -809,8 +968,26 @@ impl GameState {
         })
     }
 
-    pub fn transition_to(area_id: Option<&str>, p: Option<Point>, offset: Point, time: Time) {
-        transition_handler::transition_to(area_id, p, offset, time);
+    /// Moves the party to the specified area and location, passing the
+    /// specified time.  Returns false if the transition was not possible
+    pub fn transition_to(
+        area_id: Option<&str>,
+        p: Option<Point>,
+        offset: Point,
+        time: Time,
+    ) -> bool {
+        transition_handler::transition_to(area_id, p, offset, time)
+    }
+
+    /// Travels on the world map from the location with ID `from` to the
+    /// specified location.  See `travel_handler::travel_to`
+    pub fn travel_to(from: &str, to: &WorldMapLocation) -> bool {
+        travel_handler::travel_to(from, to)
+    }
+
+    /// Completes a journey that was interrupted by a travel encounter
+    pub fn resume_travel() -> bool {
+        travel_handler::resume_travel()
     }
 
     pub(crate) fn preload_area(area_id: &str) -> Result<(), Error> {
@@ -829,6 +1006,30 @@ impl GameState {
         Ok(())
     }
 
+    /// Discards any existing state for the specified area, so that it is
+    /// created anew the next time it is loaded.  The current area cannot
+    /// be regenerated
+    pub(crate) fn regenerate_area(area_id: &str) {
+        let area_state = match GameState::get_area_state(area_id) {
+            None => return,
+            Some(area_state) => area_state,
+        };
+
+        if Rc::ptr_eq(&area_state, &GameState::area_state()) {
+            warn!("Unable to regenerate the current area '{}'", area_id);
+            return;
+        }
+
+        let mgr = GameState::turn_manager();
+        mgr.borrow_mut().clear_area(&area_state);
+
+        STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            let state = state.as_mut().unwrap();
+            state.areas.remove(area_id);
+        });
+    }
+
     #[must_use]
     pub(crate) fn set_current_area(area: &Rc<RefCell<AreaState>>) -> bool {
         STATE.with(|state| {
@@ -891,19 +1092,24 @@ impl GameState {
         parent: &Rc<RefCell<EntityState>>,
         target: &Rc<RefCell<EntityState>>,
     ) {
+        // conditions must be checked before borrowing the state
+        let callbacks: Vec<&Trigger> = callbacks
+            .iter()
+            .filter(|cb| cb.kind == kind)
+            .filter(|cb| condition::trigger_condition_met(cb, parent, target))
+            .collect();
+
         STATE.with(|s| {
             let mut state = s.borrow_mut();
             let state = state.as_mut().unwrap();
 
-            for cb in callbacks.iter() {
-                if cb.kind == kind {
-                    let ui_cb = UICallback {
-                        on_trigger: cb.on_activate.clone(),
-                        parent: Rc::clone(parent),
-                        target: Rc::clone(target),
-                    };
-                    state.ui_callbacks.push(ui_cb);
-                }
+            for cb in callbacks {
+                let ui_cb = UICallback {
+                    on_trigger: cb.on_activate.clone(),
+                    parent: Rc::clone(parent),
+                    target: Rc::clone(target),
+                };
+                state.ui_callbacks.push(ui_cb);
             }
         })
     }
@@ -1022,6 +1228,9 @@ impl GameState {
         COMBAT_INACTIVE_TIME.with(|c| c.set(inactive_time));
 
         GameState::handle_disabled_party_members();
+        GameState::fire_item_set_scripts();
+        GameState::process_quest_events();
+        bark_handler::update(millis);
 
         let campaign = Module::campaign();
         if let Some(script_data) = &campaign.on_tick_script {
@@ -1180,6 +1389,27 @@ impl GameState {
         }
     }
 
+    /// Moves the entity through each of the specified waypoints, ignoring
+    /// action points.  Returns false if no movement was possible
+    pub fn move_along_path(entity: &Rc<RefCell<EntityState>>, waypoints: &[Point]) -> bool {
+        let anim = STATE.with(|s| {
+            let mut state = s.borrow_mut();
+            let state = state.as_mut().unwrap();
+
+            let area = state.area_state.borrow();
+            path_finder::move_along_path(&mut state.path_finder, &area, entity, waypoints)
+        });
+
+        match anim {
+            None => false,
+            Some(anim) => {
+                GameState::remove_blocking_animations(entity);
+                GameState::add_animation(anim);
+                true
+            }
+        }
+    }
+
     pub fn can_move_towards_dest(
         entity: &EntityState,
         entities_to_ignore: &[usize],
@@ -1231,6 +1461,154 @@ impl GameState {
         STATE.with(|s| s.borrow_mut().as_mut().unwrap().party_coins += amount);
     }
 
+    pub fn merchant_reputations() -> HashMap<String, i32> {
+        STATE.with(|s| s.borrow().as_ref().unwrap().merchant_reputation.clone())
+    }
+
+    /// Returns the party's reputation with the specified merchant or
+    /// merchant faction.  Merchant factions matching a named campaign
+    /// faction use the reputation of that faction
+    pub fn merchant_reputation(id: &str) -> i32 {
+        if Module::campaign().factions.contains_key(id) {
+            return GameState::faction_reputation(id);
+        }
+
+        STATE.with(|s| {
+            let state = s.borrow();
+            let state = state.as_ref().unwrap();
+            *state.merchant_reputation.get(id).unwrap_or(&0)
+        })
+    }
+
+    /// Sets the party's reputation with the specified merchant or merchant
+    /// faction, clamped to the range allowed by the rules
+    pub fn set_merchant_reputation(id: &str, value: i32) {
+        if Module::campaign().factions.contains_key(id) {
+            GameState::set_faction_reputation(id, value);
+            return;
+        }
+
+        let value = Module::rules().merchant_pricing.clamp_reputation(value);
+        STATE.with(|s| {
+            let mut state = s.borrow_mut();
+            let state = state.as_mut().unwrap();
+            state.merchant_reputation.insert(id.to_string(), value);
+        });
+    }
+
+    pub fn add_merchant_reputation(id: &str, amount: i32) {
+        let cur = GameState::merchant_reputation(id);
+        GameState::set_merchant_reputation(id, cur + amount);
+    }
+
+    pub fn faction_reputations() -> HashMap<String, i32> {
+        FACTION_REPUTATION.with(|r| r.borrow().clone())
+    }
+
+    /// Returns the party's reputation with the named campaign faction,
+    /// which is the faction's initial reputation if it has not changed
+    pub fn faction_reputation(id: &str) -> i32 {
+        let cur = FACTION_REPUTATION.with(|r| r.borrow().get(id).copied());
+        match cur {
+            Some(value) => value,
+            None => match Module::campaign().factions.get(id) {
+                None => {
+                    warn!("Invalid faction '{}' when getting reputation", id);
+                    0
+                }
+                Some(faction) => faction.initial_reputation,
+            },
+        }
+    }
+
+    pub fn set_faction_reputation(id: &str, value: i32) {
+        if !Module::campaign().factions.contains_key(id) {
+            warn!("Invalid faction '{}' when setting reputation", id);
+            return;
+        }
+
+        FACTION_REPUTATION.with(|r| r.borrow_mut().insert(id.to_string(), value));
+    }
+
+    pub fn add_faction_reputation(id: &str, amount: i32) {
+        let cur = GameState::faction_reputation(id);
+        GameState::set_faction_reputation(id, cur + amount);
+    }
+
+    /// Returns the faction that a member of the named campaign faction,
+    /// with the specified base faction, currently acts as
+    pub fn effective_faction(affiliation: &str, base: Faction) -> Faction {
+        let campaign = Module::campaign();
+        let faction = match campaign.factions.get(affiliation) {
+            None => return base,
+            Some(faction) => faction,
+        };
+
+        let reputation = FACTION_REPUTATION.with(|r| r.borrow().get(affiliation).copied());
+        let reputation = reputation.unwrap_or(faction.initial_reputation);
+        faction.effective_faction(base, reputation)
+    }
+
+    pub fn approval_state() -> ApprovalState {
+        STATE.with(|s| s.borrow().as_ref().unwrap().approval.clone())
+    }
+
+    /// Returns the specified companion's approval of the party
+    pub fn approval(id: &str) -> i32 {
+        STATE.with(|s| s.borrow().as_ref().unwrap().approval.get(id))
+    }
+
+    /// Sets the specified companion's approval of the party.  Any approval
+    /// thresholds reached for the first time fire their triggers, with the
+    /// companion as the target
+    pub fn set_approval(id: &str, value: i32) {
+        let triggers = STATE.with(|s| {
+            let mut state = s.borrow_mut();
+            let state = state.as_mut().unwrap();
+            state.approval.set(id, value)
+        });
+
+        if triggers.is_empty() {
+            return;
+        }
+
+        let player = GameState::player();
+        let target = GameState::party()
+            .into_iter()
+            .find(|entity| entity.borrow().actor.actor.id == id)
+            .or_else(|| entity_with_id(id.to_string()))
+            .unwrap_or_else(|| Rc::clone(&player));
+        GameState::add_ui_callback(triggers, &player, &target);
+    }
+
+    pub fn add_approval(id: &str, amount: i32) {
+        let cur = GameState::approval(id);
+        GameState::set_approval(id, cur + amount);
+    }
+
+    pub fn conversation_log() -> ConversationLog {
+        STATE.with(|s| s.borrow().as_ref().unwrap().conversation_log.clone())
+    }
+
+    /// Starts a new conversation in the log.  Subsequent lines are added
+    /// to this conversation
+    pub fn start_conversation_log(convo: &str, target: &str) {
+        let time = GameState::turn_manager().borrow().current_time();
+        STATE.with(|s| {
+            let mut state = s.borrow_mut();
+            let state = state.as_mut().unwrap();
+            state.conversation_log.start(convo, target, time);
+        });
+    }
+
+    pub fn add_conversation_log_line(speaker: &str, text: &str) {
+        STATE.with(|s| {
+            let mut state = s.borrow_mut();
+            let state = state.as_mut().unwrap();
+            state.conversation_log.add_line(speaker, text);
+        });
+    }
+
     pub fn party_formation() -> Rc<RefCell<Formation>> {
         STATE.with(|s| {
             let state = s.borrow();
@@ -22,8 +22,8 @@ use std::slice::Iter;
 use sulis_core::image::Image;
 use sulis_core::util::invalid_data_error;
 use sulis_module::{
-    bonus::AttackKindBuilder, Actor, ImageLayer, ItemKind, ItemSaveState, ItemState, Module,
-    QuickSlot, Slot, StatList, WeaponStyle,
+    bonus::AttackKindBuilder, Actor, ImageLayer, ItemKind, ItemSaveState, ItemSet, ItemState,
+    Module, QuickSlot, Slot, StatList, WeaponStyle,
 };
 
 #[derive(Clone)]
@@ -57,9 +57,10 @@ impl Inventory {
             };
 
             let variant = item.variant;
+            let durability = item.durability;
             let item_state = match Module::create_get_item(&item.id, &item.adjectives) {
                 None => invalid_data_error(&format!("No item with ID '{}'", item.id)),
-                Some(item) => Ok(ItemState::new(item, variant)),
+                Some(item) => Ok(ItemState::new(item, variant).with_durability(durability)),
             }?;
 
             {
@@ -91,7 +92,7 @@ impl Inventory {
         for (quick_index, quick_slot) in QuickSlot::iter().enumerate() {
             let quick_slot = *quick_slot;
 
-            if quick_index > quick.len() {
+            if quick_index >= quick.len() {
                 break;
             }
             let item = match &quick[quick_index] {
@@ -100,9 +101,10 @@ impl Inventory {
             };
 
             let variant = item.variant;
+            let durability = item.durability;
             let item_state = match Module::create_get_item(&item.id, &item.adjectives) {
                 None => invalid_data_error(&format!("No item with ID '{}'", item.id)),
-                Some(item) => Ok(ItemState::new(item, variant)),
+                Some(item) => Ok(ItemState::new(item, variant).with_durability(durability)),
             }?;
 
             self.quick.insert(quick_slot, item_state);
@@ -168,6 +170,28 @@ impl Inventory {
         self.equipped.get(&slot)
     }
 
+    /// Returns the ammo in the ammo quick slot, if it matches the currently
+    /// equipped main hand weapon
+    pub fn loaded_ammo(&self) -> Option<&ItemState> {
+        let weapon = self.equipped(Slot::HeldMain)?;
+        let ammo = self.quick(QuickSlot::Ammo)?;
+
+        if ammo.item.is_ammo_for(&weapon.item) {
+            Some(ammo)
+        } else {
+            None
+        }
+    }
+
+    /// Returns false if the main hand weapon requires ammunition and no
+    /// matching ammunition is loaded, true otherwise
+    pub fn has_ammo_for_attack(&self) -> bool {
+        match self.equipped(Slot::HeldMain) {
+            None => true,
+            Some(weapon) => !weapon.item.requires_ammo() || self.loaded_ammo().is_some(),
+        }
+    }
+
     /// Returns an iterator traversing all equipped items
     /// in this inventory.  This will only include slots that actually
     /// have an item equipped
@@ -178,6 +202,94 @@ impl Inventory {
         }
     }
 
+    /// Returns each item set with at least one piece equipped, along with the
+    /// number of distinct pieces of that set which are equipped
+    pub fn equipped_sets(&self) -> Vec<(Rc<ItemSet>, u32)> {
+        let mut sets: Vec<(Rc<ItemSet>, Vec<&str>)> = Vec::new();
+        for item_state in self.equipped_iter() {
+            let id = &item_state.item.original_id;
+            let set = match Module::item_set_for(id) {
+                None => continue,
+                Some(set) => set,
+            };
+
+            match sets.iter_mut().find(|(other, _)| other.id == set.id) {
+                None => sets.push((set, vec![id])),
+                Some((_, ids)) => {
+                    if !ids.contains(&id.as_str()) {
+                        ids.push(id);
+                    }
+                }
+            }
+        }
+
+        sets.into_iter()
+            .map(|(set, ids)| (set, ids.len() as u32))
+            .collect()
+    }
+
+    /// Returns the number of distinct pieces of the specified set equipped
+    pub fn set_pieces(&self, set: &ItemSet) -> u32 {
+        self.equipped_sets()
+            .into_iter()
+            .find(|(other, _)| other.id == set.id)
+            .map_or(0, |(_, pieces)| pieces)
+    }
+
+    /// Returns the total weight of all equipped and quick slot items
+    pub fn weight(&self) -> u32 {
+        self.equipped
+            .values()
+            .chain(self.quick.values())
+            .map(|item_state| item_state.item.weight.max(0) as u32)
+            .sum()
+    }
+
+    /// Removes durability from the item equipped in the given slot.  Returns
+    /// true if the item's condition changed
+    pub fn damage_item(&mut self, slot: Slot, amount: u32) -> bool {
+        match self.equipped.get_mut(&slot) {
+            None => false,
+            Some(item_state) => item_state.damage(amount),
+        }
+    }
+
+    /// Repairs the item equipped in the given slot.  Returns true if the
+    /// item's condition changed
+    pub fn repair_item(&mut self, slot: Slot) -> bool {
+        match self.equipped.get_mut(&slot) {
+            None => false,
+            Some(item_state) => item_state.repair(),
+        }
+    }
+
+    /// Repairs all equipped and quick slot items.  Returns true if the
+    /// condition of any equipped item changed
+    pub fn repair_all(&mut self) -> bool {
+        for item_state in self.quick.values_mut() {
+            item_state.repair();
+        }
+
+        let mut changed = false;
+        for item_state in self.equipped.values_mut() {
+            changed |= item_state.repair();
+        }
+        changed
+    }
+
+    /// Returns the slots of all equipped armor that can lose durability
+    pub fn durable_armor_slots(&self) -> Vec<Slot> {
+        Slot::iter()
+            .filter(|slot| match self.equipped.get(slot) {
+                None => false,
+                Some(item_state) => {
+                    item_state.durability().is_some() && item_state.item.is_armor()
+                }
+            })
+            .cloned()
+            .collect()
+    }
+
     pub fn swap_weapon_set(&mut self) {
         let cur_main = self.equipped.remove(&Slot::HeldMain);
         let cur_off = self.equipped.remove(&Slot::HeldOff);
@@ -229,6 +341,11 @@ impl Inventory {
                 // TODO validate - this code path is only being used
                 // when loading actors from a file
             }
+            Ammo => {
+                if item_state.item.ammo.is_none() {
+                    return false;
+                }
+            }
         }
 
         true
@@ -14,10 +14,108 @@
 //  You should have received a copy of the GNU General Public License
 //  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
 
+use std::cmp::Ordering;
 use std::ops::Index;
 use std::slice::Iter;
 
-use sulis_module::ItemState;
+use sulis_module::{Item, ItemState};
+
+/// Identifies an item by its base ID and adjectives, regardless of
+/// variant or condition.  Used to mark junk and to record loadouts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(deny_unknown_fields)]
+pub struct ItemKey {
+    pub id: String,
+
+    #[serde(default)]
+    pub adjectives: Vec<String>,
+}
+
+impl ItemKey {
+    pub fn new(item_state: &ItemState) -> ItemKey {
+        ItemKey {
+            id: item_state.item.original_id.to_string(),
+            adjectives: item_state
+                .item
+                .added_adjectives
+                .iter()
+                .map(|adj| adj.id.to_string())
+                .collect(),
+        }
+    }
+
+    pub fn matches(&self, item_state: &ItemState) -> bool {
+        *self == ItemKey::new(item_state)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields)]
+pub enum ItemSortMode {
+    #[default]
+    Newest,
+    Name,
+    Value,
+    Weight,
+    Kind,
+}
+
+use self::ItemSortMode::*;
+const SORT_MODES_LIST: [ItemSortMode; 5] = [Newest, Name, Value, Weight, Kind];
+
+impl ItemSortMode {
+    /// Returns the sort mode following this one, wrapping around
+    pub fn next(self) -> ItemSortMode {
+        let index = SORT_MODES_LIST.iter().position(|m| *m == self).unwrap_or(0);
+        SORT_MODES_LIST[(index + 1) % SORT_MODES_LIST.len()]
+    }
+
+    fn compare(self, a: (usize, &ItemState), b: (usize, &ItemState)) -> Ordering {
+        let (a_index, a) = a;
+        let (b_index, b) = b;
+        let by_name = || a.item.name.cmp(&b.item.name);
+        match self {
+            Newest => b_index.cmp(&a_index),
+            Name => by_name(),
+            Value => b.item.value.cmp(&a.item.value).then_with(by_name),
+            Weight => b.item.weight.cmp(&a.item.weight).then_with(by_name),
+            Kind => kind_order(&a.item)
+                .cmp(&kind_order(&b.item))
+                .then_with(by_name),
+        }
+    }
+}
+
+fn kind_order(item: &Item) -> u8 {
+    if item.is_weapon() {
+        0
+    } else if item.ammo.is_some() {
+        1
+    } else if item.is_armor() {
+        2
+    } else if item.equippable.is_some() {
+        3
+    } else if item.usable.is_some() {
+        4
+    } else {
+        5
+    }
+}
+
+/// Returns true if the item name or any of its adjective names contain the
+/// specified lowercase search text
+pub fn matches_search(item_state: &ItemState, search: &str) -> bool {
+    if search.is_empty() {
+        return true;
+    }
+
+    let item = &item_state.item;
+    item.name.to_lowercase().contains(search)
+        || item
+            .added_adjectives
+            .iter()
+            .any(|adj| adj.name.to_lowercase().contains(search))
+}
 
 #[derive(Clone, Debug)]
 pub struct ItemList {
@@ -54,6 +152,40 @@ impl ItemList {
         self.items.len()
     }
 
+    /// Returns the total weight of all items in this list
+    pub fn weight(&self) -> u32 {
+        self.items
+            .iter()
+            .map(|(qty, item_state)| qty * item_state.item.weight.max(0) as u32)
+            .sum()
+    }
+
+    /// Repairs all items in this list, merging any that become identical
+    pub fn repair_all(&mut self) {
+        let items = std::mem::take(&mut self.items);
+        for (qty, mut item_state) in items {
+            item_state.repair();
+            self.add_quantity(qty, item_state);
+        }
+    }
+
+    /// Returns the indices of all items matching the search text, ordered
+    /// by the specified sort mode.  Indices remain valid for `get`, `remove`,
+    /// and similar methods.
+    pub fn sorted_indices(&self, mode: ItemSortMode, search: &str) -> Vec<usize> {
+        let search = search.trim().to_lowercase();
+        let mut indices: Vec<usize> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, item))| matches_search(item, &search))
+            .map(|(index, _)| index)
+            .collect();
+
+        indices.sort_by(|a, b| mode.compare((*a, &self.items[*a].1), (*b, &self.items[*b].1)));
+        indices
+    }
+
     pub fn get(&self, index: usize) -> Option<&(u32, ItemState)> {
         self.items.get(index)
     }
@@ -36,10 +36,22 @@ pub use self::area_feedback_text::AreaFeedbackText;
 pub mod area_state;
 pub use self::area_state::AreaState;
 
+mod bark_handler;
+
 mod change_listener;
 pub use self::change_listener::ChangeListener;
 pub use self::change_listener::ChangeListenerList;
 
+pub mod condition;
+
+pub mod conversation_log;
+pub use self::conversation_log::ConversationLog;
+
+pub mod approval_state;
+pub use self::approval_state::ApprovalState;
+
+pub mod crafting;
+
 mod distance_finder;
 pub use self::distance_finder::{
     can_attack, center, center_i32, dist, is_threat, is_within, is_within_attack_dist,
@@ -50,6 +62,7 @@ mod effect;
 pub use self::effect::Effect;
 
 mod entity_attack_handler;
+pub use self::entity_attack_handler::RangedModifiers;
 
 mod entity_state;
 pub use self::entity_state::AreaDrawable;
@@ -72,7 +85,10 @@ pub mod inventory;
 pub use self::inventory::Inventory;
 
 pub mod item_list;
-pub use self::item_list::ItemList;
+pub use self::item_list::{ItemKey, ItemList, ItemSortMode};
+
+mod loadout;
+pub use self::loadout::Loadout;
 
 mod location;
 pub use self::location::Location;
@@ -80,9 +96,13 @@ pub use self::location::Location;
 mod los_calculator;
 pub use self::los_calculator::calculate_los;
 pub use self::los_calculator::has_visibility;
+pub use self::los_calculator::cover;
 
 mod merchant_state;
-pub use self::merchant_state::MerchantState;
+pub use self::merchant_state::{BuybackEntry, MerchantState};
+
+pub mod morale;
+pub use self::morale::MoraleState;
 
 mod path_finder;
 
@@ -98,12 +118,15 @@ mod p_stats;
 pub use self::p_stats::PStats;
 
 pub mod quest_state;
+pub use self::quest_state::QuestEvent;
 pub use self::quest_state::QuestState;
 pub use self::quest_state::QuestStateSet;
 
 mod range_indicator;
 pub use self::range_indicator::{RangeIndicator, RangeIndicatorHandler, RangeIndicatorImageSet};
 
+pub mod response_check;
+
 pub mod save_file;
 pub use self::save_file::SaveFile;
 pub use self::save_file::SaveFileMetaData;
@@ -114,8 +137,13 @@ pub use self::save_state::SaveState;
 pub mod script;
 pub use self::script::{Script, ScriptCallback, ScriptState};
 
+pub mod threat_table;
+pub use self::threat_table::ThreatTable;
+
 mod transition_handler;
 
+mod travel_handler;
+
 mod turn_manager;
 pub(crate) use self::turn_manager::TurnManager;
 
@@ -154,6 +182,11 @@ pub struct UICallback {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WorldMapState {
     locations: HashMap<String, WorldMapLocationState>,
+
+    /// The location the party was travelling to when the journey was
+    /// interrupted by a travel encounter
+    #[serde(default)]
+    travel_destination: Option<String>,
 }
 
 impl WorldMapState {
@@ -172,7 +205,10 @@ impl WorldMapState {
             );
         }
 
-        WorldMapState { locations }
+        WorldMapState {
+            locations,
+            travel_destination: None,
+        }
     }
 
     fn load(&mut self) {
@@ -212,6 +248,10 @@ impl WorldMapState {
         }
     }
 
+    pub fn travel_destination(&self) -> Option<&str> {
+        self.travel_destination.as_deref()
+    }
+
     fn set_visible(&mut self, location: &str, visible: bool) {
         if let Some(ref mut state) = self.locations.get_mut(location) {
             state.visible = visible;
@@ -0,0 +1,203 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::{EntityState, GameState, Inventory, ItemKey};
+use sulis_module::{ItemState, QuickSlot, Slot};
+
+/// A named set of equipped and quick slot items that an actor can
+/// switch to in one action.  Slots not present in the loadout are
+/// left empty when it is applied.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Loadout {
+    pub name: String,
+
+    #[serde(default)]
+    pub equipped: HashMap<Slot, ItemKey>,
+
+    #[serde(default)]
+    pub quick: HashMap<QuickSlot, ItemKey>,
+}
+
+impl Loadout {
+    pub fn new(name: &str, inventory: &Inventory) -> Loadout {
+        let equipped = Slot::iter()
+            .filter_map(|slot| {
+                inventory
+                    .equipped(*slot)
+                    .map(|item| (*slot, ItemKey::new(item)))
+            })
+            .collect();
+
+        let quick = QuickSlot::iter()
+            .filter_map(|slot| {
+                inventory
+                    .quick(*slot)
+                    .map(|item| (*slot, ItemKey::new(item)))
+            })
+            .collect();
+
+        Loadout {
+            name: name.to_string(),
+            equipped,
+            quick,
+        }
+    }
+
+    /// Returns true if the specified item, or lack of an item, is what this
+    /// loadout wants in the given slot
+    pub fn matches_equipped(&self, slot: Slot, item: Option<&ItemState>) -> bool {
+        match (self.equipped.get(&slot), item) {
+            (None, None) => true,
+            (Some(wanted), Some(item)) => wanted.matches(item),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the specified item, or lack of an item, is what this
+    /// loadout wants in the given quick slot
+    pub fn matches_quick(&self, slot: QuickSlot, item: Option<&ItemState>) -> bool {
+        match (self.quick.get(&slot), item) {
+            (None, None) => true,
+            (Some(wanted), Some(item)) => wanted.matches(item),
+            _ => false,
+        }
+    }
+}
+
+/// Moves items between the party stash and the entity's inventory so that
+/// the specified slots match the loadout.  Any items the loadout calls for
+/// that are not present in the stash are skipped.  Returns true if any
+/// items were moved.
+pub(crate) fn apply(
+    entity: &Rc<RefCell<EntityState>>,
+    loadout: &Loadout,
+    slots: &[Slot],
+    quick_slots: &[QuickSlot],
+) -> bool {
+    let stash = GameState::party_stash();
+    let mut changed = false;
+
+    // first, return any items that don't belong to the stash.  the entity
+    // must not be borrowed while the stash is modified
+    for slot in slots {
+        let matches = {
+            let entity = entity.borrow();
+            loadout.matches_equipped(*slot, entity.actor.inventory().equipped(*slot))
+        };
+        if matches {
+            continue;
+        }
+
+        let item = entity.borrow_mut().actor.unequip(*slot);
+        if let Some(item) = item {
+            stash.borrow_mut().add_item(1, item);
+            changed = true;
+        }
+    }
+
+    for slot in quick_slots {
+        let matches = {
+            let entity = entity.borrow();
+            loadout.matches_quick(*slot, entity.actor.inventory().quick(*slot))
+        };
+        if matches {
+            continue;
+        }
+
+        let item = entity.borrow_mut().actor.clear_quick(*slot);
+        if let Some(item) = item {
+            stash.borrow_mut().add_item(1, item);
+            changed = true;
+        }
+    }
+
+    for slot in slots {
+        let wanted = match loadout.equipped.get(slot) {
+            None => continue,
+            Some(wanted) => wanted,
+        };
+
+        if entity.borrow().actor.inventory().equipped(*slot).is_some() {
+            continue;
+        }
+
+        let item = match take_from_stash(wanted) {
+            None => continue,
+            Some(item) => item,
+        };
+
+        let to_add = {
+            let actor = &mut entity.borrow_mut().actor;
+            if actor.can_equip(&item) {
+                changed = true;
+                actor.equip(item, Some(*slot))
+            } else {
+                vec![item]
+            }
+        };
+        for item in to_add {
+            stash.borrow_mut().add_item(1, item);
+        }
+    }
+
+    for slot in quick_slots {
+        let wanted = match loadout.quick.get(slot) {
+            None => continue,
+            Some(wanted) => wanted,
+        };
+
+        if entity.borrow().actor.inventory().quick(*slot).is_some() {
+            continue;
+        }
+
+        let item = match take_from_stash(wanted) {
+            None => continue,
+            Some(item) => item,
+        };
+
+        let to_add = {
+            let actor = &mut entity.borrow_mut().actor;
+            if actor.can_set_quick(&item, *slot) {
+                changed = true;
+                actor.set_quick(item, *slot)
+            } else {
+                Some(item)
+            }
+        };
+
+        if let Some(item) = to_add {
+            stash.borrow_mut().add_item(1, item);
+        }
+    }
+
+    changed
+}
+
+fn take_from_stash(wanted: &ItemKey) -> Option<ItemState> {
+    let stash = GameState::party_stash();
+    let mut stash = stash.borrow_mut();
+    let index = stash
+        .items()
+        .iter()
+        .position(|(_, item)| wanted.matches(item))?;
+
+    stash.remove_item(index)
+}
@@ -18,6 +18,7 @@ use std::cmp;
 use std::collections::HashSet;
 
 use crate::{EntityState, GeneratedArea};
+use sulis_module::Cover;
 
 #[must_use]
 pub fn calculate_los(
@@ -94,6 +95,48 @@ pub fn has_visibility(
     false
 }
 
+/// Computes the cover that `target` has against ranged attacks from `entity`.
+/// This is based on the fraction of points occupied by the target that are
+/// blocked from the entity's line of sight by walls, props, or elevation.
+pub fn cover(
+    area: &GeneratedArea,
+    prop_vis_grid: &[bool],
+    entity: &EntityState,
+    target: &EntityState,
+    full_cover_fraction: f32,
+) -> Cover {
+    let start_x = entity.location.x + entity.size.width / 2;
+    let start_y = entity.location.y + entity.size.height / 2;
+    let src_elev = area.layer_set.elevation(start_x, start_y);
+
+    let mut total = 0;
+    let mut blocked = 0;
+    for p in target.location_points() {
+        total += 1;
+
+        // use the same eye height as check_vis, so visible targets are
+        // not reported as fully covered
+        let dist_squared = (start_x - p.x) * (start_x - p.x) + (start_y - p.y) * (start_y - p.y);
+        let elev = if dist_squared < area.area.vis_dist_up_one_squared {
+            src_elev + 1
+        } else {
+            src_elev
+        };
+
+        if !cast_ray(area, prop_vis_grid, start_x, start_y, p.x, p.y, elev) {
+            blocked += 1;
+        }
+    }
+
+    if blocked == 0 {
+        Cover::None
+    } else if blocked as f32 / total as f32 >= full_cover_fraction {
+        Cover::Full
+    } else {
+        Cover::Partial
+    }
+}
+
 fn check_vis(
     area: &GeneratedArea,
     prop_vis_grid: &[bool],
@@ -17,17 +17,30 @@
 use std::io::Error;
 use std::rc::Rc;
 
-use sulis_core::util::invalid_data_error;
-use sulis_module::{ItemState, LootList, Module, Time};
+use sulis_core::util::{invalid_data_error, ExtInt};
+use sulis_module::{ItemSaveState, ItemState, LootList, Module, QuickSlot, Slot, Time};
 
 use crate::{save_state::MerchantSaveState, ChangeListenerList, GameState, ItemList};
 
+/// An item sold to a merchant, which the party may buy back for the price
+/// it was sold at
+pub struct BuybackEntry {
+    pub item: ItemState,
+    pub price: i32,
+    pub sold_millis: usize,
+}
+
 pub struct MerchantState {
     pub id: String,
     pub buy_frac: f32,
     pub sell_frac: f32,
+    pub repair_frac: Option<f32>,
+
+    /// Merchants sharing a faction share the party's reputation
+    pub faction: Option<String>,
     pub listeners: ChangeListenerList<MerchantState>,
     items: ItemList,
+    buyback: Vec<BuybackEntry>,
 
     pub loot_list_id: Option<String>,
     pub refresh_rate_millis: usize,
@@ -38,14 +51,17 @@ impl MerchantState {
     pub fn load(save: MerchantSaveState) -> Result<MerchantState, Error> {
         let mut items = ItemList::default();
         for item_save in save.items {
-            let item = item_save.item;
-            let variant = item.variant;
-            let item = match Module::create_get_item(&item.id, &item.adjectives) {
-                None => invalid_data_error(&format!("No item with ID '{}'", item.id)),
-                Some(item) => Ok(item),
-            }?;
+            let item = load_item(&item_save.item)?;
+            items.add_quantity(item_save.quantity, item);
+        }
 
-            items.add_quantity(item_save.quantity, ItemState::new(item, variant));
+        let mut buyback = Vec::new();
+        for entry in save.buyback {
+            buyback.push(BuybackEntry {
+                item: load_item(&entry.item)?,
+                price: entry.price,
+                sold_millis: entry.sold_millis,
+            });
         }
 
         Ok(MerchantState {
@@ -53,8 +69,11 @@ impl MerchantState {
             loot_list_id: save.loot_list_id,
             buy_frac: save.buy_frac,
             sell_frac: save.sell_frac,
+            repair_frac: save.repair_frac,
+            faction: save.faction,
             listeners: ChangeListenerList::default(),
             items,
+            buyback,
             refresh_rate_millis: save.refresh_rate_millis,
             last_refresh_millis: save.last_refresh_millis,
         })
@@ -65,6 +84,7 @@ impl MerchantState {
         loot_list: &Rc<LootList>,
         buy_frac: f32,
         sell_frac: f32,
+        repair_frac: Option<f32>,
         refresh_time: Time,
     ) -> MerchantState {
         let mgr = GameState::turn_manager();
@@ -82,7 +102,10 @@ impl MerchantState {
             loot_list_id: Some(loot_list.id.to_string()),
             buy_frac,
             sell_frac,
+            repair_frac,
+            faction: None,
             items,
+            buyback: Vec::new(),
             listeners: ChangeListenerList::default(),
             last_refresh_millis,
             refresh_rate_millis,
@@ -106,6 +129,7 @@ impl MerchantState {
         }
 
         self.last_refresh_millis = cur_millis;
+        self.expire_buyback();
 
         let loot_list_id = match self.loot_list_id {
             None => return,
@@ -129,12 +153,216 @@ impl MerchantState {
         }
     }
 
+    /// Returns the ID used to track the party's reputation with this
+    /// merchant - the faction if it has one, otherwise the merchant ID
+    pub fn reputation_id(&self) -> &str {
+        match self.faction {
+            None => &self.id,
+            Some(ref faction) => faction,
+        }
+    }
+
+    pub fn reputation(&self) -> i32 {
+        GameState::merchant_reputation(self.reputation_id())
+    }
+
+    /// Returns the price modifier from the party's reputation with this
+    /// merchant.  Positive values are better prices for the party
+    pub fn reputation_modifier(&self) -> f32 {
+        Module::rules()
+            .merchant_pricing
+            .reputation_modifier(self.reputation())
+    }
+
+    /// Returns the price modifier from the best haggler in the party.
+    /// Positive values are better prices for the party
+    pub fn haggle_modifier(&self) -> f32 {
+        let rules = Module::rules();
+        let pricing = &rules.merchant_pricing;
+
+        let mut best = None;
+        for member in GameState::party() {
+            let member = member.borrow();
+            let attrs = member.actor.stats.attributes;
+            let bonus = attrs.bonus(pricing.haggle_attribute, rules.base_attribute);
+            let mut score = bonus as f32 * pricing.haggle_attribute_factor;
+
+            if let Some(ref stat) = pricing.haggle_class_stat {
+                score += match member.actor.current_class_stat(stat) {
+                    ExtInt::Int(amount) => amount as f32 * pricing.haggle_class_stat_factor,
+                    ExtInt::Infinity => pricing.max_modifier,
+                };
+            }
+
+            best = Some(best.map_or(score, |best: f32| best.max(score)));
+        }
+
+        best.unwrap_or(0.0)
+    }
+
+    /// Returns the combined price modifier from reputation and haggling,
+    /// limited by the rules `max_modifier`
+    pub fn price_modifier(&self) -> f32 {
+        let max = Module::rules().merchant_pricing.max_modifier;
+        let modifier = self.reputation_modifier() + self.haggle_modifier();
+        modifier.max(-max).min(max)
+    }
+
+    /// Returns the number of items with the specified ID this merchant has,
+    /// including items held for buyback
+    pub fn stock_of(&self, id: &str) -> u32 {
+        let stock: u32 = self
+            .items
+            .iter()
+            .filter(|(_, item)| item.item.id == id)
+            .map(|(qty, _)| *qty)
+            .sum();
+        let buyback = self.buyback.iter().filter(|e| e.item.item.id == id).count();
+        stock + buyback as u32
+    }
+
+    /// Returns the fraction of its normal sell price this merchant will pay
+    /// for the item, based on how many are already in stock
+    pub fn scarcity_frac(&self, item_state: &ItemState) -> f32 {
+        let stock = self.stock_of(&item_state.item.id);
+        Module::rules().merchant_pricing.scarcity_frac(stock)
+    }
+
     pub fn get_buy_price(&self, item_state: &ItemState) -> i32 {
-        ((item_state.item.value as f32) * self.buy_frac).ceil() as i32
+        let frac = self.buy_frac * (1.0 - self.price_modifier());
+        ((item_state.item.value as f32) * frac).ceil() as i32
     }
 
+    /// Returns the price this merchant will pay for the item.  This is
+    /// never more than the price the merchant would charge for it
     pub fn get_sell_price(&self, item_state: &ItemState) -> i32 {
-        ((item_state.item.value as f32) * self.sell_frac).floor() as i32
+        let frac = self.sell_frac * (1.0 + self.price_modifier()) * self.scarcity_frac(item_state);
+        let price = ((item_state.item.value as f32) * frac).floor() as i32;
+        price.min(self.get_buy_price(item_state))
+    }
+
+    /// Returns the price to repair the specified item, or `None` if this
+    /// merchant does not repair items or the item is not damaged
+    pub fn get_repair_price(&self, item_state: &ItemState) -> Option<i32> {
+        let repair_frac = self.repair_frac?;
+        if !item_state.is_damaged() {
+            return None;
+        }
+
+        let value = item_state.repaired_item().value as f32;
+        Some((value * item_state.damage_frac() * repair_frac).ceil() as i32)
+    }
+
+    /// Returns the total price to repair all items carried by the party,
+    /// or `None` if this merchant does not repair items
+    pub fn party_repair_price(&self) -> Option<i32> {
+        self.repair_frac?;
+
+        let mut total = 0;
+        for member in GameState::party() {
+            let member = member.borrow();
+            let inventory = member.actor.inventory();
+            for slot in Slot::iter() {
+                if let Some(item_state) = inventory.equipped(*slot) {
+                    total += self.get_repair_price(item_state).unwrap_or(0);
+                }
+            }
+            for slot in QuickSlot::iter() {
+                if let Some(item_state) = inventory.quick(*slot) {
+                    total += self.get_repair_price(item_state).unwrap_or(0);
+                }
+            }
+        }
+
+        let stash = GameState::party_stash();
+        for (qty, item_state) in stash.borrow().items().iter() {
+            total += *qty as i32 * self.get_repair_price(item_state).unwrap_or(0);
+        }
+
+        Some(total)
+    }
+
+    /// Repairs all items carried by the party, if the party can afford it.
+    /// Returns true if the repair was done
+    pub fn repair_party_items(&self) -> bool {
+        let price = match self.party_repair_price() {
+            None => return false,
+            Some(price) => price,
+        };
+
+        if price == 0 || price > GameState::party_coins() {
+            return false;
+        }
+
+        info!("Merchant '{}' repairing party items for {}", self.id, price);
+        GameState::add_party_coins(-price);
+        for member in GameState::party() {
+            member.borrow_mut().actor.repair_all_items();
+        }
+        GameState::party_stash().borrow_mut().repair_all();
+        true
+    }
+
+    /// Sells the item to this merchant, returning the price paid.  The item
+    /// is kept available for buyback at that price for the time set in
+    /// the rules
+    pub fn sell(&mut self, item_state: ItemState) -> i32 {
+        self.expire_buyback();
+
+        let price = self.get_sell_price(&item_state);
+        let mgr = GameState::turn_manager();
+        let sold_millis = mgr.borrow().total_elapsed_millis();
+        self.buyback.insert(
+            0,
+            BuybackEntry {
+                item: item_state,
+                price,
+                sold_millis,
+            },
+        );
+
+        let max = Module::rules().merchant_buyback_max;
+        while self.buyback.len() > max {
+            if let Some(entry) = self.buyback.pop() {
+                self.items.add(entry.item);
+            }
+        }
+
+        self.listeners.notify(&self);
+        price
+    }
+
+    /// Moves any items sold longer ago than the buyback time into the
+    /// merchant's regular stock
+    pub fn expire_buyback(&mut self) {
+        let mgr = GameState::turn_manager();
+        let cur_millis = mgr.borrow().total_elapsed_millis();
+        let window = Module::rules().compute_millis(Module::rules().merchant_buyback_time);
+
+        let mut i = 0;
+        while i < self.buyback.len() {
+            if self.buyback[i].sold_millis + window < cur_millis {
+                let entry = self.buyback.remove(i);
+                self.items.add(entry.item);
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    pub fn buyback(&self) -> &[BuybackEntry] {
+        &self.buyback
+    }
+
+    /// Removes the buyback entry at the specified index
+    pub fn remove_buyback(&mut self, index: usize) -> Option<BuybackEntry> {
+        if index >= self.buyback.len() {
+            return None;
+        }
+
+        let entry = self.buyback.remove(index);
+        self.listeners.notify(&self);
+        Some(entry)
     }
 
     pub fn add(&mut self, item_state: ItemState) {
@@ -158,3 +386,12 @@ impl MerchantState {
         &self.items
     }
 }
+
+fn load_item(item: &ItemSaveState) -> Result<ItemState, Error> {
+    let base = match Module::create_get_item(&item.id, &item.adjectives) {
+        None => invalid_data_error(&format!("No item with ID '{}'", item.id)),
+        Some(base) => Ok(base),
+    }?;
+
+    Ok(ItemState::new(base, item.variant).with_durability(item.durability))
+}
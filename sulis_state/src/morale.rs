@@ -0,0 +1,249 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::area_feedback_text::ColorKind;
+use crate::{AreaFeedbackText, EntityState, GameState, TurnManager};
+use sulis_module::Faction;
+
+const BASE: &str = "MoraleBase";
+const FLEE_THRESHOLD: &str = "MoraleFleeThreshold";
+const SURRENDER: &str = "MoraleSurrender";
+const ALLY_LOSS: &str = "MoraleAllyLoss";
+const HP_LOSS: &str = "MoraleHpLoss";
+
+const DEFAULT_BASE: i32 = 100;
+const DEFAULT_ALLY_LOSS: i32 = 20;
+const DEFAULT_HP_LOSS: i32 = 50;
+
+const FLEE_DIST: f32 = 12.0;
+const MAX_FLEE_THRESH: u32 = 10;
+
+/// Whether an AI controlled entity is still fighting, or has broken and
+/// is either running from the fight or has given up.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields)]
+pub enum MoraleState {
+    #[default]
+    Steady,
+    Fleeing,
+    Surrendered,
+}
+
+impl MoraleState {
+    pub fn option_from_str(val: &str) -> Option<MoraleState> {
+        match val {
+            "Steady" => Some(MoraleState::Steady),
+            "Fleeing" => Some(MoraleState::Fleeing),
+            "Surrendered" => Some(MoraleState::Surrendered),
+            _ => None,
+        }
+    }
+
+    pub fn to_str(self) -> &'static str {
+        match self {
+            MoraleState::Steady => "Steady",
+            MoraleState::Fleeing => "Fleeing",
+            MoraleState::Surrendered => "Surrendered",
+        }
+    }
+}
+
+/// Computes the current morale of the entity, based on its AI template
+/// params, the number of its AI group that have been lost, its current
+/// hit points, and any bonus applied by scripts.
+pub fn morale(entity: &EntityState, mgr: &TurnManager) -> i32 {
+    let (base, ally_loss, hp_loss) = match &entity.actor.actor.ai {
+        None => (DEFAULT_BASE, DEFAULT_ALLY_LOSS, DEFAULT_HP_LOSS),
+        Some(ai) => (
+            ai.param(BASE, DEFAULT_BASE),
+            ai.param(ALLY_LOSS, DEFAULT_ALLY_LOSS),
+            ai.param(HP_LOSS, DEFAULT_HP_LOSS),
+        ),
+    };
+
+    let allies_lost = match entity.ai_group() {
+        None => 0,
+        Some(group) => mgr
+            .entity_iter()
+            .filter(|other| {
+                let other = other.borrow();
+                other.index() != entity.index()
+                    && other.ai_group() == Some(group)
+                    && (other.actor.is_dead() || other.morale_state() != MoraleState::Steady)
+            })
+            .count() as i32,
+    };
+
+    let max_hp = entity.actor.stats.max_hp;
+    let hp_frac_lost = if max_hp > 0 {
+        1.0 - entity.actor.hp().max(0) as f32 / max_hp as f32
+    } else {
+        0.0
+    };
+
+    base + entity.morale_bonus() - allies_lost * ally_loss - (hp_frac_lost * hp_loss as f32) as i32
+}
+
+/// Checks whether the entity's morale has broken, updating its morale
+/// state if so.  Entities whose AI template does not specify a flee
+/// threshold never break.
+pub fn update(entity: &Rc<RefCell<EntityState>>) -> MoraleState {
+    let cur_state = entity.borrow().morale_state();
+    if cur_state != MoraleState::Steady {
+        return cur_state;
+    }
+
+    let ai = match &entity.borrow().actor.actor.ai {
+        None => return cur_state,
+        Some(ai) => Rc::clone(ai),
+    };
+
+    if !ai.has_param(FLEE_THRESHOLD) {
+        return cur_state;
+    }
+
+    let mgr = GameState::turn_manager();
+    let value = morale(&entity.borrow(), &mgr.borrow());
+    if value >= ai.param(FLEE_THRESHOLD, 0) {
+        return cur_state;
+    }
+
+    debug!(
+        "Morale for '{}' broken at {}",
+        entity.borrow().actor.actor.name,
+        value
+    );
+
+    let state = if ai.param(SURRENDER, 0) != 0 {
+        MoraleState::Surrendered
+    } else {
+        MoraleState::Fleeing
+    };
+    set_state(entity, state);
+    state
+}
+
+/// Sets the morale state of the entity, showing feedback text and, for
+/// surrendering entities, removing them from the fight.
+pub fn set_state(entity: &Rc<RefCell<EntityState>>, state: MoraleState) {
+    if entity.borrow().morale_state() == state {
+        return;
+    }
+
+    entity.borrow_mut().set_morale_state(state);
+
+    let text = match state {
+        MoraleState::Steady => return,
+        MoraleState::Fleeing => "Flees!",
+        MoraleState::Surrendered => "Surrenders!",
+    };
+
+    let area_state = GameState::area_state();
+    let mut feedback = AreaFeedbackText::with_target(&entity.borrow(), &area_state.borrow());
+    feedback.add_entry(text.to_string(), ColorKind::Info);
+    area_state.borrow_mut().add_feedback_text(feedback);
+
+    if state == MoraleState::Surrendered {
+        {
+            let mut entity = entity.borrow_mut();
+            entity.actor.set_faction(Faction::Neutral);
+            entity.set_ai_active(false);
+        }
+
+        let mgr = GameState::turn_manager();
+        mgr.borrow_mut().check_combat_over(entity);
+    }
+}
+
+/// Attempts to move the fleeing entity away from all visible hostiles.
+/// Returns true if a move was started, false otherwise.
+pub fn flee(entity: &Rc<RefCell<EntityState>>) -> bool {
+    if !entity.borrow().can_move() {
+        return false;
+    }
+
+    let mgr = GameState::turn_manager();
+    let area = match GameState::get_area_state(&entity.borrow().location.area_id) {
+        None => return false,
+        Some(area) => area,
+    };
+
+    let (x, y) = (
+        entity.borrow().location.x as f32,
+        entity.borrow().location.y as f32,
+    );
+    let mut total = (0.0, 0.0);
+    let mut to_ignore = Vec::new();
+    for index in area.borrow().entity_iter() {
+        let other = mgr.borrow().entity(*index);
+        if Rc::ptr_eq(&other, entity) {
+            continue;
+        }
+        let parent = entity.borrow();
+        let other = other.borrow();
+
+        if parent.is_friendly(&other) && parent.ai_group() == other.ai_group() {
+            to_ignore.push(*index);
+        }
+
+        if other.actor.is_dead() || !parent.is_hostile(&other) {
+            continue;
+        }
+        if !area.borrow().has_visibility(&other, &parent) {
+            continue;
+        }
+
+        let angle = (y - other.location.y as f32).atan2(x - other.location.x as f32);
+        total.0 += angle.cos();
+        total.1 += angle.sin();
+    }
+
+    if total.0 == 0.0 && total.1 == 0.0 {
+        return false;
+    }
+
+    let angle = total.1.atan2(total.0);
+    let dest_x = (x + angle.cos() * FLEE_DIST).round();
+    let dest_y = (y + angle.sin() * FLEE_DIST).round();
+
+    for thresh in 1..=MAX_FLEE_THRESH {
+        let mut dest = GameState::get_point_dest(&entity.borrow(), dest_x, dest_y);
+        dest.dist = thresh as f32;
+        if GameState::move_towards_dest(entity, &to_ignore, dest, None) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Returns true if the fleeing entity is far enough from the party to
+/// leave the area entirely.
+pub fn has_escaped(entity: &EntityState) -> bool {
+    let area = match GameState::get_area_state(&entity.location.area_id) {
+        None => return false,
+        Some(area) => area,
+    };
+    let vis_dist = area.borrow().area.area.vis_dist as f32;
+
+    let pos = entity.location.to_point();
+    GameState::party()
+        .into_iter()
+        .all(|member| member.borrow().location.to_point().dist(pos) > vis_dist)
+}
@@ -14,25 +14,78 @@
 //  You should have received a copy of the GNU General Public License
 //  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
 
-use crate::{ChangeListenerList, GameState, ItemList};
+use std::collections::HashSet;
+
+use crate::{ChangeListenerList, GameState, ItemKey, ItemList, ItemSortMode, QuestEvent};
 use sulis_module::{ItemListEntrySaveState, ItemState, Module};
 
 pub struct PartyStash {
     items: ItemList,
     coins_id: String,
+    sort_mode: ItemSortMode,
+    junk: HashSet<ItemKey>,
     pub listeners: ChangeListenerList<PartyStash>,
 }
 
 impl PartyStash {
-    pub(crate) fn new(items: ItemList) -> PartyStash {
+    pub(crate) fn new(items: ItemList, sort_mode: ItemSortMode, junk: Vec<ItemKey>) -> PartyStash {
         let coins_id = Module::rules().coins_item.to_string();
         PartyStash {
             items,
             coins_id,
+            sort_mode,
+            junk: junk.into_iter().collect(),
             listeners: ChangeListenerList::default(),
         }
     }
 
+    pub fn sort_mode(&self) -> ItemSortMode {
+        self.sort_mode
+    }
+
+    pub fn set_sort_mode(&mut self, sort_mode: ItemSortMode) {
+        self.sort_mode = sort_mode;
+        self.listeners.notify(&self);
+    }
+
+    pub(crate) fn junk(&self) -> Vec<ItemKey> {
+        self.junk.iter().cloned().collect()
+    }
+
+    pub fn is_junk(&self, item_state: &ItemState) -> bool {
+        self.junk.contains(&ItemKey::new(item_state))
+    }
+
+    /// Marks or unmarks the item at the specified index, and all other
+    /// items with the same ID and adjectives, as junk
+    pub fn set_junk(&mut self, index: usize, junk: bool) {
+        let key = match self.items.get(index) {
+            None => return,
+            Some((_, item_state)) => ItemKey::new(item_state),
+        };
+
+        if junk {
+            self.junk.insert(key);
+        } else {
+            self.junk.remove(&key);
+        }
+        self.listeners.notify(&self);
+    }
+
+    /// Returns the indices of all junk items, in descending order so that
+    /// they may be removed one at a time
+    pub fn junk_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, item_state))| self.is_junk(item_state))
+            .map(|(index, _)| index)
+            .collect();
+        indices.reverse();
+        indices
+    }
+
     pub(crate) fn save(&self) -> Vec<ItemListEntrySaveState> {
         self.items
             .iter()
@@ -55,13 +108,50 @@ impl PartyStash {
             return None;
         }
 
+        let id = item_state.item.original_id.to_string();
         let index = self.items.add_quantity(quantity, item_state);
+        let quantity = self.quantity_of(&id);
+        GameState::add_quest_event(QuestEvent::ItemObtained { id, quantity });
 
         self.listeners.notify(&self);
+        self.update_encumbrance();
 
         Some(index)
     }
 
+    /// Returns the total weight of all items in the stash
+    pub fn weight(&self) -> u32 {
+        self.items.weight()
+    }
+
+    /// Returns the total carry capacity of all party members
+    pub fn capacity(&self) -> u32 {
+        GameState::party()
+            .iter()
+            .map(|member| member.borrow().actor.carry_capacity())
+            .sum()
+    }
+
+    /// Splits the weight of the stash evenly among all party members, so
+    /// that the whole party is slowed down by carrying too much.
+    pub(crate) fn update_encumbrance(&self) {
+        let party = GameState::party();
+        if party.is_empty() {
+            return;
+        }
+
+        let share = self.weight() / party.len() as u32;
+        for member in party {
+            member.borrow_mut().actor.set_stash_weight(share);
+        }
+    }
+
+    /// Repairs all damaged items in the stash
+    pub fn repair_all(&mut self) {
+        self.items.repair_all();
+        self.listeners.notify(&self);
+    }
+
     /// Returns whether or not this stash has at least one item
     /// with the specified ID
     pub fn has_item(&self, id: &str) -> bool {
@@ -74,6 +164,43 @@ impl PartyStash {
         false
     }
 
+    /// Returns the total quantity of items with the specified ID
+    /// in this stash, across all variants and adjectives
+    pub fn quantity_of(&self, id: &str) -> u32 {
+        self.items
+            .iter()
+            .filter(|(_, item)| item.item.original_id == id)
+            .map(|(qty, _)| *qty)
+            .sum()
+    }
+
+    /// Removes the specified quantity of items with the given ID, across all
+    /// variants and adjectives.  Returns false and removes nothing if there
+    /// are not enough items present.
+    pub fn remove_quantity_of(&mut self, id: &str, quantity: u32) -> bool {
+        if self.quantity_of(id) < quantity {
+            return false;
+        }
+
+        let mut remaining = quantity;
+        while remaining > 0 {
+            let index = match self
+                .items
+                .iter()
+                .position(|(_, item)| item.item.original_id == id)
+            {
+                None => break,
+                Some(index) => index,
+            };
+            self.items.remove(index);
+            remaining -= 1;
+        }
+
+        self.listeners.notify(&self);
+        self.update_encumbrance();
+        true
+    }
+
     #[must_use]
     /// Removes one item from the specified index.  returns it if there
     /// was an item to remove
@@ -81,6 +208,7 @@ impl PartyStash {
         let result = self.items.remove(index);
 
         self.listeners.notify(&self);
+        self.update_encumbrance();
 
         result
     }
@@ -23,6 +23,7 @@ use sulis_core::{
     util::{self, Point},
 };
 use sulis_module::area::{Destination, LocationChecker, PathFinder, PathFinderGrid};
+use sulis_module::MOVE_TO_THRESHOLD;
 
 pub struct StateLocationChecker<'a, 'b> {
     width: i32,
@@ -133,6 +134,61 @@ pub fn move_towards_point(
     Some(anim)
 }
 
+/// Creates a move animation taking the entity through each of the waypoints
+/// in turn, finding a path between each successive pair.  Waypoints that
+/// cannot be reached are skipped.  Action points are ignored
+pub fn move_along_path(
+    finder: &mut PathFinder,
+    area: &AreaState,
+    entity: &Rc<RefCell<EntityState>>,
+    waypoints: &[Point],
+) -> Option<Anim> {
+    let mut path: Vec<Point> = Vec::new();
+    {
+        let entity = entity.borrow();
+        let checker = StateLocationChecker::new(area, &entity, &[]);
+        finder.set_max_iterations(2_000);
+
+        let w = entity.size.width as f32;
+        let h = entity.size.height as f32;
+        let mut start = entity.location.to_point();
+        for waypoint in waypoints {
+            let dest = Destination {
+                x: waypoint.x as f32,
+                y: waypoint.y as f32,
+                w,
+                h,
+                parent_w: w,
+                parent_h: h,
+                dist: MOVE_TO_THRESHOLD,
+                max_path_len: None,
+            };
+
+            let segment = match finder.find(&checker, start.x, start.y, dest) {
+                None => {
+                    debug!("Skipping unreachable waypoint {:?}", waypoint);
+                    continue;
+                }
+                Some(segment) => segment,
+            };
+
+            let skip = if path.is_empty() { 0 } else { 1 };
+            path.extend(segment.into_iter().skip(skip));
+            start = path[path.len() - 1];
+        }
+    }
+
+    if path.is_empty() {
+        return None;
+    }
+
+    Some(animation::move_animation::new(
+        entity,
+        path,
+        Config::animation_base_time_millis(),
+    ))
+}
+
 pub fn can_move_towards_point(
     finder: &mut PathFinder,
     area: &AreaState,
@@ -71,6 +71,7 @@ impl PropState {
             let quantity = item_save.quantity;
             let item = &item_save.item;
             let variant = item.variant;
+            let durability = item.durability;
             let item = match Module::create_get_item(&item.id, &item.adjectives) {
                 None => {
                     warn!(
@@ -81,7 +82,8 @@ impl PropState {
                 }
                 Some(item) => item,
             };
-            items.add_quantity(quantity, ItemState::new(item, variant));
+            let item = ItemState::new(item, variant).with_durability(durability);
+            items.add_quantity(quantity, item);
         }
 
         let mut anim_state = AnimationState::default();
@@ -162,6 +164,7 @@ impl PropState {
                 for item_save_state in items {
                     let item = &item_save_state.item;
                     let variant = item.variant;
+                    let durability = item.durability;
                     let item = match Module::create_get_item(&item.id, &item.adjectives) {
                         None => invalid_data_error(&format!(
                             "No item with ID '{}'",
@@ -170,7 +173,8 @@ impl PropState {
                         Some(item) => Ok(item),
                     }?;
 
-                    item_list.add_quantity(item_save_state.quantity, ItemState::new(item, variant));
+                    let item = ItemState::new(item, variant).with_durability(durability);
+                    item_list.add_quantity(item_save_state.quantity, item);
                 }
 
                 let loot = match loot_to_generate {
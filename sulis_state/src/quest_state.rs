@@ -15,9 +15,23 @@
 //  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
 
 use std::collections::HashMap;
-
-use crate::{save_state::QuestSaveState, ChangeListenerList};
-use sulis_module::{on_trigger::QuestEntryState, Module};
+use std::rc::Rc;
+
+use crate::{save_state::QuestSaveState, ChangeListenerList, GameState, PartyStash};
+use sulis_core::util::Point;
+use sulis_module::quest::{ObjectiveKind, QuestEntry, QuestMarker};
+use sulis_module::{on_trigger::QuestEntryState, Module, Quest};
+
+/// Game events which may advance quest objectives
+#[derive(Debug, Clone)]
+pub enum QuestEvent {
+    Killed(String),
+    ItemObtained { id: String, quantity: u32 },
+    Reached { area: String, location: Point },
+    Talked(String),
+    Counter { id: String, amount: u32 },
+    EntryActivated { quest: String, entry: String },
+}
 
 pub struct QuestStateSet {
     quests: HashMap<String, QuestState>,
@@ -53,6 +67,34 @@ impl Default for QuestStateSet {
     }
 }
 
+/// Returns an `ItemObtained` event for each obtain objective of the
+/// specified quest entry, with the quantity currently held in the stash.
+/// `EntryActivated` events are converted with this before being handled,
+/// so that items already held count towards newly active objectives
+pub(crate) fn held_item_events(quest: &str, entry: &str, stash: &PartyStash) -> Vec<QuestEvent> {
+    let quest = match Module::quest(quest) {
+        None => return Vec::new(),
+        Some(quest) => quest,
+    };
+
+    let entry = match quest.entries.get(entry) {
+        None => return Vec::new(),
+        Some(entry) => entry,
+    };
+
+    entry
+        .objectives
+        .iter()
+        .filter_map(|objective| match &objective.kind {
+            ObjectiveKind::Obtain { item, .. } => Some(QuestEvent::ItemObtained {
+                id: item.to_string(),
+                quantity: stash.quantity_of(item),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
 impl QuestStateSet {
     pub fn load(data: QuestSaveState) -> QuestStateSet {
         let mut quests = HashMap::new();
@@ -95,6 +137,27 @@ impl QuestStateSet {
         }
     }
 
+    /// Returns the markers for all active entries of the currently tracked
+    /// quest
+    pub fn active_markers(&self) -> Vec<QuestMarker> {
+        let quest = match self.current_quest().and_then(|id| Module::quest(id)) {
+            None => return Vec::new(),
+            Some(quest) => quest,
+        };
+
+        let quest_state = match self.quests.get(&quest.id) {
+            None => return Vec::new(),
+            Some(quest_state) => quest_state,
+        };
+
+        quest_state
+            .iter()
+            .filter(|(_, state)| *state == QuestEntryState::Active)
+            .filter_map(|(id, _)| quest.entries.get(id))
+            .filter_map(|entry| entry.marker.clone())
+            .collect()
+    }
+
     fn set_current_quest_and_notify(&mut self, quest: &str) {
         self.current_quest.retain(|id| id != quest);
 
@@ -130,6 +193,13 @@ impl QuestStateSet {
     }
 
     pub fn set_entry_state(&mut self, quest_id: &str, entry: &str, state: QuestEntryState) {
+        if state == QuestEntryState::Active {
+            GameState::add_quest_event(QuestEvent::EntryActivated {
+                quest: quest_id.to_string(),
+                entry: entry.to_string(),
+            });
+        }
+
         let mut done = false;
         if let Some(ref mut quest) = self.quests.get_mut(quest_id) {
             quest.set_entry_state(entry, state);
@@ -147,6 +217,82 @@ impl QuestStateSet {
         self.set_current_quest_and_notify(quest_id);
     }
 
+    /// Advances the objectives of all active quest entries matching the
+    /// event.  Entries whose objectives are all met are completed, and are
+    /// returned so that their rewards can be given
+    pub(crate) fn handle_event(&mut self, event: &QuestEvent) -> Vec<(Rc<Quest>, String)> {
+        let mut changed = false;
+        let mut completed = Vec::new();
+        for quest in Module::all_quests() {
+            let quest_state = match self.quests.get_mut(&quest.id) {
+                None => continue,
+                Some(quest_state) => quest_state,
+            };
+
+            match quest_state.state {
+                QuestEntryState::Visible | QuestEntryState::Active => (),
+                QuestEntryState::Hidden | QuestEntryState::Complete => continue,
+            }
+
+            let active: Vec<String> = quest_state
+                .iter()
+                .filter(|(_, state)| *state == QuestEntryState::Active)
+                .map(|(id, _)| id.to_string())
+                .collect();
+
+            for entry_id in active {
+                let entry = match quest.entries.get(&entry_id) {
+                    None => continue,
+                    Some(entry) => entry,
+                };
+
+                if !quest_state.advance(&entry_id, entry, event) {
+                    continue;
+                }
+                changed = true;
+
+                if quest_state.objectives_met(&entry_id, entry) {
+                    completed.push((Rc::clone(&quest), entry_id));
+                }
+            }
+        }
+
+        for (quest, entry_id) in completed.iter() {
+            self.complete_entry(quest, entry_id);
+        }
+
+        if changed && completed.is_empty() {
+            self.listeners.notify(&self);
+        }
+
+        completed
+    }
+
+    fn complete_entry(&mut self, quest: &Quest, entry_id: &str) {
+        info!(
+            "Completed objectives for quest '{}': '{}'",
+            quest.id, entry_id
+        );
+        self.set_entry_state(&quest.id, entry_id, QuestEntryState::Complete);
+
+        let entry = match quest.entries.get(entry_id) {
+            None => return,
+            Some(entry) => entry,
+        };
+
+        if let Some(next) = &entry.next {
+            if !quest.entries.contains_key(next) {
+                warn!("Invalid next entry '{}' for quest '{}'", next, quest.id);
+            } else {
+                self.set_entry_state(&quest.id, next, QuestEntryState::Active);
+            }
+        }
+
+        if entry.completes_quest {
+            self.set_state(&quest.id, QuestEntryState::Complete);
+        }
+    }
+
     pub fn quests_iter(self) -> impl Iterator<Item = (String, QuestState)> {
         self.quests.into_iter()
     }
@@ -157,6 +303,10 @@ pub struct QuestState {
     id: String,
     state: QuestEntryState,
     entries: Vec<(String, QuestEntryState)>,
+
+    /// Progress towards each objective, for entries with objectives
+    #[serde(default)]
+    progress: HashMap<String, Vec<u32>>,
 }
 
 impl QuestState {
@@ -165,6 +315,7 @@ impl QuestState {
             id,
             state: QuestEntryState::Hidden,
             entries: Vec::new(),
+            progress: HashMap::new(),
         }
     }
 
@@ -204,6 +355,75 @@ impl QuestState {
         self.state
     }
 
+    /// Returns the current progress towards the objective at `index` of
+    /// the specified entry
+    pub fn progress(&self, entry: &str, index: usize) -> u32 {
+        self.progress
+            .get(entry)
+            .and_then(|progress| progress.get(index))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn objectives_met(&self, entry_id: &str, entry: &QuestEntry) -> bool {
+        entry
+            .objectives
+            .iter()
+            .enumerate()
+            .all(|(index, obj)| self.progress(entry_id, index) >= obj.kind.count())
+    }
+
+    /// Applies the event to each objective of the entry.  Returns true if
+    /// any progress changed
+    fn advance(&mut self, entry_id: &str, entry: &QuestEntry, event: &QuestEvent) -> bool {
+        use self::ObjectiveKind::*;
+
+        let progress = self.progress.entry(entry_id.to_string()).or_default();
+        progress.resize(entry.objectives.len(), 0);
+
+        let mut changed = false;
+        for (objective, cur) in entry.objectives.iter().zip(progress.iter_mut()) {
+            let new = match (&objective.kind, event) {
+                (Kill { actor, .. }, QuestEvent::Killed(id)) if actor == id => *cur + 1,
+                (Obtain { item, .. }, QuestEvent::ItemObtained { id, quantity }) if item == id => {
+                    *quantity
+                }
+                (
+                    Reach {
+                        area,
+                        location,
+                        distance,
+                    },
+                    QuestEvent::Reached {
+                        area: reached,
+                        location: p,
+                    },
+                ) if area == reached => match location {
+                    None => 1,
+                    Some(location) if location.dist(*p) <= *distance => 1,
+                    Some(_) => *cur,
+                },
+                (Talk { actor }, QuestEvent::Talked(id)) if actor == id => 1,
+                (
+                    Counter { id, .. },
+                    QuestEvent::Counter {
+                        id: counter,
+                        amount,
+                    },
+                ) if id == counter => *cur + amount,
+                _ => continue,
+            };
+
+            let new = new.min(objective.kind.count());
+            if new != *cur {
+                *cur = new;
+                changed = true;
+            }
+        }
+
+        changed
+    }
+
     pub fn iter(&self) -> impl DoubleEndedIterator<Item = &(String, QuestEntryState)> {
         self.entries.iter()
     }
@@ -0,0 +1,88 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::{EntityState, GameState};
+use sulis_core::util::gen_rand;
+use sulis_module::conversation::{CheckKind, ResponseCheck};
+use sulis_module::Module;
+
+const CHECK_DIE: i32 = 20;
+
+fn attribute_value(check: &ResponseCheck, entity: &EntityState) -> i32 {
+    entity.actor.stats.attributes.get(check.attribute) as i32
+}
+
+/// Returns the chance, between 0.0 and 1.0, that the entity will succeed
+/// at the check
+pub fn chance(check: &ResponseCheck, entity: &EntityState) -> f32 {
+    let value = attribute_value(check, entity);
+    match check.kind {
+        CheckKind::Fixed => {
+            if value >= check.difficulty {
+                1.0
+            } else {
+                0.0
+            }
+        }
+        CheckKind::Roll => {
+            let bonus = value - Module::rules().base_attribute;
+            let min_roll = check.difficulty - bonus;
+            let successes = (CHECK_DIE + 1 - min_roll).clamp(0, CHECK_DIE);
+            successes as f32 / CHECK_DIE as f32
+        }
+    }
+}
+
+/// Returns the entity that will make the check.  This is the player unless
+/// the check allows any party member, in which case it is the member with
+/// the best chance of success
+pub fn checker(check: &ResponseCheck, pc: &Rc<RefCell<EntityState>>) -> Rc<RefCell<EntityState>> {
+    let mut best = Rc::clone(pc);
+    if !check.party {
+        return best;
+    }
+
+    let mut best_chance = chance(check, &pc.borrow());
+    for member in GameState::party() {
+        let member_chance = chance(check, &member.borrow());
+        if member_chance > best_chance {
+            best_chance = member_chance;
+            best = member;
+        }
+    }
+
+    best
+}
+
+/// Makes the check for the entity, returning true on success
+pub fn roll(check: &ResponseCheck, entity: &EntityState) -> bool {
+    let value = attribute_value(check, entity);
+    match check.kind {
+        CheckKind::Fixed => value >= check.difficulty,
+        CheckKind::Roll => {
+            let bonus = value - Module::rules().base_attribute;
+            let roll = gen_rand(1, CHECK_DIE + 1);
+            info!(
+                "'{}' rolled {} + {} against {} for {:?} check",
+                entity.actor.actor.name, roll, bonus, check.difficulty, check.attribute
+            );
+            roll + bonus >= check.difficulty
+        }
+    }
+}
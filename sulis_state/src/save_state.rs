@@ -30,8 +30,10 @@ use crate::animation::AnimSaveState;
 use crate::area_state::TriggerState;
 use crate::script::CallbackData;
 use crate::{
-    effect, prop_state::Interactive, turn_manager::EncounterRef, ActorState, Effect, EntityState,
-    Formation, GameState, Location, MerchantState, PStats, PropState, QuestState, WorldMapState,
+    effect, prop_state::Interactive, turn_manager::EncounterRef, ActorState, ApprovalState,
+    ConversationLog, Effect, EntityState, Formation, GameState, ItemKey, ItemSortMode, Loadout,
+    Location, MerchantState, MoraleState, PStats, PropState, QuestState, ThreatTable,
+    WorldMapState,
 };
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -41,6 +43,12 @@ pub struct SaveState {
     pub(crate) formation: Formation,
     pub(crate) coins: i32,
     pub(crate) stash: Vec<ItemListEntrySaveState>,
+
+    #[serde(default)]
+    pub(crate) stash_sort: ItemSortMode,
+
+    #[serde(default)]
+    pub(crate) junk: Vec<ItemKey>,
     pub(crate) selected: Vec<usize>,
 
     #[serde(default = "default_zoom")]
@@ -54,6 +62,21 @@ pub struct SaveState {
 
     #[serde(default)]
     pub(crate) total_elapsed_millis: usize,
+
+    #[serde(default)]
+    pub(crate) merchant_reputation: HashMap<String, i32>,
+
+    #[serde(default)]
+    pub(crate) faction_reputation: HashMap<String, i32>,
+
+    #[serde(default)]
+    pub(crate) conversation_log: ConversationLog,
+
+    #[serde(default)]
+    pub(crate) approval: ApprovalState,
+
+    #[serde(default)]
+    pub(crate) played_banter: Vec<String>,
 }
 
 fn default_zoom() -> f32 {
@@ -85,6 +108,8 @@ impl SaveState {
         let formation = formation.borrow().clone();
 
         let stash = GameState::party_stash();
+        let stash_sort = stash.borrow().sort_mode();
+        let junk = stash.borrow().junk();
         let stash = stash.borrow().save();
 
         let quest_state = GameState::quest_state();
@@ -111,11 +136,18 @@ impl SaveState {
             formation,
             coins: GameState::party_coins(),
             stash,
+            stash_sort,
+            junk,
             manager: ManagerSaveState::new(),
             anims: GameState::save_anims(),
             world_map: GameState::world_map(),
             quests: quest_state,
             total_elapsed_millis,
+            merchant_reputation: GameState::merchant_reputations(),
+            faction_reputation: GameState::faction_reputations(),
+            conversation_log: GameState::conversation_log(),
+            approval: GameState::approval_state(),
+            played_banter: GameState::played_banter(),
         }
     }
 
@@ -381,6 +413,8 @@ pub struct MerchantSaveState {
     pub(crate) id: String,
     pub(crate) buy_frac: f32,
     pub(crate) sell_frac: f32,
+    #[serde(default)]
+    pub(crate) faction: Option<String>,
     pub(crate) items: Vec<ItemListEntrySaveState>,
     #[serde(default)]
     pub(crate) refresh_rate_millis: usize,
@@ -389,6 +423,20 @@ pub struct MerchantSaveState {
 
     #[serde(default)]
     pub(crate) loot_list_id: Option<String>,
+
+    #[serde(default)]
+    pub(crate) repair_frac: Option<f32>,
+
+    #[serde(default)]
+    pub(crate) buyback: Vec<BuybackSaveState>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct BuybackSaveState {
+    pub(crate) item: ItemSaveState,
+    pub(crate) price: i32,
+    pub(crate) sold_millis: usize,
 }
 
 impl MerchantSaveState {
@@ -399,11 +447,24 @@ impl MerchantSaveState {
             .map(|(q, ref it)| ItemListEntrySaveState::new(*q, &it))
             .collect();
 
+        let buyback = merchant
+            .buyback()
+            .iter()
+            .map(|entry| BuybackSaveState {
+                item: ItemSaveState::new(&entry.item),
+                price: entry.price,
+                sold_millis: entry.sold_millis,
+            })
+            .collect();
+
         MerchantSaveState {
             id: merchant.id.to_string(),
             loot_list_id: merchant.loot_list_id.clone(),
             buy_frac: merchant.buy_frac,
             sell_frac: merchant.sell_frac,
+            faction: merchant.faction.clone(),
+            buyback,
+            repair_frac: merchant.repair_frac,
             items,
             refresh_rate_millis: merchant.refresh_rate_millis,
             last_refresh_millis: merchant.last_refresh_millis,
@@ -433,6 +494,15 @@ pub struct EntitySaveState {
 
     #[serde(default)]
     pub(crate) collapsed_groups: Vec<String>,
+
+    #[serde(default)]
+    pub(crate) morale_state: MoraleState,
+
+    #[serde(default)]
+    pub(crate) morale_bonus: i32,
+
+    #[serde(default)]
+    pub(crate) threat_table: ThreatTable,
 }
 
 impl EntitySaveState {
@@ -478,6 +548,7 @@ impl EntitySaveState {
                 attributes: actor.attributes,
                 conversation: actor.conversation.as_ref().map(|c| c.id.to_string()),
                 faction: Some(actor.faction()),
+                affiliation: actor.affiliation.clone(),
                 images: actor.builder_images.clone(),
                 hue: actor.hue,
                 hair_color: actor.hair_color,
@@ -510,6 +581,9 @@ impl EntitySaveState {
             show_portrait: entity.show_portrait(),
             actor_base,
             collapsed_groups: entity.collapsed_groups(),
+            morale_state: entity.morale_state(),
+            morale_bonus: entity.morale_bonus(),
+            threat_table: entity.threat_table().clone(),
         }
     }
 }
@@ -540,6 +614,9 @@ pub struct ActorSaveState {
     pub(crate) quick: Vec<Option<ItemSaveState>>,
     pub(crate) ability_states: HashMap<String, AbilitySaveState>,
     pub(crate) p_stats: PStats,
+
+    #[serde(default)]
+    pub(crate) loadouts: Vec<Loadout>,
 }
 
 impl ActorSaveState {
@@ -578,6 +655,7 @@ impl ActorSaveState {
             quick,
             ability_states,
             p_stats: actor_state.clone_p_stats(),
+            loadouts: actor_state.loadouts().to_vec(),
         }
     }
 }
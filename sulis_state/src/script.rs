@@ -18,7 +18,7 @@
 //! objects.  The documentation for each struct describes the available functions on each
 //! object when interacting with them within a lua script.
 //!
-//! There are currently four kinds of scripts:
+//! There are currently five kinds of scripts:
 //!
 //! 1. AI Scripts:  These are attached to a given actor in their resource definition under `ai`.
 //!    Whenever the parent entity is active, the `ai_action(parent, state)` method is called.
@@ -31,6 +31,9 @@
 //!    targeter.
 //! 4. Item Scripts: Similar to ability scripts, but called when using an item.  The entry point is
 //!    `on_activate(parent, item)`.
+//! 5. Rules Scripts: Optional hooks specified in the module rules.  For example, the
+//!    `attack_resolution_script` is called as `func(attacker, defender, roll)` in place of the
+//!    built in attack roll.  See `ScriptAttackRoll`.
 //!
 //! Since standard Lua methods for referencing other script files will not work, Sulis includes
 //! a simple facility to include the contents of a script into another script.  This is done
@@ -46,6 +49,12 @@ pub use self::area_targeter::AreaTargeter;
 mod module_export;
 pub use self::module_export::ModuleExport;
 
+mod script_ai_plan;
+pub use self::script_ai_plan::ScriptAIPlan;
+
+mod script_attack_roll;
+pub use self::script_attack_roll::ScriptAttackRoll;
+
 mod script_ability;
 pub use self::script_ability::{ScriptAbility, ScriptAbilitySet};
 
@@ -75,6 +84,7 @@ pub use self::script_inventory::{ScriptInventory, ScriptStashItem, ScriptUsableI
 
 mod script_item;
 pub use self::script_item::{ScriptItem, ScriptItemKind};
+pub(crate) use self::script_item::add_another_to_quickbar;
 
 mod script_menu;
 pub use self::script_menu::ScriptMenu;
@@ -106,7 +116,7 @@ use rlua::{self, FromLuaMulti, Function, Lua, ToLuaMulti};
 
 use crate::{ai, EntityState, GameState};
 use sulis_core::{config::Config, util::Point};
-use sulis_module::{Ability, DamageKind, HitKind, Module, QuickSlot};
+use sulis_module::{on_trigger::ScriptData, Ability, DamageKind, HitKind, Module, QuickSlot};
 
 pub type Result<T> = std::result::Result<T, rlua::Error>;
 
@@ -299,6 +309,27 @@ impl Script {
         }
     }
 
+    /// Resolves an attack roll using the specified rules script.  Returns `None`
+    /// if the script could not be run, in which case the built in attack roll
+    /// should be used.
+    pub fn attack_resolution(
+        parent: &Rc<RefCell<EntityState>>,
+        target: &Rc<RefCell<EntityState>>,
+        roll: ScriptAttackRoll,
+        script: &ScriptData,
+    ) -> Option<(HitKind, f32)> {
+        match script_cache::attack_resolution_script(parent, target, roll, script) {
+            Err(e) => {
+                warn!(
+                    "Error in attack resolution script '{}/{}': {}",
+                    script.id, script.func, e
+                );
+                None
+            }
+            Ok(result) => Some(result),
+        }
+    }
+
     pub fn trigger<Arg>(script_id: &str, func: &str, arg: Arg)
     where
         Arg: for<'a> ToLuaMulti<'a>,
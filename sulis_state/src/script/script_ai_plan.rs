@@ -0,0 +1,403 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rlua::{UserData, UserDataMethods};
+
+use crate::script::{Result, ScriptAbility, ScriptEntity, ScriptEntitySet, ScriptUsableItem};
+use crate::{dist, is_within_attack_dist, EntityState, GameState, RangedModifiers};
+use sulis_module::ability::{AIData, AIGroup, AIKind, AIRange};
+use sulis_module::{AccuracyKind, Attack, AttackKind, Module};
+
+const HEAL_FRAC: f32 = 0.5;
+const SHORT_RANGE: f32 = 8.0;
+const MOVE_PENALTY: f32 = 0.02;
+const KILL_BONUS: f32 = 0.5;
+const PRIORITY_BONUS: f32 = 1.0;
+const BUFF_VALUE: f32 = 0.3;
+const DEBUFF_VALUE: f32 = 0.4;
+const DAMAGE_VALUE: f32 = 0.6;
+const SUMMON_VALUE: f32 = 0.5;
+const MULTIPLE_RADIUS: f32 = 3.0;
+const MULTIPLE_HOSTILE_BONUS: f32 = 0.2;
+const MULTIPLE_FRIENDLY_PENALTY: f32 = 0.3;
+
+#[derive(Clone)]
+enum PlanKind {
+    Attack,
+    Ability(ScriptAbility),
+    Item(ScriptUsableItem),
+}
+
+/// A single candidate action for an AI entity, as computed by
+/// `ScriptEntity::plan_actions`.  Plans are scored using the `AIData` of
+/// each ability and item, the expected damage of standard attacks, and the
+/// distance the parent must move to act.  The Lua AI script is responsible
+/// for executing the plan, or may choose to ignore it.
+///
+/// # `kind() -> String`
+/// The kind of action, one of `Attack`, `Ability`, or `Item`.
+///
+/// # `score() -> Float`
+/// The score of this plan.  Higher scores are better.
+///
+/// # `target() -> ScriptEntity`
+/// The target of the action.  For personal abilities and items, this is the parent.
+///
+/// # `ability() -> ScriptAbility`
+/// For `Ability` plans, the ability to activate.  Returns nil otherwise.
+///
+/// # `item() -> ScriptUsableItem`
+/// For `Item` plans, the item to use.  Returns nil otherwise.
+///
+/// # `needs_move() -> Bool`
+/// Whether the parent must first move towards the target before acting.
+///
+/// # `move_dist() -> Float`
+/// The distance to the target the parent must be within in order to act.
+#[derive(Clone)]
+pub struct ScriptAIPlan {
+    kind: PlanKind,
+    score: f32,
+    target: usize,
+    needs_move: bool,
+    move_dist: f32,
+}
+
+impl UserData for ScriptAIPlan {
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_method("kind", |_, plan, ()| {
+            Ok(match plan.kind {
+                PlanKind::Attack => "Attack",
+                PlanKind::Ability(_) => "Ability",
+                PlanKind::Item(_) => "Item",
+            })
+        });
+        methods.add_method("score", |_, plan, ()| Ok(plan.score));
+        methods.add_method("target", |_, plan, ()| Ok(ScriptEntity::new(plan.target)));
+        methods.add_method("ability", |_, plan, ()| match &plan.kind {
+            PlanKind::Ability(ability) => Ok(Some(ability.clone())),
+            _ => Ok(None),
+        });
+        methods.add_method("item", |_, plan, ()| match &plan.kind {
+            PlanKind::Item(item) => Ok(Some(item.clone())),
+            _ => Ok(None),
+        });
+        methods.add_method("needs_move", |_, plan, ()| Ok(plan.needs_move));
+        methods.add_method("move_dist", |_, plan, ()| Ok(plan.move_dist));
+    }
+}
+
+struct Targets {
+    hostiles: Vec<Rc<RefCell<EntityState>>>,
+    friendlies: Vec<Rc<RefCell<EntityState>>>,
+}
+
+/// Enumerates and scores all candidate actions for the parent against the
+/// specified targets, returning them sorted from best to worst.  Only
+/// actions with a positive score are returned.
+pub fn plan_actions(
+    parent: &Rc<RefCell<EntityState>>,
+    targets: &ScriptEntitySet,
+) -> Result<Vec<ScriptAIPlan>> {
+    let targets = sort_targets(parent, targets)?;
+    let mut plans = Vec::new();
+
+    plan_attacks(parent, &targets, &mut plans);
+
+    let abilities: Vec<_> = {
+        let parent = parent.borrow();
+        parent
+            .actor
+            .ability_states
+            .keys()
+            .filter(|id| parent.actor.can_activate(id))
+            .filter_map(|id| Module::ability(id))
+            .filter(|ability| ability.active.is_some())
+            .map(|ability| ScriptAbility::from(&ability))
+            .collect()
+    };
+
+    for ability in abilities {
+        let ai_data = ability.ai_data().clone();
+        plan_use(
+            parent,
+            &targets,
+            &ai_data,
+            PlanKind::Ability(ability),
+            &mut plans,
+        );
+    }
+
+    for item in ScriptUsableItem::list(&ScriptEntity::from(parent))? {
+        let ai_data = item.ai_data().clone();
+        plan_use(parent, &targets, &ai_data, PlanKind::Item(item), &mut plans);
+    }
+
+    plans.retain(|plan| plan.score > 0.0);
+    plans.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    Ok(plans)
+}
+
+fn sort_targets(parent: &Rc<RefCell<EntityState>>, targets: &ScriptEntitySet) -> Result<Targets> {
+    let area = GameState::area_state();
+    let area = area.borrow();
+
+    let mut hostiles = Vec::new();
+    let mut friendlies = vec![Rc::clone(parent)];
+    for index in targets.indices.iter() {
+        let entity = match index {
+            None => continue,
+            Some(index) => ScriptEntity::new(*index).try_unwrap()?,
+        };
+
+        if Rc::ptr_eq(&entity, parent) {
+            continue;
+        }
+
+        let parent = parent.borrow();
+        let target = entity.borrow();
+        if target.actor.is_dead() || !area.has_visibility(&parent, &target) {
+            continue;
+        }
+
+        if parent.is_hostile(&target) {
+            hostiles.push(Rc::clone(&entity));
+        } else if parent.is_friendly(&target) {
+            friendlies.push(Rc::clone(&entity));
+        }
+    }
+
+    Ok(Targets {
+        hostiles,
+        friendlies,
+    })
+}
+
+fn plan_attacks(
+    parent: &Rc<RefCell<EntityState>>,
+    targets: &Targets,
+    plans: &mut Vec<ScriptAIPlan>,
+) {
+    let parent_ref = parent.borrow();
+    let stats = &parent_ref.actor.stats;
+    if stats.attack_disabled || !parent_ref.actor.has_ap_to_attack() {
+        return;
+    }
+
+    for target in targets.hostiles.iter() {
+        let target = target.borrow();
+        let damage = expected_damage(&parent_ref, &target);
+        let hp = target.actor.hp().max(1) as f32;
+
+        let mut score = (damage / hp).min(1.0);
+        if damage >= hp {
+            score += KILL_BONUS;
+        }
+        score += parent_ref.threat_table().get(target.index()) / stats.max_hp.max(1) as f32;
+
+        let needs_move = !is_within_attack_dist(&parent_ref, &*target);
+        let move_dist = stats.attack_distance();
+        if let Some(score) = apply_move(&parent_ref, &target, score, needs_move, move_dist) {
+            plans.push(ScriptAIPlan {
+                kind: PlanKind::Attack,
+                score,
+                target: target.index(),
+                needs_move,
+                move_dist,
+            });
+        }
+    }
+}
+
+fn plan_use(
+    parent: &Rc<RefCell<EntityState>>,
+    targets: &Targets,
+    ai_data: &AIData,
+    kind: PlanKind,
+    plans: &mut Vec<ScriptAIPlan>,
+) {
+    let parent = parent.borrow();
+    let priority = 1.0 + PRIORITY_BONUS / (1 + ai_data.priority) as f32;
+
+    let candidates = match ai_data.kind {
+        AIKind::Special => return,
+        AIKind::Damage | AIKind::Debuff | AIKind::Summon => &targets.hostiles,
+        AIKind::Heal | AIKind::Buff => &targets.friendlies,
+    };
+
+    for target in candidates.iter() {
+        let target = target.borrow();
+        if ai_data.range == AIRange::Personal && target.index() != parent.index() {
+            continue;
+        }
+
+        let mut score = match ai_data.kind {
+            AIKind::Special => 0.0,
+            AIKind::Damage => DAMAGE_VALUE,
+            AIKind::Debuff => DEBUFF_VALUE,
+            AIKind::Summon => SUMMON_VALUE,
+            AIKind::Buff => BUFF_VALUE,
+            AIKind::Heal => {
+                let frac = target.actor.hp() as f32 / target.actor.stats.max_hp.max(1) as f32;
+                if frac >= HEAL_FRAC {
+                    continue;
+                }
+                1.0 - frac
+            }
+        };
+
+        if ai_data.group == AIGroup::Multiple {
+            score += multiple_bonus(&target, targets);
+        }
+        score *= priority;
+
+        let move_dist = match ai_data.range {
+            AIRange::Personal => 0.0,
+            AIRange::Touch => parent.actor.stats.touch_distance(),
+            AIRange::Attack => parent.actor.stats.attack_distance(),
+            AIRange::Short => SHORT_RANGE,
+            AIRange::Visible => {
+                let area = GameState::area_state();
+                let vis_dist = area.borrow().area.area.vis_dist as f32;
+                vis_dist - 1.0
+            }
+        };
+        let needs_move = ai_data.range != AIRange::Personal && dist(&*parent, &*target) > move_dist;
+
+        if let Some(score) = apply_move(&parent, &target, score, needs_move, move_dist) {
+            plans.push(ScriptAIPlan {
+                kind: kind.clone(),
+                score,
+                target: target.index(),
+                needs_move,
+                move_dist,
+            });
+        }
+    }
+}
+
+/// Area abilities are more valuable when they hit many hostiles, and less
+/// valuable when they also hit friendlies
+fn multiple_bonus(target: &EntityState, targets: &Targets) -> f32 {
+    let count = |list: &Vec<Rc<RefCell<EntityState>>>| {
+        list.iter()
+            .filter(|other| {
+                let other = other.borrow();
+                other.index() != target.index() && dist(&*other, target) <= MULTIPLE_RADIUS
+            })
+            .count() as f32
+    };
+
+    if targets
+        .hostiles
+        .iter()
+        .any(|e| e.borrow().index() == target.index())
+    {
+        count(&targets.hostiles) * MULTIPLE_HOSTILE_BONUS
+            - count(&targets.friendlies) * MULTIPLE_FRIENDLY_PENALTY
+    } else {
+        count(&targets.friendlies) * MULTIPLE_HOSTILE_BONUS
+            - count(&targets.hostiles) * MULTIPLE_FRIENDLY_PENALTY
+    }
+}
+
+/// Reduces the score by the distance that must be moved to act, or returns
+/// None if a move is needed but the parent is unable to move
+fn apply_move(
+    parent: &EntityState,
+    target: &EntityState,
+    score: f32,
+    needs_move: bool,
+    move_dist: f32,
+) -> Option<f32> {
+    if !needs_move {
+        return Some(score);
+    }
+
+    if !parent.can_move() {
+        return None;
+    }
+
+    let to_move = (dist(parent, target) - move_dist).max(0.0);
+    Some(score - to_move * MOVE_PENALTY)
+}
+
+/// Estimates the damage from a single standard attack by the parent against
+/// the target, including hit chance, terrain modifiers, and armor
+fn expected_damage(parent: &EntityState, target: &EntityState) -> f32 {
+    let stats = &parent.actor.stats;
+    let target_stats = &target.actor.stats;
+
+    let mut total = 0.0;
+    for attack in stats.attacks.iter() {
+        let (accuracy, defense) = accuracy_and_defense(parent, target, attack);
+
+        let chance = |threshold: i32| -> f32 {
+            ((100 - (threshold + defense - accuracy)) as f32 / 100.0).clamp(0.0, 1.0)
+        };
+
+        let bonuses = &attack.bonuses;
+        let hit = chance(stats.hit_threshold + bonuses.hit_threshold);
+        let graze = (chance(stats.graze_threshold + bonuses.graze_threshold) - hit).max(0.0);
+        let crit = if target_stats.crit_immunity {
+            0.0
+        } else {
+            hit * (stats.crit_chance + bonuses.crit_chance) as f32 / 100.0
+        };
+
+        let hit_mult = stats.hit_multiplier + bonuses.hit_multiplier;
+        let graze_mult = stats.graze_multiplier + bonuses.graze_multiplier;
+        let crit_mult = stats.crit_multiplier + bonuses.crit_multiplier;
+        let multiplier = hit * hit_mult + graze * graze_mult + crit * (crit_mult - hit_mult);
+
+        let average = (attack.damage.min() + attack.damage.max()) as f32 / 2.0;
+        let armor = target_stats.armor.base() as f32;
+        total += (average * multiplier - armor).max(0.0);
+    }
+
+    total
+}
+
+fn accuracy_and_defense(parent: &EntityState, target: &EntityState, attack: &Attack) -> (i32, i32) {
+    let stats = &parent.actor.stats;
+    let target_stats = &target.actor.stats;
+    let bonuses = &attack.bonuses;
+
+    let accuracy = |kind: AccuracyKind| match kind {
+        AccuracyKind::Melee => stats.melee_accuracy + bonuses.melee_accuracy,
+        AccuracyKind::Ranged => stats.ranged_accuracy + bonuses.ranged_accuracy,
+        AccuracyKind::Spell => stats.spell_accuracy + bonuses.spell_accuracy,
+    };
+
+    match attack.kind {
+        AttackKind::Melee { .. } => (accuracy(AccuracyKind::Melee), target_stats.defense),
+        AttackKind::Ranged { .. } => {
+            let modifiers = RangedModifiers::compute(parent, target);
+            (
+                accuracy(AccuracyKind::Ranged) + modifiers.accuracy(),
+                target_stats.defense + modifiers.defense(),
+            )
+        }
+        AttackKind::Fortitude { accuracy: kind } => (accuracy(kind), target_stats.fortitude),
+        AttackKind::Reflex { accuracy: kind } => (accuracy(kind), target_stats.reflex),
+        AttackKind::Will { accuracy: kind } => (accuracy(kind), target_stats.will),
+        AttackKind::Dummy => (0, 0),
+    }
+}
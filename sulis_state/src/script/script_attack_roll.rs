@@ -0,0 +1,150 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use rlua::{UserData, UserDataMethods};
+
+use sulis_module::{AccuracyKind, AttackBonuses, AttackKind, HitFlags, HitKind, StatList};
+
+/// The inputs to an attack roll, passed to the rules `attack_resolution_script`
+/// along with the attacking and defending `ScriptEntity`.  The script function
+/// is called as `func(attacker, defender, roll)` and must return the hit kind
+/// as a String, one of `miss`, `graze`, `hit`, or `crit`, and optionally a
+/// damage multiplier as a Float.  If the multiplier is not returned, the
+/// attacker's multiplier for that hit kind is used.
+///
+/// # `kind() -> String`
+/// The kind of attack, one of `Melee`, `Ranged`, `Fortitude`, `Reflex`, or `Will`.
+///
+/// # `accuracy_kind() -> String`
+/// The kind of accuracy used by the attack, one of `Melee`, `Ranged`, or `Spell`.
+///
+/// # `accuracy() -> Int`
+/// The attacker's total accuracy for this attack, including any attack bonuses.
+///
+/// # `defense() -> Int`
+/// The defender's defense value that this attack is rolled against.
+///
+/// # `crit_chance() -> Int`
+/// The attacker's total crit chance for this attack.
+///
+/// # `hit_threshold() -> Int`
+/// The attacker's total hit threshold for this attack.
+///
+/// # `graze_threshold() -> Int`
+/// The attacker's total graze threshold for this attack.
+///
+/// # `graze_multiplier() -> Float`
+/// The damage multiplier the attacker applies on a graze.
+///
+/// # `hit_multiplier() -> Float`
+/// The damage multiplier the attacker applies on a hit.
+///
+/// # `crit_multiplier() -> Float`
+/// The damage multiplier the attacker applies on a crit.
+///
+/// # `is_crit_immune() -> Bool`
+/// Whether the defender is immune to critical hits.
+///
+/// # `is_flanking() -> Bool`
+/// Whether the attacker is flanking the defender.
+///
+/// # `is_sneak_attack() -> Bool`
+/// Whether this attack is a sneak attack.
+#[derive(Clone)]
+pub struct ScriptAttackRoll {
+    kind: &'static str,
+    accuracy_kind: &'static str,
+    accuracy: i32,
+    defense: i32,
+    crit_chance: i32,
+    hit_threshold: i32,
+    graze_threshold: i32,
+    graze_multiplier: f32,
+    hit_multiplier: f32,
+    crit_multiplier: f32,
+    crit_immunity: bool,
+    flags: HitFlags,
+}
+
+impl ScriptAttackRoll {
+    pub fn new(
+        stats: &StatList,
+        kind: &AttackKind,
+        accuracy_kind: AccuracyKind,
+        defense: i32,
+        crit_immunity: bool,
+        bonuses: &AttackBonuses,
+        flags: HitFlags,
+    ) -> ScriptAttackRoll {
+        let kind = match kind {
+            AttackKind::Melee { .. } => "Melee",
+            AttackKind::Ranged { .. } => "Ranged",
+            AttackKind::Fortitude { .. } => "Fortitude",
+            AttackKind::Reflex { .. } => "Reflex",
+            AttackKind::Will { .. } => "Will",
+            AttackKind::Dummy => "Dummy",
+        };
+
+        let (accuracy_kind, accuracy) = match accuracy_kind {
+            AccuracyKind::Melee => ("Melee", stats.melee_accuracy + bonuses.melee_accuracy),
+            AccuracyKind::Ranged => ("Ranged", stats.ranged_accuracy + bonuses.ranged_accuracy),
+            AccuracyKind::Spell => ("Spell", stats.spell_accuracy + bonuses.spell_accuracy),
+        };
+
+        ScriptAttackRoll {
+            kind,
+            accuracy_kind,
+            accuracy,
+            defense,
+            crit_chance: stats.crit_chance + bonuses.crit_chance,
+            hit_threshold: stats.hit_threshold + bonuses.hit_threshold,
+            graze_threshold: stats.graze_threshold + bonuses.graze_threshold,
+            graze_multiplier: stats.graze_multiplier + bonuses.graze_multiplier,
+            hit_multiplier: stats.hit_multiplier + bonuses.hit_multiplier,
+            crit_multiplier: stats.crit_multiplier + bonuses.crit_multiplier,
+            crit_immunity,
+            flags,
+        }
+    }
+
+    /// Returns the default damage multiplier for the specified hit kind
+    pub fn multiplier(&self, hit_kind: HitKind) -> f32 {
+        match hit_kind {
+            HitKind::Miss => 0.0,
+            HitKind::Graze => self.graze_multiplier,
+            HitKind::Hit | HitKind::Auto => self.hit_multiplier,
+            HitKind::Crit => self.crit_multiplier,
+        }
+    }
+}
+
+impl UserData for ScriptAttackRoll {
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_method("kind", |_, roll, ()| Ok(roll.kind));
+        methods.add_method("accuracy_kind", |_, roll, ()| Ok(roll.accuracy_kind));
+        methods.add_method("accuracy", |_, roll, ()| Ok(roll.accuracy));
+        methods.add_method("defense", |_, roll, ()| Ok(roll.defense));
+        methods.add_method("crit_chance", |_, roll, ()| Ok(roll.crit_chance));
+        methods.add_method("hit_threshold", |_, roll, ()| Ok(roll.hit_threshold));
+        methods.add_method("graze_threshold", |_, roll, ()| Ok(roll.graze_threshold));
+        methods.add_method("graze_multiplier", |_, roll, ()| Ok(roll.graze_multiplier));
+        methods.add_method("hit_multiplier", |_, roll, ()| Ok(roll.hit_multiplier));
+        methods.add_method("crit_multiplier", |_, roll, ()| Ok(roll.crit_multiplier));
+        methods.add_method("is_crit_immune", |_, roll, ()| Ok(roll.crit_immunity));
+        methods.add_method("is_flanking", |_, roll, ()| Ok(roll.flags.flanking));
+        methods.add_method("is_sneak_attack", |_, roll, ()| Ok(roll.flags.sneak_attack));
+    }
+}
@@ -22,11 +22,12 @@ use std::time::{Duration, Instant};
 use rlua::{self, FromLuaMulti, ToLua, ToLuaMulti};
 
 use crate::script::{
-    Result, ScriptAbility, ScriptEntity, ScriptEntitySet, ScriptItem, ScriptItemKind, ScriptState,
+    Result, ScriptAbility, ScriptAttackRoll, ScriptEntity, ScriptEntitySet, ScriptItem,
+    ScriptItemKind, ScriptState,
 };
 use crate::{ai, EntityState};
 use sulis_core::util::Point;
-use sulis_module::{ai::AITemplate, Ability, Item, Module};
+use sulis_module::{ai::AITemplate, on_trigger::ScriptData, Ability, HitKind, Item, Module};
 
 thread_local! {
     static SCRIPT_CACHE: RefCell<HashMap<String, Rc<ScriptState>>> = RefCell::new(HashMap::new());
@@ -303,6 +304,34 @@ where
     exec_func(script_id, func, args)
 }
 
+pub fn attack_resolution_script(
+    parent: &Rc<RefCell<EntityState>>,
+    target: &Rc<RefCell<EntityState>>,
+    roll: ScriptAttackRoll,
+    script: &ScriptData,
+) -> Result<(HitKind, f32)> {
+    let default_roll = roll.clone();
+    let parent = ScriptEntity::from(parent);
+    let target = ScriptEntity::from(target);
+
+    let (kind, multiplier): (String, Option<f32>) =
+        exec_func(&script.id, &script.func, (parent, target, roll))?;
+
+    let kind = match kind.to_lowercase().parse() {
+        Ok(HitKind::Auto) | Err(_) => {
+            return Err(rlua::Error::FromLuaConversionError {
+                from: "String",
+                to: "HitKind",
+                message: Some(format!("Invalid hit kind '{}'", kind)),
+            });
+        }
+        Ok(kind) => kind,
+    };
+
+    let multiplier = multiplier.unwrap_or_else(|| default_roll.multiplier(kind));
+    Ok((kind, multiplier))
+}
+
 fn get_script_data_from_entity(entity: &Rc<RefCell<EntityState>>) -> Result<Rc<AITemplate>> {
     let entity = entity.borrow();
     let id = entity.unique_id();
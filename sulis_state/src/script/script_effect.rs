@@ -119,6 +119,7 @@ impl UserData for ScriptMenuSelection {
 /// `reflex`, `will`, `concealment`, `concealment_ignore`, `crit_chance`,
 /// `hit_threshold`, `graze_threshold`, `graze_multiplier`, `hit_multiplier`,
 /// `crit_multiplier`, `movement_rate`, `move_anim_rate`, `attack_cost`, `ability_ap_cost`,
+/// `caster_level`, `flanking_angle`, `threat`,
 /// `hidden`, `free_ability_group_use`, abilities_disabled`, `move_disabled`,
 /// `attack_disabled`, `flanked_immunity`, `sneak_attack_immunity`, `crit_immunity`
 ///
@@ -185,6 +186,7 @@ fn check_for_bonus(effect: &ScriptAppliedEffect, kind: String) -> bool {
         "move_anim_rate" => MoveAnimRate(0.0),
         "attack_cost" => AttackCost(0),
         "caster_level" => CasterLevel(0),
+        "threat" => Threat(0),
         "flanking_angle" => FlankingAngle(0),
         "hidden" => Hidden,
         "free_ability_group_use" => FreeAbilityGroupUse,
@@ -358,7 +360,7 @@ impl UserData for ScriptAppliedEffect {
 /// `reflex`, `will`, `concealment`, `concealment_ignore`, `crit_chance`,
 /// `hit_threshold`, `graze_threshold`, `graze_multiplier`, `hit_multiplier`,
 /// `crit_multiplier`, `movement_rate`, `move_anim_rate`, `attack_cost`, `ability_ap_cost`,
-/// `caster_level`, `flanking_angle`
+/// `caster_level`, `flanking_angle`, `threat`
 ///
 /// # `add_damage(min: Float, max: Float, ap: Float (Optional), when: String (Optional))`
 /// Adds a damage bonus of the specified amount (from `min` to `max` randomly, with `ap`
@@ -808,6 +810,7 @@ fn add_num_bonus(
         "move_anim_rate" => MoveAnimRate(amount),
         "attack_cost" => AttackCost(amount_int),
         "caster_level" => CasterLevel(amount_int),
+        "threat" => Threat(amount_int),
         "flanking_angle" => FlankingAngle(amount_int),
         _ => {
             warn!("Attempted to add num bonus with invalid type '{}'", name);
@@ -25,6 +25,7 @@ use rlua::{self, Context, UserData, UserDataMethods};
 use crate::{ability_state::DisabledReason, dist, is_within_attack_dist, is_within_touch_dist};
 use crate::{ai, animation, entity_attack_handler, script::*, AreaFeedbackText};
 use crate::{area_feedback_text::ColorKind, EntityState, GameState, Location};
+use crate::{morale, threat_table, MoraleState};
 use sulis_core::config::Config;
 use sulis_core::resource::ResourceSet;
 use sulis_core::util::ExtInt;
@@ -104,6 +105,26 @@ use sulis_module::{
 /// `Hostile`, `Neutral`, or `Friendly`.  Hostiles will attack the player and
 /// friendlies on sight, but will not engage neutrals.
 ///
+/// # `morale() -> Int`
+/// Returns the current morale of this entity.  Morale starts at the `MoraleBase`
+/// param of the entity's AI template and drops as members of its AI group are
+/// lost and as it loses hit points.  When it falls below the `MoraleFleeThreshold`
+/// param, the entity will flee or surrender.
+///
+/// # `add_morale(amount: Int)`
+/// Adds the specified `amount`, which may be negative, to this entity's morale.
+/// This value persists as part of the entity's state.
+///
+/// # `morale_state() -> String`
+/// Returns the current morale state of this entity, one of `Steady`, `Fleeing`,
+/// or `Surrendered`.
+///
+/// # `set_morale_state(state: String)`
+/// Sets the morale state of this entity to one of `Steady`, `Fleeing`, or
+/// `Surrendered`.  Fleeing entities will attempt to run away from hostiles on
+/// their turn and leave the area once out of sight of the party.  Surrendering
+/// entities become neutral and no longer take part in combat.
+///
 /// # `set_flag(flag: String, value: String (Optional))`
 /// Sets a `flag` to be stored on this entity.  This value will persist as part of the
 /// save game and can be used to store custom state.  If the value is not specified,
@@ -271,6 +292,13 @@ use sulis_module::{
 /// Computes the euclidean distance to the specified `point`, in tiles.  Point is
 /// a table of the form `{x: x_coord, y: y_coord}`
 ///
+/// # `plan_actions(targets: ScriptEntitySet (Optional)) -> Table`
+/// Scores every action this entity could take this turn - standard attacks, abilities
+/// that can currently be activated, and usable items - against the visible entities
+/// in `targets`, or all entities in the area if not specified.  Returns a table of
+/// `ScriptAIPlan`, sorted from best to worst.  AI scripts may execute the first plan,
+/// or inspect the list and choose a different action.
+///
 /// # `has_ap_to_attack() -> Bool`
 /// Returns true if this entity has enough AP to issue a single attack, false otherwise.
 ///
@@ -334,9 +362,26 @@ use sulis_module::{
 /// based on this entity's armor.  The damage is rolled randomly between `min_damage` and
 /// `max_damage`, with the specified (`ap`) amount of armor piercing.
 ///
-/// # `heal_damage(amount: Float)`
+/// # `heal_damage(amount: Float, healer: ScriptEntity (Optional))`
 /// Adds the specified number of hit points to this entity.  The entity's maximum hit
-/// points cannot be exceeded in this way.
+/// points cannot be exceeded in this way.  If a `healer` is specified, entities hostile
+/// to the healer that are tracking threat from this entity gain threat against the healer.
+///
+/// # `threat_table() -> Table`
+/// Returns a table of all living entities that have generated threat against this
+/// entity, sorted from highest to lowest threat.  Each entry is a table of the form
+/// `{entity: ScriptEntity, threat: Float}`.  Threat is generated by dealing damage,
+/// healing enemies, and by `add_threat`, and decays each round.
+///
+/// # `threat(target: ScriptEntity) -> Float`
+/// Returns the threat the `target` has generated against this entity.
+///
+/// # `add_threat(source: ScriptEntity, amount: Float)`
+/// Adds the specified `amount` of threat from `source` against this entity, modified
+/// by the source's `threat` bonus.  This can be used for taunt effects.
+///
+/// # `clear_threat()`
+/// Removes all threat against this entity.
 ///
 /// # `add_class_stat(stat: String, amount: Float)`
 /// Adds the specified amount of the specified stat for this entity.  The entity's maximum
@@ -699,6 +744,88 @@ impl UserData for ScriptEntity {
             Ok(())
         });
 
+        methods.add_method("threat_table", |lua, entity, ()| {
+            let parent = entity.try_unwrap()?;
+            let mgr = GameState::turn_manager();
+
+            let mut entries: Vec<(usize, f32)> = Vec::new();
+            for (index, threat) in parent.borrow().threat_table().iter() {
+                match mgr.borrow().entity_checked(*index) {
+                    None => continue,
+                    Some(other) => {
+                        if other.borrow().actor.is_dead() {
+                            continue;
+                        }
+                    }
+                }
+                entries.push((*index, *threat));
+            }
+            entries.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+            let table = lua.create_table()?;
+            for (i, (index, threat)) in entries.into_iter().enumerate() {
+                let entry = lua.create_table()?;
+                entry.set("entity", ScriptEntity::new(index))?;
+                entry.set("threat", threat)?;
+                table.set(i + 1, entry)?;
+            }
+
+            Ok(table)
+        });
+
+        methods.add_method("threat", |_, entity, target: ScriptEntity| {
+            let parent = entity.try_unwrap()?;
+            let target = target.try_unwrap()?;
+            let threat = parent.borrow().threat_table().get(target.borrow().index());
+            Ok(threat)
+        });
+
+        methods.add_method(
+            "add_threat",
+            |_, entity, (source, amount): (ScriptEntity, f32)| {
+                let parent = entity.try_unwrap()?;
+                let source = source.try_unwrap()?;
+                threat_table::add(&parent, &source, amount);
+                Ok(())
+            },
+        );
+
+        methods.add_method("clear_threat", |_, entity, ()| {
+            let parent = entity.try_unwrap()?;
+            parent.borrow_mut().threat_table_mut().clear();
+            Ok(())
+        });
+
+        methods.add_method("morale", |_, entity, ()| {
+            let entity = entity.try_unwrap()?;
+            let mgr = GameState::turn_manager();
+            let morale = morale::morale(&entity.borrow(), &mgr.borrow());
+            Ok(morale)
+        });
+
+        methods.add_method("add_morale", |_, entity, amount: i32| {
+            let entity = entity.try_unwrap()?;
+            entity.borrow_mut().add_morale_bonus(amount);
+            Ok(())
+        });
+
+        methods.add_method("morale_state", |_, entity, ()| {
+            let entity = entity.try_unwrap()?;
+            let state = entity.borrow().morale_state();
+            Ok(state.to_str())
+        });
+
+        methods.add_method("set_morale_state", |_, entity, state: String| {
+            let entity = entity.try_unwrap()?;
+
+            match MoraleState::option_from_str(&state) {
+                None => warn!("Invalid morale state '{}' in script", state),
+                Some(state) => morale::set_state(&entity, state),
+            }
+
+            Ok(())
+        });
+
         methods.add_method("get_num_flag", |_, entity, flag: String| {
             let entity = entity.try_unwrap()?;
             let val = entity.borrow().get_num_flag(&flag);
@@ -1049,6 +1176,18 @@ impl UserData for ScriptEntity {
             },
         );
 
+        methods.add_method(
+            "plan_actions",
+            |lua, entity, targets: Option<ScriptEntitySet>| {
+                let targets = match targets {
+                    None => self::targets(lua, entity, ())?,
+                    Some(targets) => targets,
+                };
+                let parent = entity.try_unwrap()?;
+                script_ai_plan::plan_actions(&parent, &targets)
+            },
+        );
+
         methods.add_method("has_ap_to_attack", |_, entity, ()| {
             let parent = entity.try_unwrap()?;
             let result = parent.borrow().actor.has_ap_to_attack();
@@ -1313,19 +1452,25 @@ impl UserData for ScriptEntity {
             },
         );
 
-        methods.add_method("heal_damage", |_, entity, amount: f32| {
-            let amount = amount as u32;
-            let parent = entity.try_unwrap()?;
-            parent.borrow_mut().actor.add_hp(amount);
-            let area_state = GameState::area_state();
+        methods.add_method(
+            "heal_damage",
+            |_, entity, (amount, healer): (f32, Option<ScriptEntity>)| {
+                let amount = amount as u32;
+                let parent = entity.try_unwrap()?;
+                parent.borrow_mut().actor.add_hp(amount);
+                if let Some(healer) = healer {
+                    threat_table::add_healing(&healer.try_unwrap()?, &parent, amount);
+                }
+                let area_state = GameState::area_state();
 
-            let mut feedback =
-                AreaFeedbackText::with_target(&parent.borrow(), &area_state.borrow());
-            feedback.add_entry(format!("{}", amount), ColorKind::Heal);
-            area_state.borrow_mut().add_feedback_text(feedback);
+                let mut feedback =
+                    AreaFeedbackText::with_target(&parent.borrow(), &area_state.borrow());
+                feedback.add_entry(format!("{}", amount), ColorKind::Heal);
+                area_state.borrow_mut().add_feedback_text(feedback);
 
-            Ok(())
-        });
+                Ok(())
+            },
+        );
 
         methods.add_method(
             "add_class_stat",
@@ -1737,6 +1882,7 @@ fn create_stats_table<'a>(
 
     stats.set("level", parent.actor.actor.total_level)?;
     stats.set("caster_level", src.caster_level)?;
+    stats.set("threat", src.threat)?;
     stats.set("bonus_reach", src.bonus_reach)?;
     stats.set("bonus_range", src.bonus_range)?;
     stats.set("max_hp", src.max_hp)?;
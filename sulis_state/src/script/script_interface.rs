@@ -20,10 +20,10 @@ use std::rc::Rc;
 use rlua::{self, UserData, UserDataMethods};
 
 use crate::script::*;
-use crate::{animation::Anim, AreaState, EntityState, GameState, Location};
+use crate::{animation::Anim, crafting, AreaState, EntityState, GameState, Location, QuestEvent};
 use sulis_core::{config::Config};
 use sulis_module::on_trigger::{self, QuestEntryState};
-use sulis_module::{Faction, ItemState, Module, OnTrigger, Time};
+use sulis_module::{Faction, ItemState, Module, OnTrigger, Recipe, Time};
 
 /// The ScriptInterface, accessible in all Lua scripts as the global `game`.
 /// The following methods are available on this object (documentation WIP):
@@ -208,6 +208,10 @@ use sulis_module::{Faction, ItemState, Module, OnTrigger, Time};
 /// # `get_quest_entry_state(quest: String, entry: String)`
 /// Returns the current `state` of the specified `entry` in the given `quest`.
 ///
+/// # `add_quest_counter(id: String, amount: Int (Optional))`
+/// Advances any active `counter` quest objectives with the specified `id` by `amount`,
+/// or by 1 if `amount` is not specified.
+///
 /// # `set_world_map_location_visible(location: String, visible: Bool)`
 /// Sets the specified `location` in the world map to the specified `visible`.  The
 /// location must be defined in the world_map section of the campaign definition file.
@@ -318,6 +322,41 @@ use sulis_module::{Faction, ItemState, Module, OnTrigger, Time};
 /// Adds the specified number of coins to the party.  Note that this value is divided by
 /// the item_value_display_factor to get the displayed coinage.
 ///
+/// # `merchant_reputation(id: String) -> Int`
+/// Returns the party's reputation with the merchant faction, or merchant if it has
+/// no faction, with the specified `id`.  Reputation starts at zero.
+///
+/// # `set_merchant_reputation(id: String, value: Int)`
+/// Sets the party's reputation with the specified merchant faction or merchant.  The value
+/// is clamped to the range given by the merchant_pricing section of the module rules.
+/// Higher reputation results in better buy and sell prices.
+///
+/// # `add_merchant_reputation(id: String, amount: Int)`
+/// Adds the specified `amount`, which may be negative, to the party's reputation with
+/// the merchant faction or merchant.
+///
+/// # `faction_reputation(id: String) -> Int`
+/// Returns the party's reputation with the named campaign faction with the specified
+/// `id`.  Merchant factions with the same `id` share this reputation.
+///
+/// # `set_faction_reputation(id: String, value: Int)`
+/// Sets the party's reputation with the specified campaign faction.  Actors affiliated
+/// with the faction become hostile or neutral based on the faction's thresholds.
+///
+/// # `add_faction_reputation(id: String, amount: Int)`
+/// Adds the specified `amount`, which may be negative, to the party's reputation with
+/// the campaign faction.
+///
+/// # `approval(id: String) -> Int`
+/// Returns the approval of the party by the companion with the specified actor `id`.
+///
+/// # `set_approval(id: String, value: Int)`
+/// Sets the companion's approval of the party.  Any approval thresholds for the
+/// companion reached for the first time fire their triggers.
+///
+/// # `add_approval(id: String, amount: Int)`
+/// Adds the specified `amount`, which may be negative, to the companion's approval.
+///
 /// # `find_party_item(id: String, adjective: String (Optional, up to 3)) -> ScriptStashItem`
 /// Returns a ScriptStashItem representing the first item in the party stash found
 /// matching the specified ID and all specified `adjective`s.  If no such item is found,
@@ -336,6 +375,16 @@ use sulis_module::{Faction, ItemState, Module, OnTrigger, Time};
 /// Adds the specified amount of XP to the party.  Each current party member is given
 /// this amount of XP.
 ///
+/// # `can_craft(recipe: String, crafter: ScriptEntity) -> Bool`
+/// Returns true if the `crafter` is able to craft the recipe with the specified ID,
+/// meaning the party stash holds all ingredients and tools, the crafter meets all
+/// prereqs and class stat costs, and any required props are nearby.
+///
+/// # `craft(recipe: String, crafter: ScriptEntity, target: ScriptStashItem (Optional)) -> Bool`
+/// Crafts the recipe with the specified ID, consuming its ingredients from the party
+/// stash.  Recipes that add an adjective to an existing item require a `target` item
+/// from the party stash.  Returns true if the item was successfully crafted.
+///
 /// # `transition_party_to(x: Int, y: Int, area: String (Optional))`
 /// Moves the party to the specified coordinates within the specified area.  If an area is not
 /// specified, the transition occurs within the current area.  If the area
@@ -641,6 +690,15 @@ impl UserData for ScriptInterface {
             },
         );
 
+        methods.add_method(
+            "add_quest_counter",
+            |_, _, (id, amount): (String, Option<u32>)| {
+                let amount = amount.unwrap_or(1);
+                GameState::add_quest_event(QuestEvent::Counter { id, amount });
+                Ok(())
+            },
+        );
+
         methods.add_method(
             "set_world_map_location_visible",
             |_, _, (location, vis): (String, bool)| {
@@ -949,6 +1007,58 @@ impl UserData for ScriptInterface {
             Ok(())
         });
 
+        methods.add_method("merchant_reputation", |_, _, id: String| {
+            Ok(GameState::merchant_reputation(&id))
+        });
+
+        methods.add_method(
+            "set_merchant_reputation",
+            |_, _, (id, value): (String, i32)| {
+                GameState::set_merchant_reputation(&id, value);
+                Ok(())
+            },
+        );
+
+        methods.add_method(
+            "add_merchant_reputation",
+            |_, _, (id, amount): (String, i32)| {
+                GameState::add_merchant_reputation(&id, amount);
+                Ok(())
+            },
+        );
+
+        methods.add_method("faction_reputation", |_, _, id: String| {
+            Ok(GameState::faction_reputation(&id))
+        });
+
+        methods.add_method(
+            "set_faction_reputation",
+            |_, _, (id, value): (String, i32)| {
+                GameState::set_faction_reputation(&id, value);
+                Ok(())
+            },
+        );
+
+        methods.add_method(
+            "add_faction_reputation",
+            |_, _, (id, amount): (String, i32)| {
+                GameState::add_faction_reputation(&id, amount);
+                Ok(())
+            },
+        );
+
+        methods.add_method("approval", |_, _, id: String| Ok(GameState::approval(&id)));
+
+        methods.add_method("set_approval", |_, _, (id, value): (String, i32)| {
+            GameState::set_approval(&id, value);
+            Ok(())
+        });
+
+        methods.add_method("add_approval", |_, _, (id, amount): (String, i32)| {
+            GameState::add_approval(&id, amount);
+            Ok(())
+        });
+
         methods.add_method("find_party_item", |_, _, (id, adj1, adj2, adj3):
                            (String, Option<String>, Option<String>, Option<String>)| {
 
@@ -1009,6 +1119,25 @@ impl UserData for ScriptInterface {
             Ok(())
         });
 
+        methods.add_method("can_craft", |_, _, (id, crafter): (String, ScriptEntity)| {
+            let recipe = get_recipe(&id)?;
+            let crafter = crafter.try_unwrap()?;
+            Ok(crafting::can_craft(&recipe, &crafter))
+        });
+
+        methods.add_method(
+            "craft",
+            |_, _, (id, crafter, target): (String, ScriptEntity, Option<ScriptStashItem>)| {
+                let recipe = get_recipe(&id)?;
+                let crafter = crafter.try_unwrap()?;
+                let target = match target {
+                    None => None,
+                    Some(target) => Some(target.unwrap_index()?),
+                };
+                Ok(crafting::craft(&recipe, &crafter, target))
+            },
+        );
+
         methods.add_method(
             "transition_party_to",
             |_, _, (x, y, area): (i32, i32, Option<String>)| {
@@ -1052,6 +1181,14 @@ fn get_area(id: Option<String>) -> Result<Rc<RefCell<AreaState>>> {
     }
 }
 
+fn get_recipe(id: &str) -> Result<Rc<Recipe>> {
+    Module::recipe(id).ok_or(rlua::Error::FromLuaConversionError {
+        from: "String",
+        to: "Recipe",
+        message: Some(format!("Recipe '{}' does not exist", id)),
+    })
+}
+
 fn entities_with_ids(ids: Vec<String>) -> Vec<ScriptEntity> {
     let mut result = Vec::new();
 
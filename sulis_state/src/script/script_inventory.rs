@@ -43,7 +43,19 @@ use sulis_module::{ability::AIData, ItemKind, QuickSlot, Slot};
 /// errors if there is no item or the slot is invalid.  See `has_equipped`
 /// for valid slots.  The table `stats` includes `stats.name`, `stats.value`,
 /// `stats.weight`, `stats.kind`, and `stats.armor_kind` for armor or
-/// `stats.weapon_kind` for weapons.
+/// `stats.weapon_kind` for weapons.  Items that can degrade also include
+/// `stats.durability` and `stats.max_durability`.
+///
+/// # `damage_item(slot: String, amount: Int)`
+/// Removes `amount` durability from the item equipped in the given slot.
+/// Has no effect if there is no item or the item does not degrade.
+///
+/// # `repair_item(slot: String)`
+/// Restores the item equipped in the given slot to full durability.
+///
+/// # `repair_all()`
+/// Restores all equipped and quick slot items of the parent to full
+/// durability.
 ///
 /// # `equip_item(item: ScriptStashItem)`
 /// Equips the given `item` from the stash into the appropriate inventory
@@ -155,12 +167,17 @@ impl UserData for ScriptInventory {
                 Some(item) => item,
             };
 
+            let item_state = item;
             let item = &item.item;
 
             let stats = lua.create_table()?;
             stats.set("name", item.name.to_string())?;
             stats.set("value", item.value)?;
             stats.set("weight", item.weight)?;
+            if let (Some(cur), Some(max)) = (item_state.durability(), item_state.max_durability()) {
+                stats.set("durability", cur)?;
+                stats.set("max_durability", max)?;
+            }
 
             match item.kind {
                 ItemKind::Armor { kind } => {
@@ -177,6 +194,40 @@ impl UserData for ScriptInventory {
             Ok(stats)
         });
 
+        methods.add_method("damage_item", |_, data, (slot, amount): (String, u32)| {
+            let entity = data.parent.try_unwrap()?;
+            let slot = match Slot::from_str(&slot) {
+                Err(e) => {
+                    warn!("{}", e);
+                    return Ok(());
+                }
+                Ok(slot) => slot,
+            };
+
+            entity.borrow_mut().actor.damage_item(slot, amount);
+            Ok(())
+        });
+
+        methods.add_method("repair_item", |_, data, slot: String| {
+            let entity = data.parent.try_unwrap()?;
+            let slot = match Slot::from_str(&slot) {
+                Err(e) => {
+                    warn!("{}", e);
+                    return Ok(());
+                }
+                Ok(slot) => slot,
+            };
+
+            entity.borrow_mut().actor.repair_item(slot);
+            Ok(())
+        });
+
+        methods.add_method("repair_all", |_, data, ()| {
+            let entity = data.parent.try_unwrap()?;
+            entity.borrow_mut().actor.repair_all_items();
+            Ok(())
+        });
+
         methods.add_method("equip_item", |_, data, item: ScriptStashItem| {
             let entity = data.parent.try_unwrap()?;
             let index = item.unwrap_index()?;
@@ -258,28 +309,7 @@ impl UserData for ScriptInventory {
         });
 
         methods.add_method("usable_items", |_, data, ()| {
-            let parent = data.parent.try_unwrap()?;
-            let parent = parent.borrow();
-            let mut items = Vec::new();
-            for slot in QuickSlot::usable_iter() {
-                let item = match parent.actor.inventory().quick(*slot) {
-                    None => continue,
-                    Some(item) => item,
-                };
-
-                let usable = match &item.item.usable {
-                    None => unreachable!(),
-                    Some(usable) => usable,
-                };
-
-                items.push(ScriptUsableItem {
-                    parent: data.parent.clone(),
-                    slot: *slot,
-                    ai: usable.ai.clone(),
-                });
-            }
-
-            Ok(items)
+            ScriptUsableItem::list(&data.parent)
         });
     }
 }
@@ -336,6 +366,32 @@ pub struct ScriptUsableItem {
 }
 
 impl ScriptUsableItem {
+    /// Returns all usable items in the quick slots of the parent
+    pub fn list(parent: &ScriptEntity) -> Result<Vec<ScriptUsableItem>> {
+        let entity = parent.try_unwrap()?;
+        let entity = entity.borrow();
+        let mut items = Vec::new();
+        for slot in QuickSlot::usable_iter() {
+            let item = match entity.actor.inventory().quick(*slot) {
+                None => continue,
+                Some(item) => item,
+            };
+
+            let usable = match &item.item.usable {
+                None => unreachable!(),
+                Some(usable) => usable,
+            };
+
+            items.push(ScriptUsableItem {
+                parent: parent.clone(),
+                slot: *slot,
+                ai: usable.ai.clone(),
+            });
+        }
+
+        Ok(items)
+    }
+
     pub fn ai_data(&self) -> &AIData {
         &self.ai
     }
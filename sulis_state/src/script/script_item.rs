@@ -222,7 +222,9 @@ fn activate_item(_lua: Context, script_item: &ScriptItem, target: ScriptEntity)
     Ok(())
 }
 
-fn add_another_to_quickbar(
+/// Replaces a consumed quick slot item with another identical item from
+/// the party stash, if one is present
+pub(crate) fn add_another_to_quickbar(
     parent: &Rc<RefCell<EntityState>>,
     item: Option<ItemState>,
     slot: QuickSlot,
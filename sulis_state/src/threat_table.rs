@@ -0,0 +1,122 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::{EntityState, GameState};
+use sulis_module::Module;
+
+const MIN_THREAT: f32 = 0.5;
+
+/// The amount of threat each other entity has generated against the owning
+/// entity, by damaging it, healing its enemies, or through taunt effects.
+/// Entries are keyed by entity index and decay each round.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ThreatTable {
+    entries: HashMap<usize, f32>,
+}
+
+impl ThreatTable {
+    pub fn add(&mut self, index: usize, amount: f32) {
+        let threat = self.entries.entry(index).or_insert(0.0);
+        *threat = (*threat + amount).max(0.0);
+    }
+
+    pub fn get(&self, index: usize) -> f32 {
+        *self.entries.get(&index).unwrap_or(&0.0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&usize, &f32)> {
+        self.entries.iter()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Rekeys all entries using the specified map of old to new entity
+    /// indices, dropping any entries for entities not in the map
+    pub fn remap(&mut self, indices: &HashMap<usize, usize>) {
+        self.entries = self
+            .entries
+            .drain()
+            .filter_map(|(index, threat)| indices.get(&index).map(|new| (*new, threat)))
+            .collect();
+    }
+
+    /// Multiplies all threat by the rules threat decay factor, removing
+    /// any entries that have decayed to nearly zero
+    pub fn decay(&mut self) {
+        let factor = Module::rules().threat_decay_factor;
+        for threat in self.entries.values_mut() {
+            *threat *= factor;
+        }
+        self.entries.retain(|_, threat| *threat >= MIN_THREAT);
+    }
+}
+
+/// Returns the multiplier applied to all threat generated by this entity
+pub fn multiplier(entity: &EntityState) -> f32 {
+    (1.0 + entity.actor.stats.threat as f32 / 100.0).max(0.0)
+}
+
+/// Adds threat from `source` against `target`, modified by the source's
+/// threat bonuses
+pub fn add(target: &Rc<RefCell<EntityState>>, source: &Rc<RefCell<EntityState>>, amount: f32) {
+    if Rc::ptr_eq(target, source) {
+        return;
+    }
+
+    let (index, amount) = {
+        let source = source.borrow();
+        (source.index(), amount * multiplier(&source))
+    };
+    target.borrow_mut().threat_table_mut().add(index, amount);
+}
+
+/// Adds threat from `healer` healing `target` to every entity that is
+/// hostile to the healer and is already tracking threat from the target
+pub fn add_healing(
+    healer: &Rc<RefCell<EntityState>>,
+    target: &Rc<RefCell<EntityState>>,
+    amount: u32,
+) {
+    let amount = amount as f32 * Module::rules().healing_threat_factor;
+    let target_index = target.borrow().index();
+
+    let mgr = GameState::turn_manager();
+    let entities: Vec<_> = mgr.borrow().entity_iter().collect();
+    for entity in entities {
+        if Rc::ptr_eq(&entity, healer) {
+            continue;
+        }
+
+        {
+            let entity = entity.borrow();
+            if !entity.is_hostile(&healer.borrow()) {
+                continue;
+            }
+            if entity.threat_table().get(target_index) <= 0.0 {
+                continue;
+            }
+        }
+
+        add(&entity, healer, amount);
+    }
+}
@@ -17,26 +17,32 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use crate::{AreaState, EntityState, GameState, Location, TurnManager};
+use crate::{AreaState, EntityState, GameState, Location, QuestEvent, TurnManager};
 use sulis_core::{util::Point};
 use sulis_module::{
     area::{ToKind, TriggerKind},
+    bark::BarkEvent,
     Area, ObjectSize, Time,
 };
 
-pub(crate) fn transition_to(area_id: Option<&str>, p: Option<Point>, offset: Point, time: Time) {
+pub(crate) fn transition_to(
+    area_id: Option<&str>,
+    p: Option<Point>,
+    offset: Point,
+    time: Time,
+) -> bool {
     info!("Area transition to {:?}: {:?}", area_id, p);
 
     if let Some(id) = area_id {
         if let Err(e) = GameState::preload_area(id) {
             error!("Error loading {} while transitioning", id);
             error!("{}", e);
-            return;
+            return false;
         }
     }
 
     let (area, location) = match get_area(area_id, p) {
-        None => return,
+        None => return false,
         Some((area, location)) => (area, location),
     };
 
@@ -46,7 +52,7 @@ pub(crate) fn transition_to(area_id: Option<&str>, p: Option<Point>, offset: Poi
         let area = &area.borrow().area.area;
         if !check_location(p, area) {
             error!("Invalid transition location {:?} in {}", location, area.id);
-            return;
+            return false;
         }
     }
 
@@ -81,6 +87,15 @@ pub(crate) fn transition_to(area_id: Option<&str>, p: Option<Point>, offset: Poi
 
     area.update_view_visibility();
 
+    GameState::add_quest_event(QuestEvent::Reached {
+        area: area.area.area.id.to_string(),
+        location: p,
+    });
+
+    if new_area {
+        GameState::add_bark_event(BarkEvent::EnterArea, None);
+    }
+
     if !area.on_load_fired {
         area.on_load_fired = true;
         GameState::add_ui_callbacks_of_kind(
@@ -90,6 +105,8 @@ pub(crate) fn transition_to(area_id: Option<&str>, p: Option<Point>, offset: Poi
             &pc,
         );
     }
+
+    true
 }
 
 fn transition_party(
@@ -0,0 +1,166 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use sulis_core::util::{gen_rand, Point};
+use sulis_module::campaign::{TravelEncounter, TravelEncounterTable, WorldMapLocation};
+use sulis_module::{Module, Time};
+
+use crate::GameState;
+
+/// Returns the number of provisions needed to travel to the specified
+/// location, or `None` if no provisions are needed or the location
+/// cannot be reached from `from`
+fn provisions_required(from: &str, to: &WorldMapLocation) -> Option<(String, u32)> {
+    let campaign = Module::campaign();
+    let provisions = campaign.world_map.provisions.as_ref()?;
+    let hours = to.travel_times.get(from)?;
+    Some((provisions.item.to_string(), provisions.required(*hours)))
+}
+
+/// Travels from the world map location `from` to `to`, consuming
+/// provisions and passing the travel time.  The journey may be interrupted
+/// by a random encounter, in which case the party is moved to the encounter
+/// area instead and the destination is recorded so travel can be resumed.
+/// Returns false if travel was not possible
+pub(crate) fn travel_to(from: &str, to: &WorldMapLocation) -> bool {
+    let hours = match to.travel_times.get(from) {
+        None => {
+            warn!("No travel time from '{}' to '{}'", from, to.id);
+            return false;
+        }
+        Some(hours) => *hours,
+    };
+
+    let area_id = match &to.linked_area {
+        None => {
+            warn!("Unable to travel to '{}' with no linked area", to.id);
+            return false;
+        }
+        Some(id) => id,
+    };
+
+    let provisions = provisions_required(from, to);
+    if let Some((item, quantity)) = &provisions {
+        if GameState::party_stash().borrow().quantity_of(item) < *quantity {
+            info!("Not enough '{}' to travel to '{}'", item, to.id);
+            return false;
+        }
+    }
+
+    let mut time = Time::from_hours(hours);
+    Module::rules().canonicalize_time(&mut time);
+
+    let encounter = to
+        .travel_encounters
+        .get(from)
+        .and_then(|t| roll_encounter(t));
+    let transitioned = match encounter {
+        None => {
+            let transitioned = GameState::transition_to(
+                Some(area_id),
+                Some(to.linked_area_pos),
+                Point::default(),
+                time,
+            );
+            if transitioned {
+                GameState::set_travel_destination(None);
+            }
+            transitioned
+        }
+        Some(encounter) => {
+            info!(
+                "Travel to '{}' interrupted by encounter in '{}'",
+                to.id, encounter.area
+            );
+
+            // encounter areas are generated, so discard any previous visit
+            // and generate a fresh area for this encounter
+            GameState::regenerate_area(&encounter.area);
+
+            let transitioned = GameState::transition_to(
+                Some(&encounter.area),
+                Some(encounter.location),
+                Point::default(),
+                time,
+            );
+            if transitioned {
+                GameState::set_travel_destination(Some(to.id.to_string()));
+            }
+            transitioned
+        }
+    };
+
+    if !transitioned {
+        warn!("Unable to travel from '{}' to '{}'", from, to.id);
+        return false;
+    }
+
+    if let Some((item, quantity)) = provisions {
+        GameState::party_stash()
+            .borrow_mut()
+            .remove_quantity_of(&item, quantity);
+    }
+
+    true
+}
+
+/// Moves the party on to the destination of a journey that was interrupted
+/// by a travel encounter.  No further time passes
+pub(crate) fn resume_travel() -> bool {
+    let world_map = GameState::world_map();
+    let id = match world_map.travel_destination() {
+        None => return false,
+        Some(id) => id,
+    };
+
+    let campaign = Module::campaign();
+    let location = campaign.world_map.locations.iter().find(|l| l.id == id);
+    let (area_id, pos) = match location {
+        Some(WorldMapLocation {
+            linked_area: Some(area_id),
+            linked_area_pos,
+            ..
+        }) => (area_id, *linked_area_pos),
+        _ => {
+            warn!("Invalid travel destination '{}'", id);
+            return false;
+        }
+    };
+
+    if !GameState::transition_to(Some(area_id), Some(pos), Point::default(), Time::default()) {
+        return false;
+    }
+
+    GameState::set_travel_destination(None);
+    true
+}
+
+fn roll_encounter(table: &TravelEncounterTable) -> Option<&TravelEncounter> {
+    if gen_rand(0, 100) >= table.chance {
+        return None;
+    }
+
+    let total: u32 = table.encounters.iter().map(|e| e.weight).sum();
+    let mut roll = gen_rand(0, total);
+    for encounter in table.encounters.iter() {
+        if roll < encounter.weight {
+            return Some(encounter);
+        }
+        roll -= encounter.weight;
+    }
+
+    None
+}
@@ -19,9 +19,11 @@ use std::collections::{vec_deque::Iter, HashMap, HashSet, VecDeque};
 use std::rc::Rc;
 
 use crate::script::{CallbackData, FuncKind, TriggeredCallback};
-use crate::{AreaState, ChangeListener, ChangeListenerList, Effect, EntityState, GameState};
+use crate::{
+    AreaState, ChangeListener, ChangeListenerList, Effect, EntityState, GameState, MoraleState,
+};
 use sulis_core::{config::Config, util::{gen_rand, Point}};
-use sulis_module::{Faction, Module, Time, ROUND_TIME_MILLIS, OnTrigger};
+use sulis_module::{bark::BarkEvent, Faction, Module, Time, ROUND_TIME_MILLIS, OnTrigger};
 
 fn add_campaign_elapsed_callback(cbs: &mut Vec<Rc<CallbackData>>) {
     let script_data = match Module::campaign().on_round_elapsed_script {
@@ -476,6 +478,7 @@ impl TurnManager {
                 Entry::Entity(index) => {
                     if let Some(entity) = &self.entities[index] {
                         entity.borrow_mut().actor.end_turn();
+                        entity.borrow_mut().threat_table_mut().decay();
                         if let Some(cb) = entity.borrow().ai_callbacks() {
                             cbs.push(cb);
                         }
@@ -655,6 +658,7 @@ impl TurnManager {
             self.end_combat();
         } else {
             self.initiate_combat();
+            GameState::add_bark_event(BarkEvent::CombatStart, None);
         }
     }
 
@@ -697,6 +701,7 @@ impl TurnManager {
             let mut entity = entity.borrow_mut();
 
             entity.set_ai_active(false);
+            entity.threat_table_mut().clear();
 
             if !entity.is_party_member() {
                 continue;
@@ -1025,6 +1030,9 @@ impl TurnManager {
             if other.actor.hp() <= 0 {
                 continue;
             }
+            if other.morale_state() == MoraleState::Surrendered {
+                continue;
+            }
             if let Some(index) = other.ai_group() {
                 if index == ai_group {
                     debug!(
@@ -1040,7 +1048,37 @@ impl TurnManager {
         Some(ai_group)
     }
 
+    /// Removes all entities and surfaces in the specified area, without firing
+    /// any encounter cleared or effect removal scripts.  Used when the area is
+    /// about to be discarded and regenerated
+    pub(crate) fn clear_area(&mut self, area_state: &Rc<RefCell<AreaState>>) {
+        let to_remove: Vec<usize> = area_state.borrow().entity_iter().copied().collect();
+        for index in to_remove {
+            self.detach_entity(index);
+        }
+
+        let area_id = area_state.borrow().area.area.id.to_string();
+        let surfaces: Vec<usize> = self
+            .surfaces
+            .iter()
+            .copied()
+            .filter(|index| match &self.effects[*index] {
+                None => false,
+                Some(effect) => matches!(effect.surface(), Some((id, _)) if id == area_id),
+            })
+            .collect();
+
+        for index in surfaces {
+            self.remove_effect(index);
+        }
+    }
+
     fn remove_entity(&mut self, index: usize) {
+        let entity = self.detach_entity(index);
+        self.check_combat_over(&entity);
+    }
+
+    fn detach_entity(&mut self, index: usize) -> Rc<RefCell<EntityState>> {
         let entity = Rc::clone(self.entities[index].as_ref().unwrap());
         let area_state = GameState::get_area_state(&entity.borrow().location.area_id).unwrap();
         let surfaces = area_state.borrow_mut().remove_entity(&entity, &self);
@@ -1075,6 +1113,13 @@ impl TurnManager {
             Entry::TurnChange => true,
         });
 
+        entity
+    }
+
+    /// Called when an entity is no longer taking part in a fight, either by
+    /// being removed or surrendering.  Ends combat and fires the encounter
+    /// cleared script as needed.
+    pub(crate) fn check_combat_over(&mut self, entity: &Rc<RefCell<EntityState>>) {
         if self.order.iter().all(|e| match e {
             Entry::Effect(_) => true,
             Entry::Entity(index) => {
@@ -1086,12 +1131,12 @@ impl TurnManager {
             self.set_combat_active(false);
         }
 
-        if let Some(ai_group) = self.check_encounter_cleared(&entity) {
+        if let Some(ai_group) = self.check_encounter_cleared(entity) {
             let enc_ref = self.ai_groups.get(&ai_group).unwrap().clone();
             let area_state = GameState::get_area_state(&enc_ref.area_id).unwrap();
             area_state
                 .borrow_mut()
-                .fire_on_encounter_cleared(enc_ref.encounter_index, &entity);
+                .fire_on_encounter_cleared(enc_ref.encounter_index, entity);
         }
 
         self.listeners.notify(&self);
@@ -766,6 +766,7 @@ fn add_disabled_text_arg(
         RequiresShield => "Equip a shield",
         RequiresMelee => "Equip a melee weapon",
         RequiresRanged => "Equip a ranged weapon",
+        NoAmmo => "Out of ammunition",
         RequiresActiveMode => "Must first activate a mode",
         CombatOnly => "May only be used in combat",
         OnCooldown => "The cooldown is active",
@@ -23,9 +23,9 @@ use sulis_core::ui::{animation_state, Widget};
 use sulis_core::util::Point;
 use sulis_module::{
     area::{Destination, ToKind},
-    Faction, Module, ObjectSize, OnTrigger, Time, MOVE_TO_THRESHOLD,
+    Cover, Faction, Module, ObjectSize, OnTrigger, Time, MOVE_TO_THRESHOLD,
 };
-use sulis_state::{can_attack, is_within};
+use sulis_state::{can_attack, is_within, RangedModifiers};
 use sulis_state::{AreaState, EntityState, GameState, PropState, ScriptCallback};
 
 pub fn get_action(x_f32: f32, y_f32: f32) -> Box<dyn ActionKind> {
@@ -100,6 +100,7 @@ pub struct ActionHoverInfo {
     pub path: Vec<(f32, f32)>,
     pub ap: i32,
     pub total_ap: i32,
+    pub info: Vec<String>,
 }
 
 impl ActionHoverInfo {
@@ -118,6 +119,7 @@ impl ActionHoverInfo {
                         base.ap = append.ap;
                     }
                     base.path = append.path;
+                    base.info.extend(append.info);
                 }
                 Some(base)
             }
@@ -132,6 +134,7 @@ impl ActionHoverInfo {
             path: Vec::new(),
             ap: 0,
             total_ap: 0,
+            info: Vec::new(),
         })
     }
 
@@ -148,6 +151,7 @@ impl ActionHoverInfo {
             path: Vec::new(),
             ap,
             total_ap,
+            info: Vec::new(),
         })
     }
 
@@ -165,6 +169,7 @@ impl ActionHoverInfo {
             path: path.iter().copied().collect(),
             ap,
             total_ap: entity.actor.ap() as i32,
+            info: Vec::new(),
         })
     }
 }
@@ -564,6 +569,9 @@ impl AttackAction {
             if pc.actor.stats.attack_disabled {
                 return None;
             }
+            if !pc.actor.has_ammo_for_attack() {
+                return Some(Box::new(NoAmmoAction { target }));
+            }
             pc.actor.stats.attack_cost
         };
 
@@ -599,7 +607,16 @@ impl ActionKind for AttackAction {
     fn get_hover_info(&self) -> Option<ActionHoverInfo> {
         let point = self.target.borrow().location.to_point();
         let total_ap = self.pc.borrow().actor.ap() as i32;
-        ActionHoverInfo::with_ap(&self.target.borrow(), point, total_ap, self.ap)
+        let mut info = ActionHoverInfo::with_ap(&self.target.borrow(), point, total_ap, self.ap);
+
+        if self.pc.borrow().actor.stats.attack_is_ranged() {
+            let modifiers = RangedModifiers::compute(&self.pc.borrow(), &self.target.borrow());
+            if let Some(info) = info.as_mut() {
+                info.info = ranged_modifiers_info(&modifiers);
+            }
+        }
+
+        info
     }
 
     fn fire_action(&mut self, _widget: &Rc<RefCell<Widget>>) -> bool {
@@ -617,6 +634,47 @@ impl ActionKind for AttackAction {
     }
 }
 
+struct NoAmmoAction {
+    target: Rc<RefCell<EntityState>>,
+}
+
+impl ActionKind for NoAmmoAction {
+    fn cursor_state(&self) -> animation_state::Kind {
+        animation_state::Kind::MouseInvalid
+    }
+
+    fn get_hover_info(&self) -> Option<ActionHoverInfo> {
+        let target = self.target.borrow();
+        let mut info = ActionHoverInfo::new(&target.size, target.location.to_point());
+        if let Some(info) = info.as_mut() {
+            info.info.push("No ammunition".to_string());
+        }
+        info
+    }
+
+    fn fire_action(&mut self, _widget: &Rc<RefCell<Widget>>) -> bool {
+        false
+    }
+}
+
+fn ranged_modifiers_info(modifiers: &RangedModifiers) -> Vec<String> {
+    let mut info = Vec::new();
+
+    match modifiers.cover {
+        Cover::None => (),
+        Cover::Partial => info.push(format!("Partial Cover: +{} Defense", modifiers.defense())),
+        Cover::Full => info.push(format!("Full Cover: +{} Defense", modifiers.defense())),
+    }
+
+    if modifiers.high_ground {
+        info.push(format!("High Ground: +{} Accuracy", modifiers.accuracy()));
+    } else if modifiers.low_ground {
+        info.push(format!("Low Ground: {} Accuracy", modifiers.accuracy()));
+    }
+
+    info
+}
+
 struct ActionCallback {
     action: Rc<RefCell<Box<dyn ActionKind>>>,
     widget: Rc<RefCell<Widget>>,
@@ -47,6 +47,7 @@ pub struct AreaOverlayHandler {
     path_point_image: Option<Rc<dyn Image>>,
     path_point_end_image: Option<Rc<dyn Image>>,
     path_ap: Option<i32>,
+    hover_info: Vec<String>,
 }
 
 impl Default for AreaOverlayHandler {
@@ -61,6 +62,7 @@ impl Default for AreaOverlayHandler {
             path_point_image: None,
             path_point_end_image: None,
             path_ap: None,
+            hover_info: Vec::new(),
         }
     }
 }
@@ -214,11 +216,13 @@ impl AreaOverlayHandler {
                     0 => self.path_ap = None,
                     ap => self.path_ap = Some(info.total_ap - ap),
                 }
+                self.hover_info = info.info;
             }
             None => {
                 self.hover_sprite = None;
                 self.path.clear();
                 self.path_ap = None;
+                self.hover_info.clear();
             }
         }
     }
@@ -298,6 +302,7 @@ impl AreaOverlayHandler {
         self.selection_box_start = None;
         self.path.clear();
         self.path_ap = None;
+        self.hover_info.clear();
         Cursor::set_cursor_state(animation_state::Kind::Normal);
         self.clear_area_mouseover();
     }
@@ -388,25 +393,34 @@ impl AreaOverlayHandler {
             }
         }
 
-        if !GameState::is_combat_active() {
-            return;
+        let (x, mut y) = match &self.hover_sprite {
+            None => (0.0, 0.0),
+            Some(hover) => (
+                hover.x as f32 + offset.x,
+                hover.y as f32 + hover.h as f32 + offset.y,
+            ),
+        };
+        let font_rend = LineRenderer::new(&params.font);
+
+        if GameState::is_combat_active() {
+            if let Some(ap) = self.path_ap {
+                let text = format!("{} AP", Module::rules().format_ap(ap));
+                let offset = Offset { x, y };
+                let (mut draw_list, _) = font_rend.get_draw_list(&text, offset, params.ap_scale);
+                draw_list.set_color(params.ap_color);
+                draw_list.set_scale(scale);
+                renderer.draw(draw_list);
+                y += params.ap_scale;
+            }
         }
-        if let Some(ap) = self.path_ap {
-            let font_rend = LineRenderer::new(&params.font);
-            let text = format!("{} AP", Module::rules().format_ap(ap));
-            let (x, y) = match &self.hover_sprite {
-                None => (0.0, 0.0),
-                Some(hover) => (
-                    hover.x as f32 + offset.x,
-                    hover.y as f32 + hover.h as f32 + offset.y,
-                ),
-            };
 
+        for text in self.hover_info.iter() {
             let offset = Offset { x, y };
-            let (mut draw_list, _) = font_rend.get_draw_list(&text, offset, params.ap_scale);
-            draw_list.set_color(params.ap_color);
+            let (mut draw_list, _) = font_rend.get_draw_list(text, offset, params.ap_scale);
+            draw_list.set_color(params.info_color);
             draw_list.set_scale(scale);
             renderer.draw(draw_list);
+            y += params.ap_scale;
         }
     }
 
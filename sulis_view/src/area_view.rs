@@ -33,10 +33,13 @@ use sulis_core::util::{self, Offset, Point, Rect, Scale};
 use sulis_core::widgets::Label;
 use sulis_module::{
     area::{Layer, Tile},
+    quest::QuestMarker,
     DamageKind, Module,
 };
 use sulis_state::{area_feedback_text, area_state::PCVisRedraw, RangeIndicatorImageSet};
-use sulis_state::{AreaDrawable, AreaState, EntityState, EntityTextureCache, GameState};
+use sulis_state::{
+    AreaDrawable, AreaState, ChangeListener, EntityState, EntityTextureCache, GameState,
+};
 
 use crate::{action_kind, window_fade, AreaOverlayHandler, ScreenShake, WindowFade};
 
@@ -59,6 +62,10 @@ pub struct AreaView {
     targeter_tile: Option<Rc<dyn Image>>,
     range_indicator_image_set: Option<RangeIndicatorImageSet>,
 
+    quest_marker: Option<Rc<dyn Image>>,
+    quest_marker_size: f32,
+    quest_markers: Rc<RefCell<Vec<QuestMarker>>>,
+
     scroll: Scrollable,
     active_entity: Option<Rc<RefCell<EntityState>>>,
     feedback_text_params: area_feedback_text::Params,
@@ -94,6 +101,9 @@ impl AreaView {
             scroll: Scrollable::default(),
             targeter_tile: None,
             range_indicator_image_set: None,
+            quest_marker: None,
+            quest_marker_size: 2.0,
+            quest_markers: Rc::new(RefCell::new(Vec::new())),
             active_entity: None,
             entity_see_through_alpha: 0.2,
             feedback_text_params: area_feedback_text::Params::default(),
@@ -412,6 +422,72 @@ impl AreaView {
         // info!("Entity & Prop draw time: {}", util::format_elapsed_secs(start_time.elapsed()));
     }
 
+    fn draw_quest_markers(
+        &self,
+        renderer: &mut dyn GraphicsRenderer,
+        scale: Scale,
+        widget: &Widget,
+        state: &AreaState,
+        millis: u32,
+    ) {
+        let image = match self.quest_marker {
+            None => return,
+            Some(ref image) => image,
+        };
+
+        let markers = self.quest_markers.borrow();
+        if markers.is_empty() {
+            return;
+        }
+
+        let x_base = widget.state.inner_left() as f32 - self.scroll.x();
+        let y_base = widget.state.inner_top() as f32 - self.scroll.y();
+        let size = self.quest_marker_size;
+
+        let mgr = GameState::turn_manager();
+        let mut draw_list = DrawList::empty_sprite();
+        for marker in markers.iter() {
+            // markers are drawn centered above the marked entity or location
+            let (x, y) = if let Some(ref id) = marker.entity {
+                let entity = state
+                    .entity_iter()
+                    .map(|index| mgr.borrow().entity(*index))
+                    .find(|entity| entity.borrow().unique_id() == id);
+
+                let entity = match entity {
+                    None => continue,
+                    Some(entity) => entity,
+                };
+                let entity = entity.borrow();
+                let w = entity.size.width as f32;
+                (
+                    entity.location.x as f32 + entity.sub_pos.0 + (w - size) / 2.0,
+                    entity.location.y as f32 + entity.sub_pos.1 - size,
+                )
+            } else if let Some(p) = marker.location {
+                if marker.area != state.area.area.id {
+                    continue;
+                }
+                (p.x as f32 + (1.0 - size) / 2.0, p.y as f32 + 1.0 - size)
+            } else {
+                continue;
+            };
+
+            let rect = Rect {
+                x: x_base + x,
+                y: y_base + y,
+                w: size,
+                h: size,
+            };
+            image.append_to_draw_list(&mut draw_list, &animation_state::NORMAL, rect, millis);
+        }
+
+        if !draw_list.is_empty() {
+            draw_list.set_scale(scale);
+            renderer.draw(draw_list);
+        }
+    }
+
     fn draw_selection(
         &mut self,
         selected: &Rc<RefCell<EntityState>>,
@@ -577,6 +653,11 @@ impl WidgetKind for AreaView {
             self.targeter_tile = ResourceSet::image(image_id);
         }
 
+        if let Some(ref image_id) = theme.custom.get("quest_marker") {
+            self.quest_marker = ResourceSet::image(image_id);
+        }
+        self.quest_marker_size = theme.get_custom_or_default("quest_marker_size", 2.0);
+
         self.entity_see_through_alpha = theme.get_custom_or_default("entity_see_through_alpha", 0.2);
         self.feedback_text_params.scale = theme.get_custom_or_default("feedback_text_scale", 1.0);
         self.feedback_text_params.ap_scale =
@@ -646,6 +727,15 @@ impl WidgetKind for AreaView {
         info!("Adding area to widget tree");
         self.overlay_handler = AreaOverlayHandler::default();
 
+        *self.quest_markers.borrow_mut() = GameState::quest_state().active_markers();
+        let markers = Rc::clone(&self.quest_markers);
+        GameState::add_quest_state_change_listener(ChangeListener::new(
+            NAME,
+            Box::new(move |quests| {
+                *markers.borrow_mut() = quests.active_markers();
+            }),
+        ));
+
         let area_state = GameState::area_state();
         let area = &area_state.borrow().area;
 
@@ -817,6 +907,8 @@ impl WidgetKind for AreaView {
             renderer.draw(draw_list);
         }
 
+        self.draw_quest_markers(renderer, scale, widget, &state, millis);
+
         let active_entity = self.active_entity.clone();
         if let Some(ref entity) = active_entity {
             self.draw_selection(entity, renderer, scale, widget, millis);
@@ -18,7 +18,9 @@ use std::fmt::Display;
 
 use sulis_core::ui::WidgetState;
 use sulis_module::bonus::{AttackBuilder, AttackKindBuilder, Contingent};
-use sulis_module::{Armor, Bonus, BonusList, DamageKind, Module, PrereqList};
+use sulis_module::{
+    Armor, AttackBonuses, Attribute, Bonus, BonusList, DamageKind, Module, PrereqList, StatList,
+};
 
 pub fn format_bonus_or_penalty(amount: i32) -> String {
     if amount >= 0 {
@@ -45,7 +47,10 @@ pub fn add_attack_text_args(attack: &AttackBuilder, widget_state: &mut WidgetSta
         }
     }
 
-    let bonuses = &attack.bonuses;
+    add_attack_bonus_text_args(&attack.bonuses, widget_state);
+}
+
+pub fn add_attack_bonus_text_args(bonuses: &AttackBonuses, widget_state: &mut WidgetState) {
     add_if_nonzero(
         widget_state,
         "attack_crit_chance",
@@ -182,6 +187,7 @@ fn add_bonus(
         MovementRate(amount) => state.add_text_arg("movement_rate", &format!("{:.2}", amount)),
         MoveAnimRate(amount) => state.add_text_arg("move_anim_rate", &format!("{:.2}", amount)),
         CasterLevel(amount) => add(state, "caster_level", amount),
+        Threat(amount) => add(state, "threat", amount),
         AttackCost(amount) => {
             let cost = Module::rules().to_display_ap(*amount);
             add(state, "attack_cost", cost);
@@ -333,6 +339,84 @@ pub fn add_bonus_text_args(bonuses: &BonusList, widget_state: &mut WidgetState)
     }
 }
 
+/// Adds text args for the differences between the current stats and the
+/// stats with a new item equipped.  Each stat that changes gets a
+/// `cmp_<stat>_up` or `cmp_<stat>_down` arg so it can be colored accordingly
+pub fn add_comparison_text_args(cur: &StatList, new: &StatList, widget_state: &mut WidgetState) {
+    widget_state.add_text_arg("compare", "true");
+    let mut any = false;
+
+    let (cur_min, cur_max) = attack_damage(cur);
+    let (new_min, new_max) = attack_damage(new);
+    any |= add_delta(widget_state, "min_damage", new_min - cur_min);
+    any |= add_delta(widget_state, "max_damage", new_max - cur_max);
+
+    let (cur_melee, cur_ranged) = attack_accuracy(cur);
+    let (new_melee, new_ranged) = attack_accuracy(new);
+    any |= add_delta(widget_state, "melee_accuracy", new_melee - cur_melee);
+    any |= add_delta(widget_state, "ranged_accuracy", new_ranged - cur_ranged);
+
+    let delta = new.armor.base() as i32 - cur.armor.base() as i32;
+    any |= add_delta(widget_state, "armor", delta);
+    for kind in DamageKind::iter() {
+        if *kind == DamageKind::Raw {
+            continue;
+        }
+
+        let delta = new.armor.amount(*kind) as i32 - cur.armor.amount(*kind) as i32;
+        let id = format!("armor_{}", kind).to_lowercase();
+        any |= add_delta(widget_state, &id, delta);
+    }
+
+    any |= add_delta(widget_state, "defense", new.defense - cur.defense);
+    any |= add_delta(widget_state, "fortitude", new.fortitude - cur.fortitude);
+    any |= add_delta(widget_state, "reflex", new.reflex - cur.reflex);
+    any |= add_delta(widget_state, "will", new.will - cur.will);
+    any |= add_delta(widget_state, "hit_points", new.max_hp - cur.max_hp);
+
+    for attr in Attribute::iter() {
+        let delta = new.attributes.get(*attr) as i32 - cur.attributes.get(*attr) as i32;
+        any |= add_delta(widget_state, attr.short_name(), delta);
+    }
+
+    if !any {
+        widget_state.add_text_arg("compare_none", "true");
+    }
+}
+
+fn attack_damage(stats: &StatList) -> (i32, i32) {
+    match stats.attacks.first() {
+        None => (0, 0),
+        Some(attack) => (attack.damage.min() as i32, attack.damage.max() as i32),
+    }
+}
+
+fn attack_accuracy(stats: &StatList) -> (i32, i32) {
+    let (melee, ranged) = match stats.attacks.first() {
+        None => (0, 0),
+        Some(attack) => (
+            attack.bonuses.melee_accuracy,
+            attack.bonuses.ranged_accuracy,
+        ),
+    };
+
+    (stats.melee_accuracy + melee, stats.ranged_accuracy + ranged)
+}
+
+fn add_delta(widget_state: &mut WidgetState, id: &str, delta: i32) -> bool {
+    let dir = match delta {
+        d if d > 0 => "up",
+        d if d < 0 => "down",
+        _ => return false,
+    };
+
+    widget_state.add_text_arg(
+        &format!("cmp_{}_{}", id, dir),
+        &format_bonus_or_penalty(delta),
+    );
+    true
+}
+
 fn add_if_nonzero(widget_state: &mut WidgetState, text: &str, val: f32) {
     if val != 0.0 {
         widget_state.add_text_arg(text, &val.to_string());
@@ -316,6 +316,7 @@ impl BuilderSet for CharacterCreator {
             sex: builder.sex,
             attributes: builder.attributes.unwrap(),
             faction: Some(Faction::Friendly),
+            affiliation: None,
             conversation: None,
             images: builder.images.clone(),
             hue: builder.hue,
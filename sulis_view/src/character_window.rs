@@ -43,6 +43,7 @@ enum ActivePane {
     Character,
     Ability { show_passives: bool },
     Effect,
+    Relationships,
 }
 
 pub struct CharacterWindow {
@@ -152,6 +153,16 @@ impl WidgetKind for CharacterWindow {
                 parent.borrow_mut().invalidate_children();
             })));
 
+        let relationships_pane = Widget::with_theme(Button::empty(), "relationships_pane_button");
+        relationships_pane
+            .borrow_mut()
+            .state
+            .add_callback(Callback::new(Rc::new(|widget, _| {
+                let (parent, window) = Widget::parent_mut::<CharacterWindow>(widget);
+                window.active_pane = ActivePane::Relationships;
+                parent.borrow_mut().invalidate_children();
+            })));
+
         let cur_pane = match self.active_pane {
             ActivePane::Character => {
                 char_pane.borrow_mut().state.set_active(true);
@@ -170,6 +181,10 @@ impl WidgetKind for CharacterWindow {
                 effects_pane.borrow_mut().state.set_active(true);
                 create_effects_pane(&mut self.character.borrow_mut().actor)
             }
+            ActivePane::Relationships => {
+                relationships_pane.borrow_mut().state.set_active(true);
+                create_relationships_pane()
+            }
         };
 
         vec![
@@ -179,6 +194,7 @@ impl WidgetKind for CharacterWindow {
             char_pane,
             abilities_pane,
             effects_pane,
+            relationships_pane,
         ]
     }
 }
@@ -252,6 +268,7 @@ fn export_character(pc: &ActorState) {
         sex: Some(pc.actor.sex),
         attributes: pc.actor.attributes,
         faction: Some(pc.actor.faction()),
+        affiliation: None,
         conversation: None,
         images: pc.actor.builder_images.clone(),
         hue: pc.actor.hue,
@@ -391,6 +408,31 @@ pub fn create_effects_pane(pc: &mut ActorState) -> Rc<RefCell<Widget>> {
     effects
 }
 
+/// Lists the approval of each companion currently in the party
+pub fn create_relationships_pane() -> Rc<RefCell<Widget>> {
+    let scrollpane = ScrollPane::new(ScrollDirection::Vertical);
+    let relationships = Widget::with_theme(scrollpane.clone(), "relationships");
+
+    let campaign = Module::campaign();
+    for entity in GameState::party() {
+        let entity = entity.borrow();
+        let actor = &entity.actor.actor;
+        if !campaign.companions.contains_key(&actor.id) {
+            continue;
+        }
+
+        let widget = Widget::with_theme(TextArea::empty(), "relationship");
+        {
+            let state = &mut widget.borrow_mut().state;
+            state.add_text_arg("name", &actor.name);
+            state.add_text_arg("approval", &GameState::approval(&actor.id).to_string());
+        }
+        scrollpane.borrow().add_to_content(widget);
+    }
+
+    relationships
+}
+
 fn add_effect_text_args(effect: &Effect, widget_state: &mut WidgetState) {
     widget_state.add_text_arg("name", effect.name());
 
@@ -0,0 +1,293 @@
+//  This file is part of Sulis, a turn based RPG written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  Sulis is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  Sulis is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::bonus_text_arg_handler::add_prereq_text_args;
+use sulis_core::ui::{Callback, Widget, WidgetKind};
+use sulis_core::widgets::{Button, Label, ScrollDirection, ScrollPane, TextArea};
+use sulis_module::{Module, Recipe, RecipeOutput};
+use sulis_state::{crafting, ChangeListener, EntityState, GameState};
+
+pub const NAME: &str = "crafting_window";
+
+pub struct CraftingWindow {
+    crafter: Rc<RefCell<EntityState>>,
+    active_recipe: Option<Rc<Recipe>>,
+    target: Option<usize>,
+}
+
+impl CraftingWindow {
+    pub fn new(crafter: &Rc<RefCell<EntityState>>) -> Rc<RefCell<CraftingWindow>> {
+        Rc::new(RefCell::new(CraftingWindow {
+            crafter: Rc::clone(crafter),
+            active_recipe: None,
+            target: None,
+        }))
+    }
+
+    fn add_requirements(&self, recipe: &Recipe, pane: &Rc<RefCell<ScrollPane>>) {
+        let stash = GameState::party_stash();
+        let stash = stash.borrow();
+
+        for ingredient in recipe.ingredients.iter() {
+            let have = stash.quantity_of(&ingredient.item.id);
+            let text = format!(
+                "{}: {} / {}",
+                ingredient.item.name, have, ingredient.quantity
+            );
+            pane.borrow()
+                .add_to_content(requirement(&text, have >= ingredient.quantity));
+        }
+
+        for tool in recipe.tools.iter() {
+            let text = format!("Tool: {}", tool.name);
+            pane.borrow()
+                .add_to_content(requirement(&text, stash.has_item(&tool.id)));
+        }
+
+        if !recipe.near_props.is_empty() {
+            let names: Vec<String> = recipe
+                .near_props
+                .iter()
+                .filter_map(|id| Module::prop(id))
+                .map(|prop| prop.name.to_string())
+                .collect();
+            let text = format!("Near: {}", names.join(" or "));
+            let met = crafting::is_near_props(recipe, &self.crafter);
+            pane.borrow().add_to_content(requirement(&text, met));
+        }
+
+        let crafter = self.crafter.borrow();
+        for (stat, amount) in recipe.class_stats.iter() {
+            let cur = crafter.actor.current_class_stat(stat);
+            let text = format!("{}: {} / {}", stat, cur, amount);
+            pane.borrow()
+                .add_to_content(requirement(&text, !cur.less_than(*amount)));
+        }
+    }
+}
+
+fn requirement(text: &str, met: bool) -> Rc<RefCell<Widget>> {
+    let widget = Widget::with_theme(TextArea::empty(), "requirement");
+    {
+        let state = &mut widget.borrow_mut().state;
+        state.add_text_arg("text", text);
+        if !met {
+            state.add_text_arg("unmet", "true");
+        }
+    }
+    widget
+}
+
+impl WidgetKind for CraftingWindow {
+    widget_kind!(NAME);
+
+    fn on_remove(&mut self, _widget: &Rc<RefCell<Widget>>) {
+        self.crafter.borrow_mut().actor.listeners.remove(NAME);
+    }
+
+    fn on_add(&mut self, widget: &Rc<RefCell<Widget>>) -> Vec<Rc<RefCell<Widget>>> {
+        let stash = GameState::party_stash();
+        stash
+            .borrow_mut()
+            .listeners
+            .add(ChangeListener::invalidate(NAME, widget));
+        self.crafter
+            .borrow_mut()
+            .actor
+            .listeners
+            .add(ChangeListener::invalidate(NAME, widget));
+
+        let widget_ref = Rc::clone(widget);
+        GameState::add_party_listener(ChangeListener::new(
+            NAME,
+            Box::new(move |entity| {
+                let entity = match entity {
+                    None => return,
+                    Some(entity) => entity,
+                };
+                let window = Widget::kind_mut::<CraftingWindow>(&widget_ref);
+                window.crafter.borrow_mut().actor.listeners.remove(NAME);
+                window.crafter = Rc::clone(entity);
+                widget_ref.borrow_mut().invalidate_children();
+            }),
+        ));
+
+        let close = Widget::with_theme(Button::empty(), "close");
+        close
+            .borrow_mut()
+            .state
+            .add_callback(Callback::new(Rc::new(|widget, _| {
+                let (parent, _) = Widget::parent::<CraftingWindow>(widget);
+                parent.borrow_mut().mark_for_removal();
+            })));
+
+        let crafter_label = Widget::with_theme(Label::empty(), "crafter_label");
+        crafter_label
+            .borrow_mut()
+            .state
+            .add_text_arg("name", &self.crafter.borrow().actor.actor.name);
+
+        let recipe_list_pane = ScrollPane::new(ScrollDirection::Vertical);
+        let recipe_list = Widget::with_theme(recipe_list_pane.clone(), "recipe_list");
+
+        let mut recipes = Module::all_recipes();
+        recipes.sort_unstable_by_key(|r| r.name.clone());
+
+        for recipe in recipes {
+            let active = match self.active_recipe {
+                None => false,
+                Some(ref active) => Rc::ptr_eq(active, &recipe),
+            };
+
+            let button = Widget::with_theme(Button::empty(), "recipe_button");
+            button.borrow_mut().state.set_active(active);
+
+            let text_area = Widget::with_defaults(TextArea::empty());
+            text_area
+                .borrow_mut()
+                .state
+                .add_text_arg("name", &recipe.name);
+            if crafting::can_craft(&recipe, &self.crafter) {
+                text_area
+                    .borrow_mut()
+                    .state
+                    .add_text_arg("craftable", "true");
+            }
+            Widget::add_child_to(&button, text_area);
+
+            let recipe_ref = Rc::clone(&recipe);
+            button
+                .borrow_mut()
+                .state
+                .add_callback(Callback::new(Rc::new(move |widget, _| {
+                    let (parent, window) = Widget::parent_mut::<CraftingWindow>(widget);
+                    window.active_recipe = Some(Rc::clone(&recipe_ref));
+                    window.target = None;
+                    parent.borrow_mut().invalidate_children();
+                })));
+
+            recipe_list_pane.borrow().add_to_content(button);
+        }
+
+        let details = Widget::with_theme(TextArea::empty(), "details");
+        let requirements_pane = ScrollPane::new(ScrollDirection::Vertical);
+        let requirements = Widget::with_theme(requirements_pane.clone(), "requirements");
+        let targets_pane = ScrollPane::new(ScrollDirection::Vertical);
+        let targets = Widget::with_theme(targets_pane.clone(), "targets");
+        let craft = Widget::with_theme(Button::empty(), "craft");
+        craft.borrow_mut().state.set_enabled(false);
+
+        let recipe = match self.active_recipe {
+            None => {
+                return vec![close, crafter_label, recipe_list, details];
+            }
+            Some(ref recipe) => Rc::clone(recipe),
+        };
+
+        {
+            let state = &mut details.borrow_mut().state;
+            state.add_text_arg("name", &recipe.name);
+            state.add_text_arg("description", &recipe.description);
+            if let Some(ref prereqs) = recipe.prereqs {
+                add_prereq_text_args(prereqs, state);
+                if !prereqs.meets(&self.crafter.borrow().actor.actor) {
+                    state.add_text_arg("prereqs_unmet", "true");
+                }
+            }
+        }
+
+        self.add_requirements(&recipe, &requirements_pane);
+
+        let mut children = vec![close, crafter_label, recipe_list, details, requirements];
+
+        let valid_targets = crafting::valid_targets(&recipe);
+        if let RecipeOutput::Adjective { .. } = recipe.output {
+            if let Some(target) = self.target {
+                if !valid_targets.contains(&target) {
+                    self.target = None;
+                }
+            }
+
+            let stash = stash.borrow();
+            for index in valid_targets {
+                let (qty, item_state) = match stash.items().get(index) {
+                    None => continue,
+                    Some(entry) => entry,
+                };
+
+                let button = Widget::with_theme(Button::empty(), "target_button");
+                button
+                    .borrow_mut()
+                    .state
+                    .set_active(self.target == Some(index));
+
+                let text_area = Widget::with_defaults(TextArea::empty());
+                {
+                    let state = &mut text_area.borrow_mut().state;
+                    state.add_text_arg("name", &item_state.item.name);
+                    if *qty > 1 {
+                        state.add_text_arg("quantity", &qty.to_string());
+                    }
+                }
+                Widget::add_child_to(&button, text_area);
+
+                button
+                    .borrow_mut()
+                    .state
+                    .add_callback(Callback::new(Rc::new(move |widget, _| {
+                        let (parent, window) = Widget::parent_mut::<CraftingWindow>(widget);
+                        window.target = Some(index);
+                        parent.borrow_mut().invalidate_children();
+                    })));
+
+                targets_pane.borrow().add_to_content(button);
+            }
+            children.push(targets);
+        }
+
+        let has_target = match recipe.output {
+            RecipeOutput::Item { .. } => true,
+            RecipeOutput::Adjective { .. } => self.target.is_some(),
+        };
+        let enabled = has_target
+            && crafting::can_craft(&recipe, &self.crafter)
+            && !GameState::is_combat_active();
+        craft.borrow_mut().state.set_enabled(enabled);
+
+        craft
+            .borrow_mut()
+            .state
+            .add_callback(Callback::new(Rc::new(|widget, _| {
+                let (parent, window) = Widget::parent_mut::<CraftingWindow>(widget);
+                let recipe = match window.active_recipe {
+                    None => return,
+                    Some(ref recipe) => Rc::clone(recipe),
+                };
+
+                if crafting::craft(&recipe, &window.crafter, window.target) {
+                    window.target = None;
+                }
+                parent.borrow_mut().invalidate_children();
+            })));
+        children.push(craft);
+
+        children
+    }
+}
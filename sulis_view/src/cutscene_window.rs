@@ -18,16 +18,24 @@ use std::any::Any;
 use std::cell::RefCell;
 use std::rc::Rc;
 
+use sulis_core::io::Audio;
 use sulis_core::ui::{Callback, Widget, WidgetKind};
 use sulis_core::widgets::{Button, TextArea};
-use sulis_module::Cutscene;
-use sulis_state::GameState;
+use sulis_module::{
+    cutscene::{CutsceneKind, Frame},
+    Cutscene, OnTrigger,
+};
+use sulis_state::{condition, script::entity_with_id, GameState};
+
+use crate::{trigger_activator, window_fade, WindowFade};
 
 pub const NAME: &str = "cutscene_window";
 
 pub struct CutsceneWindow {
     cutscene: Rc<Cutscene>,
     frame_index: usize,
+    frame_elapsed: u32,
+    started_frame: Option<usize>,
 }
 
 impl CutsceneWindow {
@@ -35,8 +43,54 @@ impl CutsceneWindow {
         Rc::new(RefCell::new(CutsceneWindow {
             cutscene,
             frame_index: 0,
+            frame_elapsed: 0,
+            started_frame: None,
         }))
     }
+
+    fn advance(&mut self, widget: &Rc<RefCell<Widget>>) {
+        self.frame_index += 1;
+        self.frame_elapsed = 0;
+        widget.borrow_mut().invalidate_children();
+    }
+}
+
+fn start_frame(widget: &Rc<RefCell<Widget>>, frame: &Frame) {
+    if let Some(ref music) = frame.music {
+        Audio::play_music(music, 1.0);
+    }
+
+    if let Some(ref sfx) = frame.sfx {
+        Audio::play_sfx(sfx, 1.0);
+    }
+
+    let pc = GameState::player();
+    if !frame.on_start.is_empty() {
+        trigger_activator::activate(widget, &frame.on_start, &pc, &pc);
+    }
+
+    let area_id = GameState::area_state().borrow().area.area.id.to_string();
+    for entity_move in frame.moves.iter() {
+        let entity = match entity_with_id(entity_move.entity.to_string()) {
+            None => {
+                warn!("Unable to find entity '{}' to move", entity_move.entity);
+                continue;
+            }
+            Some(entity) => entity,
+        };
+
+        if entity.borrow().location.area_id != area_id {
+            warn!("Entity '{}' to move is not in the area", entity_move.entity);
+            continue;
+        }
+
+        if !GameState::move_along_path(&entity, &entity_move.path) {
+            warn!(
+                "Unable to move entity '{}' for cutscene",
+                entity_move.entity
+            );
+        }
+    }
 }
 
 pub fn add_on_end_cbs(cutscene: &Rc<Cutscene>) {
@@ -49,7 +103,30 @@ pub fn add_on_end_cbs(cutscene: &Rc<Cutscene>) {
 impl WidgetKind for CutsceneWindow {
     widget_kind!(NAME);
 
+    fn update(&mut self, widget: &Rc<RefCell<Widget>>, millis: u32) {
+        let duration = match self.cutscene.frames.get(self.frame_index) {
+            None => return,
+            Some(frame) => match frame.duration {
+                None => return,
+                Some(duration) => duration,
+            },
+        };
+
+        self.frame_elapsed += millis;
+        if self.frame_elapsed > duration {
+            self.advance(widget);
+        }
+    }
+
     fn on_add(&mut self, widget: &Rc<RefCell<Widget>>) -> Vec<Rc<RefCell<Widget>>> {
+        let pc = GameState::player();
+        while let Some(frame) = self.cutscene.frames.get(self.frame_index) {
+            match &frame.condition {
+                Some(cond) if !condition::is_met(cond, &pc, &pc) => self.frame_index += 1,
+                _ => break,
+            }
+        }
+
         let frame = self.cutscene.frames.get(self.frame_index);
         let frame = match frame {
             None => {
@@ -60,6 +137,22 @@ impl WidgetKind for CutsceneWindow {
             Some(ref frame) => frame,
         };
 
+        let mut children = Vec::new();
+        if self.started_frame != Some(self.frame_index) {
+            self.started_frame = Some(self.frame_index);
+            start_frame(widget, frame);
+
+            if let (CutsceneKind::Area, Some(duration)) = (self.cutscene.kind, frame.duration) {
+                let block = [OnTrigger::BlockUI(duration)];
+                trigger_activator::activate(widget, &block, &pc, &pc);
+            }
+
+            if frame.fade {
+                let fade = WindowFade::new(window_fade::Mode::In);
+                children.push(Widget::with_theme(fade, "frame_fade"));
+            }
+        }
+
         let close = Widget::with_theme(Button::empty(), "close");
 
         let cutscene = Rc::clone(&self.cutscene);
@@ -79,14 +172,29 @@ impl WidgetKind for CutsceneWindow {
             .state
             .add_callback(Callback::new(Rc::new(|widget, _| {
                 let (parent, window) = Widget::parent_mut::<CutsceneWindow>(widget);
-                window.frame_index += 1;
-                parent.borrow_mut().invalidate_children();
+                window.advance(&parent);
             })));
 
-        let text_area = Widget::with_defaults(TextArea::empty());
+        let background = Widget::empty("background");
+        if let Some(ref image) = frame.background {
+            background.borrow_mut().state.add_text_arg("image", image);
+        }
 
+        let foreground = Widget::empty("foreground");
+        if let Some(ref image) = frame.foreground {
+            foreground.borrow_mut().state.add_text_arg("image", image);
+        }
+
+        let text_area = Widget::with_defaults(TextArea::empty());
         text_area.borrow_mut().state.add_text_arg("0", &frame.text);
+        text_area.borrow_mut().state.visible = !frame.text.is_empty();
 
-        vec![close, text_area, next_button]
+        let mut widgets = vec![background, foreground, text_area];
+        if self.cutscene.kind == CutsceneKind::Window {
+            widgets.push(close);
+            widgets.push(next_button);
+        }
+        widgets.append(&mut children);
+        widgets
     }
 }
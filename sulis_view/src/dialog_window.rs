@@ -21,10 +21,11 @@ use std::rc::Rc;
 use sulis_core::io::{event, InputAction};
 use sulis_core::ui::{theme, Widget, WidgetKind};
 use sulis_core::widgets::TextArea;
-use sulis_module::{conversation::Response, Conversation, OnTrigger};
+use sulis_module::conversation::{CheckKind, Response, ResponseCheck};
+use sulis_module::{Conversation, OnTrigger};
 use sulis_state::{
-    area_feedback_text::ColorKind, script::entity_with_id, AreaFeedbackText, ChangeListener,
-    EntityState, GameState,
+    area_feedback_text::ColorKind, response_check, script::entity_with_id, AreaFeedbackText,
+    ChangeListener, EntityState, GameState,
 };
 
 use crate::trigger_activator::{activate, is_match, scroll_view};
@@ -38,6 +39,10 @@ pub struct DialogWindow {
     convo: Rc<Conversation>,
     cur_node: String,
 
+    // whether the current node still needs to be added to the conversation
+    // log.  The window may be invalidated several times per node
+    log_node: bool,
+
     node: Rc<RefCell<TextArea>>,
 }
 
@@ -55,10 +60,23 @@ impl DialogWindow {
             convo,
             node: TextArea::empty(),
             cur_node,
+            log_node: true,
         }))
     }
 }
 
+impl DialogWindow {
+    fn speaker_name(&self) -> String {
+        if let Some(speaker) = self.convo.switch_speaker(&self.cur_node) {
+            if let Some(speaker) = entity_with_id(speaker.to_string()) {
+                return speaker.borrow().actor.actor.name.to_string();
+            }
+        }
+
+        self.entity.borrow().actor.actor.name.to_string()
+    }
+}
+
 impl WidgetKind for DialogWindow {
     widget_kind!(NAME);
 
@@ -103,6 +121,11 @@ impl WidgetKind for DialogWindow {
 
         let cur_text = theme::expand_text_args(cur_text, &node_widget.borrow().state);
 
+        if self.log_node {
+            self.log_node = false;
+            GameState::add_conversation_log_line(&self.speaker_name(), &cur_text);
+        }
+
         if responses.is_empty() {
             widget.borrow_mut().mark_for_removal();
 
@@ -141,8 +164,10 @@ impl WidgetKind for DialogWindow {
 
 struct ResponseButton {
     text: String,
+    display_text: String,
     to: Option<String>,
     on_select: Vec<OnTrigger>,
+    check: Option<ResponseCheck>,
     pc: Rc<RefCell<EntityState>>,
     convo: Rc<Conversation>,
 }
@@ -155,8 +180,10 @@ impl ResponseButton {
     ) -> Rc<RefCell<ResponseButton>> {
         Rc::new(RefCell::new(ResponseButton {
             text: response.text.to_string(),
+            display_text: String::new(),
             to: response.to.clone(),
             on_select: response.on_select.clone(),
+            check: response.check.clone(),
             pc: Rc::clone(&pc),
             convo: Rc::clone(convo),
         }))
@@ -185,6 +212,57 @@ impl ResponseButton {
         area.borrow_mut()
             .set_active_entity(Some(Rc::clone(&speaker)));
     }
+
+    /// Makes the check and fires its triggers, returning the node to move to
+    fn make_check(
+        &self,
+        check: &ResponseCheck,
+        widget: &Rc<RefCell<Widget>>,
+        pc: &Rc<RefCell<EntityState>>,
+        target: &Rc<RefCell<EntityState>>,
+    ) -> Option<String> {
+        let checker = response_check::checker(check, pc);
+        let success = response_check::roll(check, &checker.borrow());
+
+        let (result, on_trigger, to) = if success {
+            ("Succeeded", &check.on_success, &check.success)
+        } else {
+            ("Failed", &check.on_failure, &check.failure)
+        };
+
+        let area = GameState::area_state();
+        let mut feedback = AreaFeedbackText::with_target(&checker.borrow(), &area.borrow());
+        let text = format!("{} Check {}", check.attribute.name(), result);
+        GameState::add_conversation_log_line(&checker.borrow().actor.actor.name, &text);
+        feedback.add_entry(text, ColorKind::Info);
+        area.borrow_mut().add_feedback_text(feedback);
+
+        activate(widget, on_trigger, pc, target);
+
+        to.clone().or_else(|| self.to.clone())
+    }
+
+    fn add_check_text_args(&self, widget: &Rc<RefCell<Widget>>) {
+        let check = match self.check {
+            None => return,
+            Some(ref check) => check,
+        };
+
+        let checker = response_check::checker(check, &self.pc);
+        let chance = response_check::chance(check, &checker.borrow());
+
+        let state = &mut widget.borrow_mut().state;
+        state.add_text_arg("check", check.attribute.name());
+        state.add_text_arg("check_difficulty", &check.difficulty.to_string());
+        if let CheckKind::Roll = check.kind {
+            let chance = (chance * 100.0).round() as i32;
+            state.add_text_arg("check_chance", &chance.to_string());
+        }
+
+        if !Rc::ptr_eq(&checker, &self.pc) {
+            state.add_text_arg("check_by", &checker.borrow().actor.actor.name);
+        }
+    }
 }
 
 impl WidgetKind for ResponseButton {
@@ -198,8 +276,10 @@ impl WidgetKind for ResponseButton {
             .borrow_mut()
             .state
             .add_text_arg("player_name", &self.pc.borrow().actor.actor.name);
+        self.add_check_text_args(&text_area_widget);
         let cur_text = theme::expand_text_args(&self.text, &text_area_widget.borrow().state);
 
+        self.display_text = cur_text.clone();
         text_area.borrow_mut().text = Some(cur_text);
         vec![text_area_widget]
     }
@@ -215,12 +295,20 @@ impl WidgetKind for ResponseButton {
 
         let (parent, window) = Widget::parent_mut::<DialogWindow>(widget);
 
+        let pc_name = window.pc.borrow().actor.actor.name.to_string();
+        GameState::add_conversation_log_line(&pc_name, &self.display_text);
+
         activate(widget, &self.on_select, &window.pc, &window.entity);
 
+        let to = match self.check {
+            None => self.to.clone(),
+            Some(ref check) => self.make_check(check, widget, &window.pc, &window.entity),
+        };
+
         let (_, view) = Widget::parent_mut::<RootView>(&parent);
         let (area, _) = view.area_view();
 
-        match self.to {
+        match to {
             None => {
                 parent.borrow_mut().mark_for_removal();
                 area.borrow_mut().set_active_entity(None);
@@ -228,6 +316,7 @@ impl WidgetKind for ResponseButton {
             Some(ref to) => {
                 self.check_switch_speaker(to, &area);
                 window.cur_node = to.to_string();
+                window.log_node = true;
                 parent.borrow_mut().invalidate_children()
             }
         }
@@ -250,6 +339,7 @@ pub fn show_convo(
         feedback.add_entry(convo.text(&initial_node).to_string(), ColorKind::Info);
         area.borrow_mut().add_feedback_text(feedback);
     } else {
+        GameState::start_conversation_log(&convo.id, &target.borrow().actor.actor.name);
         let window = Widget::with_defaults(DialogWindow::new(&pc, &target, convo));
         window.borrow_mut().state.set_modal(true);
 
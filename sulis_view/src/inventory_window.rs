@@ -15,30 +15,32 @@
 //  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
 
 use std::any::Any;
-use std::cell::{Cell, RefCell};
+use std::cell::RefCell;
 use std::rc::Rc;
 use std::time;
 
 use sulis_core::ui::{Callback, Widget, WidgetKind};
 use sulis_core::util;
-use sulis_core::widgets::{Button, Label};
-use sulis_module::{QuickSlot, Slot};
+use sulis_core::widgets::{Button, InputField, Label};
+use sulis_module::{item::format_item_weight, Module, QuickSlot, Slot};
 use sulis_state::{script::ScriptItemKind, ChangeListener, EntityState, GameState};
 
-use crate::{item_callback_handler::*, item_list_pane::Filter, ItemButton, ItemListPane};
+use crate::{item_callback_handler::*, item_list_pane::ListState, ItemButton, ItemListPane};
 
 pub const NAME: &str = "inventory_window";
 
 pub struct InventoryWindow {
     entity: Rc<RefCell<EntityState>>,
-    filter: Rc<Cell<Filter>>,
+    list_state: Rc<ListState>,
+    loadout_name: String,
 }
 
 impl InventoryWindow {
     pub fn new(entity: &Rc<RefCell<EntityState>>) -> Rc<RefCell<InventoryWindow>> {
         Rc::new(RefCell::new(InventoryWindow {
             entity: Rc::clone(entity),
-            filter: Rc::new(Cell::new(Filter::All)),
+            list_state: Rc::new(ListState::default()),
+            loadout_name: String::new(),
         }))
     }
 }
@@ -108,7 +110,7 @@ impl WidgetKind for InventoryWindow {
         let actor = &self.entity.borrow().actor;
 
         let item_list_pane =
-            Widget::with_defaults(ItemListPane::new_entity(&self.entity, &self.filter));
+            Widget::with_defaults(ItemListPane::new_entity(&self.entity, &self.list_state));
 
         let equipped_area = Widget::empty("equipped_area");
         for slot in Slot::iter() {
@@ -168,13 +170,121 @@ impl WidgetKind for InventoryWindow {
             }
         }
 
+        let rules = Module::rules();
+        let weight_label = Widget::with_theme(Label::empty(), "weight_label");
+        {
+            let state = &mut weight_label.borrow_mut().state;
+            state.add_text_arg("weight", &format_weight(actor.carried_weight()));
+            state.add_text_arg("capacity", &format_weight(actor.carry_capacity()));
+            if let Some(index) = actor.encumbrance() {
+                state.add_text_arg("encumbrance", &rules.encumbrance[index].name);
+            }
+        }
+
+        let stash_weight_label = Widget::with_theme(Label::empty(), "stash_weight_label");
+        {
+            let stash = stash.borrow();
+            let state = &mut stash_weight_label.borrow_mut().state;
+            state.add_text_arg("weight", &format_weight(stash.weight()));
+            state.add_text_arg("capacity", &format_weight(stash.capacity()));
+        }
+
         let stash_title = Widget::with_theme(Label::empty(), "stash_title");
 
+        let loadouts = self.create_loadouts_area();
+
         trace!(
             "Inventory window creation time: {}",
             util::format_elapsed_secs(start_time.elapsed())
         );
 
-        vec![close, equipped_area, item_list_pane, stash_title]
+        vec![
+            close,
+            equipped_area,
+            item_list_pane,
+            weight_label,
+            stash_weight_label,
+            stash_title,
+            loadouts,
+        ]
+    }
+}
+
+impl InventoryWindow {
+    fn create_loadouts_area(&self) -> Rc<RefCell<Widget>> {
+        let area = Widget::empty("loadouts");
+
+        let name = Widget::with_theme(InputField::new(&self.loadout_name), "loadout_name");
+        name.borrow_mut()
+            .state
+            .add_callback(Callback::new(Rc::new(|widget, kind| {
+                let (_, window) = Widget::parent_mut::<InventoryWindow>(widget);
+                let field = Widget::downcast::<InputField>(kind);
+                window.loadout_name = field.text.trim().to_string();
+            })));
+        Widget::add_child_to(&area, name);
+
+        let actor = &self.entity.borrow().actor;
+        let loadouts = actor.loadouts();
+
+        let save = Widget::with_theme(Button::empty(), "save_loadout");
+        let entity_ref = Rc::clone(&self.entity);
+        save.borrow_mut()
+            .state
+            .add_callback(Callback::new(Rc::new(move |widget, _| {
+                let (_, window) = Widget::parent::<InventoryWindow>(widget);
+                if window.loadout_name.is_empty() {
+                    return;
+                }
+
+                entity_ref
+                    .borrow_mut()
+                    .actor
+                    .save_loadout(&window.loadout_name);
+            })));
+        Widget::add_child_to(&area, save);
+
+        let list = Widget::empty("loadout_list");
+        let can_apply = actor.can_apply_loadout();
+        for index in 0..Module::rules().max_loadouts {
+            let button = Widget::with_theme(Button::empty(), "loadout");
+            let delete = Widget::with_theme(Button::empty(), "delete_loadout");
+
+            match loadouts.get(index) {
+                None => {
+                    button.borrow_mut().state.set_enabled(false);
+                    delete.borrow_mut().state.set_visible(false);
+                }
+                Some(loadout) => {
+                    let entity_ref = Rc::clone(&self.entity);
+                    let mut button = button.borrow_mut();
+                    button.state.add_text_arg("name", &loadout.name);
+                    button.state.set_enabled(can_apply);
+                    button
+                        .state
+                        .add_callback(Callback::new(Rc::new(move |_, _| {
+                            EntityState::apply_loadout(&entity_ref, index);
+                        })));
+
+                    let entity_ref = Rc::clone(&self.entity);
+                    delete
+                        .borrow_mut()
+                        .state
+                        .add_callback(Callback::new(Rc::new(move |_, _| {
+                            entity_ref.borrow_mut().actor.delete_loadout(index);
+                        })));
+                }
+            }
+
+            Widget::add_child_to(&list, button);
+            Widget::add_child_to(&list, delete);
+        }
+        Widget::add_child_to(&area, list);
+
+        area
     }
 }
+
+fn format_weight(weight: u32) -> String {
+    format_item_weight(weight as i32)
+}
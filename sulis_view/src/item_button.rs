@@ -19,7 +19,8 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use crate::bonus_text_arg_handler::{
-    add_attack_text_args, add_bonus_text_args, add_prereq_text_args,
+    add_attack_bonus_text_args, add_attack_text_args, add_bonus_text_args,
+    add_comparison_text_args, add_prereq_text_args, format_bonus_or_penalty,
 };
 use crate::item_callback_handler::sell_item_cb;
 use crate::{ItemActionMenu, MerchantWindow, RootView};
@@ -31,8 +32,8 @@ use sulis_module::{
     item::{format_item_value, format_item_weight},
     Module,
 };
-use sulis_module::{ItemState, QuickSlot, Slot};
-use sulis_state::{inventory::has_proficiency, EntityState, GameState};
+use sulis_module::{ItemSet, ItemState, QuickSlot, Slot};
+use sulis_state::{inventory::has_proficiency, EntityState, GameState, MerchantState};
 
 enum Kind {
     Prop {
@@ -43,6 +44,10 @@ enum Kind {
         id: String,
         item_index: usize,
     },
+    Buyback {
+        id: String,
+        item_index: usize,
+    },
     Inventory {
         item_index: usize,
     },
@@ -69,6 +74,7 @@ pub struct ItemButton {
     kind: Kind,
     actions: Vec<ButtonAction>,
     keyboard_shortcut: Option<Key>,
+    junk: bool,
 
     item_window: Option<Rc<RefCell<Widget>>>,
 }
@@ -135,6 +141,21 @@ impl ItemButton {
         )
     }
 
+    pub fn buyback(
+        item: &ItemState,
+        item_index: usize,
+        merchant_id: &str,
+    ) -> Rc<RefCell<ItemButton>> {
+        ItemButton::new(
+            item,
+            1,
+            Kind::Buyback {
+                id: merchant_id.to_string(),
+                item_index,
+            },
+        )
+    }
+
     fn new(item: &ItemState, quantity: u32, kind: Kind) -> Rc<RefCell<ItemButton>> {
         let icon = item.icon().id();
         let adjective_icons = item.item.adjective_icons();
@@ -147,6 +168,7 @@ impl ItemButton {
             actions: Vec::new(),
             item_window: None,
             keyboard_shortcut: None,
+            junk: false,
         }))
     }
 
@@ -154,6 +176,11 @@ impl ItemButton {
         self.keyboard_shortcut = key;
     }
 
+    /// Marks the item shown by this button as junk in its tooltip
+    pub fn set_junk(&mut self) {
+        self.junk = true;
+    }
+
     pub fn fire_left_click_action(&mut self, widget: &Rc<RefCell<Widget>>) {
         let sell_action = self.check_sell_action(widget);
         let cb = sell_action
@@ -245,6 +272,13 @@ impl ItemButton {
                     Some(&(_, ref item_state)) => Some(item_state.clone()),
                 }
             }
+            Kind::Buyback { ref id, item_index } => {
+                let merchant = area_state.get_merchant(id)?;
+                merchant
+                    .buyback()
+                    .get(item_index)
+                    .map(|entry| entry.item.clone())
+            }
         }
     }
 
@@ -288,6 +322,15 @@ impl ItemButton {
                     item_window
                         .state
                         .add_text_arg("price", &format_item_value(value));
+                    add_price_modifier_text_args(merchant, item_window, -1.0);
+                }
+            }
+            Kind::Buyback { ref id, item_index } => {
+                let merchant = area_state.get_merchant(id);
+                if let Some(entry) = merchant.and_then(|m| m.buyback().get(item_index)) {
+                    item_window
+                        .state
+                        .add_text_arg("price", &format_item_value(entry.price));
                 }
             }
             Kind::Inventory { .. } | Kind::Equipped { .. } => {
@@ -303,11 +346,56 @@ impl ItemButton {
                     item_window
                         .state
                         .add_text_arg("price", &format_item_value(value));
+                    add_price_modifier_text_args(merchant, item_window, 1.0);
+
+                    let scarcity = merchant.scarcity_frac(item_state) - 1.0;
+                    add_price_percent(item_window, "price_scarcity", scarcity);
                 }
             }
             _ => (),
         }
     }
+
+    fn add_set_text_args(&self, set: &ItemSet, item_window: &mut Widget) {
+        let player = match &self.kind {
+            Kind::Equipped { player, .. } | Kind::Quick { player, .. } => Some(Rc::clone(player)),
+            _ => GameState::selected().into_iter().next(),
+        };
+        let pieces = player.map_or(0, |player| {
+            player.borrow().actor.inventory().set_pieces(set)
+        });
+
+        let state = &mut item_window.state;
+        state.add_text_arg("item_set", &set.name);
+        state.add_text_arg("item_set_pieces", &pieces.to_string());
+        state.add_text_arg("item_set_total", &set.items.len().to_string());
+        state.add_text_arg("item_set_bonuses", &set.bonuses.len().to_string());
+
+        let active = set.active_bonuses(pieces).count();
+        state.add_text_arg("item_set_active", &active.to_string());
+        if let Some(next) = set.bonuses.get(active) {
+            state.add_text_arg("item_set_next", &next.pieces.to_string());
+        }
+    }
+}
+
+/// Adds the reputation and haggling price modifiers as percentages.  `sign`
+/// is positive for the price paid to the party, negative for the price
+/// paid by the party
+fn add_price_modifier_text_args(merchant: &MerchantState, item_window: &mut Widget, sign: f32) {
+    let reputation = merchant.reputation_modifier() * sign;
+    add_price_percent(item_window, "price_reputation", reputation);
+
+    let haggle = merchant.haggle_modifier() * sign;
+    add_price_percent(item_window, "price_haggle", haggle);
+}
+
+fn add_price_percent(item_window: &mut Widget, name: &str, frac: f32) {
+    let percent = (frac * 100.0).round() as i32;
+    if percent != 0 {
+        let text = format!("{}%", format_bonus_or_penalty(percent));
+        item_window.state.add_text_arg(name, &text);
+    }
 }
 
 impl WidgetKind for ItemButton {
@@ -375,8 +463,15 @@ impl WidgetKind for ItemButton {
                     .add_text_arg("keybinding", &key.short_name());
             }
 
+            if self.junk {
+                item_window.state.add_text_arg("junk", "true");
+            }
+
             match self.kind {
-                Kind::Prop { .. } | Kind::Inventory { .. } | Kind::Merchant { .. } => {
+                Kind::Prop { .. }
+                | Kind::Inventory { .. }
+                | Kind::Merchant { .. }
+                | Kind::Buyback { .. } => {
                     let player = GameState::selected();
                     if !player.is_empty() {
                         if !has_proficiency(&item_state, &player[0].borrow().actor.stats) {
@@ -401,6 +496,12 @@ impl WidgetKind for ItemButton {
                                 );
                             }
                         }
+
+                        if item_state.item.equippable.is_some() {
+                            let actor = &player[0].borrow().actor;
+                            let stats = actor.stats_with_item(&item_state);
+                            add_comparison_text_args(&actor.stats, &stats, &mut item_window.state);
+                        }
                     }
                 }
                 _ => (),
@@ -415,8 +516,16 @@ impl WidgetKind for ItemButton {
             item_window
                 .state
                 .add_text_arg("weight", &format_item_weight(item_state.item.weight));
+            if let (Some(cur), Some(max)) = (item_state.durability(), item_state.max_durability()) {
+                item_window.state.add_text_arg("durability", &cur.to_string());
+                item_window.state.add_text_arg("max_durability", &max.to_string());
+            }
             self.add_price_text_arg(&root, &mut item_window, &item_state);
 
+            if let Some(set) = Module::item_set_for(&item_state.item.original_id) {
+                self.add_set_text_args(&set, &mut item_window);
+            }
+
             if let Some(ref prereqs) = &item_state.item.prereqs {
                 add_prereq_text_args(prereqs, &mut item_window.state);
             }
@@ -454,6 +563,18 @@ impl WidgetKind for ItemButton {
                     add_bonus_text_args(&equippable.bonuses, &mut item_window.state);
                 }
             }
+
+            if let Some(ref ammo) = item_state.item.ammo {
+                let kinds: Vec<String> = ammo
+                    .weapon_kinds
+                    .iter()
+                    .map(|kind| format!("{:?}", kind))
+                    .collect();
+                item_window
+                    .state
+                    .add_text_arg("ammo_for", &kinds.join(", "));
+                add_attack_bonus_text_args(&ammo.bonuses, &mut item_window.state);
+            }
         }
         Widget::add_child_to(&root, Rc::clone(&item_window));
         self.item_window = Some(item_window);
@@ -64,6 +64,22 @@ pub fn set_quickslot_cb(entity: &Rc<RefCell<EntityState>>, index: usize) -> Call
     }))
 }
 
+pub fn set_ammo_cb(entity: &Rc<RefCell<EntityState>>, index: usize) -> Callback {
+    let entity = Rc::clone(entity);
+    Callback::new(Rc::new(move |_, _| {
+        let stash = GameState::party_stash();
+        let item = match stash.borrow_mut().remove_item(index) {
+            None => return,
+            Some(item) => item,
+        };
+
+        let to_add = entity.borrow_mut().actor.set_quick(item, QuickSlot::Ammo);
+        if let Some(item) = to_add {
+            stash.borrow_mut().add_item(1, item);
+        }
+    }))
+}
+
 pub fn use_item_cb(entity: &Rc<RefCell<EntityState>>, kind: ScriptItemKind) -> Callback {
     let entity = Rc::clone(entity);
     Callback::new(Rc::new(move |_, _| {
@@ -141,6 +157,35 @@ pub fn buy_item_cb(merchant_id: &str, index: usize) -> Callback {
     }))
 }
 
+pub fn buy_back_item_cb(merchant_id: &str, index: usize) -> Callback {
+    let merchant_id = merchant_id.to_string();
+    Callback::with(Box::new(move || {
+        let area_state = GameState::area_state();
+        let mut area_state = area_state.borrow_mut();
+
+        let mut merchant = area_state.get_merchant_mut(&merchant_id);
+        let merchant = match merchant {
+            None => return,
+            Some(ref mut merchant) => merchant,
+        };
+
+        let value = match merchant.buyback().get(index) {
+            None => return,
+            Some(entry) => entry.price,
+        };
+
+        if GameState::party_coins() < value {
+            return;
+        }
+
+        if let Some(entry) = merchant.remove_buyback(index) {
+            GameState::add_party_coins(-value);
+            let stash = GameState::party_stash();
+            stash.borrow_mut().add_item(1, entry.item);
+        }
+    }))
+}
+
 pub fn sell_item_cb(entity: &Rc<RefCell<EntityState>>, index: usize) -> Callback {
     let entity = Rc::clone(entity);
     Callback::new(Rc::new(move |widget, _| {
@@ -164,9 +209,8 @@ pub fn sell_item_cb(entity: &Rc<RefCell<EntityState>>, index: usize) -> Callback
         let stash = GameState::party_stash();
         let item_state = stash.borrow_mut().remove_item(index);
         if let Some(item_state) = item_state {
-            let value = merchant.get_sell_price(&item_state);
+            let value = merchant.sell(item_state);
             GameState::add_party_coins(value);
-            merchant.add(item_state);
         }
 
         let actor = &entity.borrow().actor;
@@ -174,6 +218,48 @@ pub fn sell_item_cb(entity: &Rc<RefCell<EntityState>>, index: usize) -> Callback
     }))
 }
 
+pub fn set_junk_cb(index: usize, junk: bool) -> Callback {
+    Callback::with(Box::new(move || {
+        let stash = GameState::party_stash();
+        stash.borrow_mut().set_junk(index, junk);
+    }))
+}
+
+/// Sells every item in the party stash marked as junk to the merchant
+pub fn sell_junk_cb(merchant_id: &str) -> Callback {
+    let merchant_id = merchant_id.to_string();
+    Callback::with(Box::new(move || {
+        let area_state = GameState::area_state();
+        let mut area_state = area_state.borrow_mut();
+        let mut merchant = area_state.get_merchant_mut(&merchant_id);
+        let merchant = match merchant {
+            None => return,
+            Some(ref mut merchant) => merchant,
+        };
+
+        let stash = GameState::party_stash();
+        let indices = stash.borrow().junk_indices();
+        let mut total = 0;
+        for index in indices {
+            let qty = match stash.borrow().items().get(index) {
+                None => continue,
+                Some((qty, _)) => *qty,
+            };
+
+            for _ in 0..qty {
+                let item_state = stash.borrow_mut().remove_item(index);
+                if let Some(item_state) = item_state {
+                    total += merchant.sell(item_state);
+                }
+            }
+        }
+
+        GameState::add_party_coins(total);
+        let stash = &stash.borrow();
+        stash.listeners.notify(stash);
+    }))
+}
+
 pub fn drop_item_cb(entity: &Rc<RefCell<EntityState>>, index: usize) -> Callback {
     let entity = Rc::clone(entity);
     Callback::new(Rc::new(move |widget, _| {
@@ -19,9 +19,9 @@ use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 use sulis_core::ui::{Callback, Widget, WidgetKind};
-use sulis_core::widgets::{Button, ScrollDirection, ScrollPane};
+use sulis_core::widgets::{Button, InputField, ScrollDirection, ScrollPane};
 use sulis_module::{Item, ItemState, Module};
-use sulis_state::{script::ScriptItemKind, EntityState, GameState};
+use sulis_state::{item_list, script::ScriptItemKind, EntityState, GameState, ItemList};
 
 use crate::{item_callback_handler::*, ItemButton};
 
@@ -30,6 +30,7 @@ pub const NAME: &str = "item_list_pane";
 enum Kind {
     Entity,
     Merchant(String),
+    Buyback(String),
     Prop(usize),
 }
 
@@ -47,7 +48,7 @@ impl Filter {
         use self::Filter::*;
         match self {
             All => true,
-            Weapon => item.is_weapon(),
+            Weapon => item.is_weapon() || item.ammo.is_some(),
             Armor => item.is_armor(),
             Accessory => {
                 if item.is_weapon() || item.is_armor() {
@@ -63,53 +64,113 @@ impl Filter {
 use self::Filter::*;
 const FILTERS_LIST: [Filter; 5] = [All, Weapon, Armor, Accessory, Usable];
 
+/// The filter and search text for an item list.  This is owned by the
+/// parent window so it persists when the list is rebuilt
+pub struct ListState {
+    filter: Cell<Filter>,
+    search: RefCell<String>,
+}
+
+impl Default for ListState {
+    fn default() -> ListState {
+        ListState {
+            filter: Cell::new(Filter::All),
+            search: RefCell::new(String::new()),
+        }
+    }
+}
+
+impl ListState {
+    /// Returns the indices of the items in the list that pass the current
+    /// filter and search, in the party's chosen sort order
+    fn indices(&self, items: &ItemList) -> Vec<usize> {
+        let sort_mode = GameState::party_stash().borrow().sort_mode();
+        items
+            .sorted_indices(sort_mode, &self.search.borrow())
+            .into_iter()
+            .filter(|index| self.filter.get().is_allowed(&items[*index].1.item))
+            .collect()
+    }
+}
+
 pub struct ItemListPane {
     entity: Rc<RefCell<EntityState>>,
     kind: Kind,
-    cur_filter: Rc<Cell<Filter>>,
+    list_state: Rc<ListState>,
+    content: Option<Rc<RefCell<Widget>>>,
 }
 
 impl ItemListPane {
     fn new(
         entity: &Rc<RefCell<EntityState>>,
         kind: Kind,
-        cur_filter: &Rc<Cell<Filter>>,
+        list_state: &Rc<ListState>,
     ) -> Rc<RefCell<ItemListPane>> {
         Rc::new(RefCell::new(ItemListPane {
             entity: Rc::clone(entity),
             kind,
-            cur_filter: Rc::clone(cur_filter),
+            list_state: Rc::clone(list_state),
+            content: None,
         }))
     }
 
     pub fn new_entity(
         entity: &Rc<RefCell<EntityState>>,
-        cur_filter: &Rc<Cell<Filter>>,
+        list_state: &Rc<ListState>,
     ) -> Rc<RefCell<ItemListPane>> {
-        ItemListPane::new(entity, Kind::Entity, cur_filter)
+        ItemListPane::new(entity, Kind::Entity, list_state)
     }
 
     pub fn new_prop(
         entity: &Rc<RefCell<EntityState>>,
         prop_index: usize,
-        cur_filter: &Rc<Cell<Filter>>,
+        list_state: &Rc<ListState>,
     ) -> Rc<RefCell<ItemListPane>> {
-        ItemListPane::new(entity, Kind::Prop(prop_index), cur_filter)
+        ItemListPane::new(entity, Kind::Prop(prop_index), list_state)
     }
 
     pub fn new_merchant(
         entity: &Rc<RefCell<EntityState>>,
         merchant_id: String,
-        cur_filter: &Rc<Cell<Filter>>,
+        list_state: &Rc<ListState>,
+    ) -> Rc<RefCell<ItemListPane>> {
+        ItemListPane::new(entity, Kind::Merchant(merchant_id), list_state)
+    }
+
+    pub fn new_buyback(
+        entity: &Rc<RefCell<EntityState>>,
+        merchant_id: String,
+        list_state: &Rc<ListState>,
     ) -> Rc<RefCell<ItemListPane>> {
-        ItemListPane::new(entity, Kind::Merchant(merchant_id), cur_filter)
+        ItemListPane::new(entity, Kind::Buyback(merchant_id), list_state)
     }
 
     fn set_filter(&mut self, filter: Filter, widget: &Rc<RefCell<Widget>>) {
-        self.cur_filter.set(filter);
+        self.list_state.filter.set(filter);
         widget.borrow_mut().invalidate_children();
     }
 
+    /// Rebuilds only the list of items, leaving the other children such as
+    /// the search field in place
+    fn refresh_content(&mut self, widget: &Rc<RefCell<Widget>>) {
+        if let Some(content) = self.content.take() {
+            content.borrow_mut().mark_for_removal();
+        }
+
+        let content = self.create_content();
+        Widget::add_child_to(widget, Rc::clone(&content));
+        self.content = Some(content);
+    }
+
+    fn create_content(&self) -> Rc<RefCell<Widget>> {
+        match &self.kind {
+            Kind::Entity => self.create_content_inventory(),
+            Kind::Prop(index) => self.create_content_prop(*index),
+            Kind::Merchant(id) => self.create_content_merchant(id),
+            Kind::Buyback(id) => self.create_content_buyback(id),
+        }
+    }
+
     fn create_content_merchant(&self, merchant_id: &str) -> Rc<RefCell<Widget>> {
         let area_state = GameState::area_state();
         let area_state = area_state.borrow();
@@ -121,11 +182,9 @@ impl ItemListPane {
 
         let scrollpane = ScrollPane::new(ScrollDirection::Vertical);
         let list_content = Widget::with_theme(scrollpane.clone(), "items_list");
-        for (index, &(qty, ref item)) in merchant.items().iter().enumerate() {
-            if !self.cur_filter.get().is_allowed(&item.item) {
-                continue;
-            }
-
+        let items = merchant.items();
+        for index in self.list_state.indices(items) {
+            let (qty, ref item) = items[index];
             let item_button = ItemButton::merchant(&item, qty, index, merchant_id);
             item_button
                 .borrow_mut()
@@ -139,6 +198,39 @@ impl ItemListPane {
         list_content
     }
 
+    fn create_content_buyback(&self, merchant_id: &str) -> Rc<RefCell<Widget>> {
+        let area_state = GameState::area_state();
+        let area_state = area_state.borrow();
+        let merchant = match area_state.get_merchant(merchant_id) {
+            None => return Widget::empty("none"),
+            Some(merchant) => merchant,
+        };
+
+        let scrollpane = ScrollPane::new(ScrollDirection::Vertical);
+        let list_content = Widget::with_theme(scrollpane.clone(), "items_list");
+        let search = self.list_state.search.borrow().trim().to_lowercase();
+        for (index, entry) in merchant.buyback().iter().enumerate() {
+            if !self.list_state.filter.get().is_allowed(&entry.item.item)
+                || !item_list::matches_search(&entry.item, &search)
+            {
+                continue;
+            }
+
+            let item_button = ItemButton::buyback(&entry.item, index, merchant_id);
+            item_button.borrow_mut().add_action(
+                "Buy Back",
+                buy_back_item_cb(merchant_id, index),
+                true,
+            );
+
+            scrollpane
+                .borrow()
+                .add_to_content(Widget::with_defaults(item_button));
+        }
+
+        list_content
+    }
+
     fn create_content_prop(&self, prop_index: usize) -> Rc<RefCell<Widget>> {
         let combat_active = GameState::is_combat_active();
 
@@ -151,11 +243,8 @@ impl ItemListPane {
         match prop.items() {
             None => (),
             Some(ref items) => {
-                for (index, &(qty, ref item)) in items.iter().enumerate() {
-                    if !self.cur_filter.get().is_allowed(&item.item) {
-                        continue;
-                    }
-
+                for index in self.list_state.indices(items) {
+                    let (qty, ref item) = items[index];
                     let item_button = ItemButton::prop(&item, qty, index, prop_index);
                     if !combat_active {
                         item_button.borrow_mut().add_action(
@@ -184,12 +273,14 @@ impl ItemListPane {
 
         let stash = GameState::party_stash();
         let stash = stash.borrow();
-        for (index, &(quantity, ref item)) in stash.items().iter().enumerate() {
-            if !self.cur_filter.get().is_allowed(&item.item) {
-                continue;
-            }
-
+        let items = stash.items();
+        for index in self.list_state.indices(items) {
+            let (quantity, ref item) = items[index];
             let item_but = ItemButton::inventory(&item, quantity, index);
+            let junk = stash.is_junk(item);
+            if junk {
+                item_but.borrow_mut().set_junk();
+            }
 
             if let Some(ref usable) = item.item.usable {
                 if !combat_active && item.item.meets_prereqs(&actor.actor) {
@@ -207,12 +298,23 @@ impl ItemListPane {
                 }
             }
 
+            if item.item.ammo.is_some() && !combat_active {
+                item_but
+                    .borrow_mut()
+                    .add_action("Load Ammo", set_ammo_cb(&self.entity, index), true);
+            }
+
             if !combat_active && actor.can_equip(&item) {
                 item_but
                     .borrow_mut()
                     .add_action("Equip", equip_item_cb(&self.entity, index), true);
             }
 
+            let junk_label = if junk { "Unmark Junk" } else { "Mark as Junk" };
+            item_but
+                .borrow_mut()
+                .add_action(junk_label, set_junk_cb(index, !junk), false);
+
             if !combat_active {
                 item_but
                     .borrow_mut()
@@ -234,13 +336,40 @@ impl WidgetKind for ItemListPane {
     fn on_add(&mut self, _widget: &Rc<RefCell<Widget>>) -> Vec<Rc<RefCell<Widget>>> {
         let mut children = Vec::new();
 
-        let content = match &self.kind {
-            Kind::Entity => self.create_content_inventory(),
-            Kind::Prop(index) => self.create_content_prop(*index),
-            Kind::Merchant(id) => self.create_content_merchant(id),
-        };
+        let content = self.create_content();
+        self.content = Some(Rc::clone(&content));
         children.push(content);
 
+        let search = InputField::new(&self.list_state.search.borrow());
+        let search = Widget::with_theme(search, "search");
+        search
+            .borrow_mut()
+            .state
+            .add_callback(Callback::new(Rc::new(|widget, kind| {
+                let (parent, pane) = Widget::parent_mut::<ItemListPane>(widget);
+                let field = Widget::downcast::<InputField>(kind);
+
+                *pane.list_state.search.borrow_mut() = field.text.to_string();
+                pane.refresh_content(&parent);
+            })));
+        children.push(search);
+
+        let sort_mode = GameState::party_stash().borrow().sort_mode();
+        let sort = Widget::with_theme(Button::empty(), "sort");
+        sort.borrow_mut()
+            .state
+            .add_text_arg("sort", &format!("{:?}", sort_mode));
+        sort.borrow_mut()
+            .state
+            .add_callback(Callback::new(Rc::new(|widget, _| {
+                let (parent, _) = Widget::parent::<ItemListPane>(widget);
+                let stash = GameState::party_stash();
+                let next = stash.borrow().sort_mode().next();
+                stash.borrow_mut().set_sort_mode(next);
+                parent.borrow_mut().invalidate_children();
+            })));
+        children.push(sort);
+
         if let Kind::Entity = &self.kind {
             let coins_item = match Module::item(&Module::rules().coins_item) {
                 None => {
@@ -272,7 +401,7 @@ impl WidgetKind for ItemListPane {
                     let (parent, pane) = Widget::parent_mut::<ItemListPane>(widget);
                     pane.set_filter(filter, &parent);
                 })));
-            if filter == self.cur_filter.get() {
+            if filter == self.list_state.filter.get() {
                 button.borrow_mut().state.set_active(true);
             }
             children.push(button);
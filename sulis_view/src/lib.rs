@@ -57,6 +57,9 @@ pub use self::class_pane::ClassPane;
 mod console_window;
 pub use self::console_window::ConsoleWindow;
 
+mod crafting_window;
+pub use self::crafting_window::CraftingWindow;
+
 mod cutscene_window;
 pub use self::cutscene_window::CutsceneWindow;
 
@@ -15,21 +15,23 @@
 //  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
 
 use std::any::Any;
-use std::cell::{Cell, RefCell};
+use std::cell::RefCell;
 use std::rc::Rc;
 
 use sulis_core::ui::{Callback, Widget, WidgetKind};
-use sulis_core::widgets::Button;
+use sulis_core::widgets::{Button, Label};
+use sulis_module::item::format_item_value;
 use sulis_state::{ChangeListener, EntityState, GameState};
 
-use crate::{item_list_pane::Filter, ItemListPane};
+use crate::{item_callback_handler::sell_junk_cb, item_list_pane::ListState, ItemListPane};
 
 pub const NAME: &str = "merchant_window";
 
 pub struct MerchantWindow {
     merchant_id: String,
     player: Rc<RefCell<EntityState>>,
-    filter: Rc<Cell<Filter>>,
+    list_state: Rc<ListState>,
+    show_buyback: bool,
 }
 
 impl MerchantWindow {
@@ -37,7 +39,8 @@ impl MerchantWindow {
         Rc::new(RefCell::new(MerchantWindow {
             merchant_id: merchant_id.to_string(),
             player,
-            filter: Rc::new(Cell::new(Filter::All)),
+            list_state: Rc::new(ListState::default()),
+            show_buyback: false,
         }))
     }
 
@@ -64,9 +67,20 @@ impl WidgetKind for MerchantWindow {
         if let Some(ref mut merchant) = area_state.get_merchant_mut(&self.merchant_id) {
             merchant.listeners.remove(NAME);
         }
+
+        let stash = GameState::party_stash();
+        stash.borrow_mut().listeners.remove(NAME);
     }
 
     fn on_add(&mut self, widget: &Rc<RefCell<Widget>>) -> Vec<Rc<RefCell<Widget>>> {
+        let stash = GameState::party_stash();
+        stash
+            .borrow_mut()
+            .listeners
+            .add(ChangeListener::invalidate(NAME, widget));
+
+        let repair = Widget::with_theme(Button::empty(), "repair");
+        let reputation = Widget::with_theme(Label::empty(), "reputation");
         {
             let area_state = GameState::area_state();
             let mut area_state = area_state.borrow_mut();
@@ -80,8 +94,57 @@ impl WidgetKind for MerchantWindow {
             merchant
                 .listeners
                 .add(ChangeListener::invalidate(NAME, widget));
+
+            reputation
+                .borrow_mut()
+                .state
+                .add_text_arg("reputation", &merchant.reputation().to_string());
+
+            let mut repair = repair.borrow_mut();
+            match merchant.party_repair_price() {
+                None => repair.state.set_visible(false),
+                Some(price) => {
+                    repair.state.add_text_arg("price", &format_item_value(price));
+                    repair
+                        .state
+                        .set_enabled(price > 0 && price <= GameState::party_coins());
+                }
+            }
         }
 
+        let merchant_id = self.merchant_id.to_string();
+        repair
+            .borrow_mut()
+            .state
+            .add_callback(Callback::new(Rc::new(move |widget, _| {
+                let area_state = GameState::area_state();
+                if let Some(merchant) = area_state.borrow().get_merchant(&merchant_id) {
+                    merchant.repair_party_items();
+                }
+
+                let (parent, _) = Widget::parent::<MerchantWindow>(widget);
+                parent.borrow_mut().invalidate_children();
+            })));
+
+        let buyback = Widget::with_theme(Button::empty(), "buyback");
+        buyback.borrow_mut().state.set_active(self.show_buyback);
+        buyback
+            .borrow_mut()
+            .state
+            .add_callback(Callback::new(Rc::new(|widget, _| {
+                let (parent, window) = Widget::parent_mut::<MerchantWindow>(widget);
+                window.show_buyback = !window.show_buyback;
+                parent.borrow_mut().invalidate_children();
+            })));
+
+        let sell_junk = Widget::with_theme(Button::empty(), "sell_junk");
+        let has_junk = !stash.borrow().junk_indices().is_empty();
+        sell_junk.borrow_mut().state.set_enabled(has_junk);
+        sell_junk
+            .borrow_mut()
+            .state
+            .add_callback(sell_junk_cb(&self.merchant_id));
+
         let close = Widget::with_theme(Button::empty(), "close");
         close
             .borrow_mut()
@@ -91,12 +154,20 @@ impl WidgetKind for MerchantWindow {
                 parent.borrow_mut().mark_for_removal();
             })));
 
-        let item_list_pane = Widget::with_defaults(ItemListPane::new_merchant(
-            &self.player,
-            self.merchant_id.to_string(),
-            &self.filter,
-        ));
-
-        vec![close, item_list_pane]
+        let pane = if self.show_buyback {
+            ItemListPane::new_buyback(&self.player, self.merchant_id.to_string(), &self.list_state)
+        } else {
+            ItemListPane::new_merchant(&self.player, self.merchant_id.to_string(), &self.list_state)
+        };
+        let item_list_pane = Widget::with_defaults(pane);
+
+        vec![
+            close,
+            repair,
+            buyback,
+            sell_junk,
+            reputation,
+            item_list_pane,
+        ]
     }
 }
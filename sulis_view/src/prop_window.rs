@@ -15,10 +15,10 @@
 //  along with Sulis.  If not, see <http://www.gnu.org/licenses/>
 
 use std::any::Any;
-use std::cell::{Cell, RefCell};
+use std::cell::RefCell;
 use std::rc::Rc;
 
-use crate::{item_list_pane::Filter, ItemListPane, RootView};
+use crate::{item_list_pane::ListState, ItemListPane, RootView};
 use sulis_core::ui::{Callback, Widget, WidgetKind};
 use sulis_core::widgets::{Button, Label};
 use sulis_state::{ChangeListener, EntityState, GameState};
@@ -28,7 +28,7 @@ pub const NAME: &str = "prop_window";
 pub struct PropWindow {
     prop_index: usize,
     player: Rc<RefCell<EntityState>>,
-    filter: Rc<Cell<Filter>>,
+    list_state: Rc<ListState>,
 }
 
 impl PropWindow {
@@ -36,7 +36,7 @@ impl PropWindow {
         Rc::new(RefCell::new(PropWindow {
             prop_index,
             player,
-            filter: Rc::new(Cell::new(Filter::All)),
+            list_state: Rc::new(ListState::default()),
         }))
     }
 
@@ -124,7 +124,7 @@ impl WidgetKind for PropWindow {
         let item_list_pane = Widget::with_defaults(ItemListPane::new_prop(
             &self.player,
             self.prop_index,
-            &self.filter,
+            &self.list_state,
         ));
 
         vec![icon, close, item_list_pane, take_all]
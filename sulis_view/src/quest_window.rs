@@ -18,16 +18,21 @@ use std::any::Any;
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use sulis_core::ui::{Callback, Widget, WidgetKind};
+use sulis_core::ui::{Callback, Widget, WidgetKind, WidgetState};
 use sulis_core::widgets::{Button, Label, ScrollDirection, ScrollPane, TextArea};
+use sulis_module::item::format_item_value;
+use sulis_module::quest::{QuestEntry, QuestRewards};
 use sulis_module::{on_trigger::QuestEntryState, Module, Quest};
-use sulis_state::{ChangeListener, GameState};
+use sulis_state::{ChangeListener, GameState, QuestState};
 
 pub const NAME: &str = "quest_window";
 
 pub struct QuestWindow {
     active_quest: Option<Rc<Quest>>,
     show_completed: bool,
+    show_conversations: bool,
+    active_conversation: Option<usize>,
+    show_factions: bool,
 }
 
 impl QuestWindow {
@@ -35,8 +40,94 @@ impl QuestWindow {
         Rc::new(RefCell::new(QuestWindow {
             active_quest: None,
             show_completed: false,
+            show_conversations: false,
+            active_conversation: None,
+            show_factions: false,
         }))
     }
+
+    fn create_faction_pane(&self) -> Rc<RefCell<Widget>> {
+        let pane = ScrollPane::new(ScrollDirection::Vertical);
+        let widget = Widget::with_theme(pane.clone(), "faction_list");
+
+        let campaign = Module::campaign();
+        let mut factions: Vec<_> = campaign.factions.iter().collect();
+        factions.sort_by_key(|(_, faction)| &faction.name);
+
+        for (id, faction) in factions {
+            let reputation = GameState::faction_reputation(id);
+
+            let entry = Widget::with_theme(TextArea::empty(), "faction_entry");
+            {
+                let state = &mut entry.borrow_mut().state;
+                state.add_text_arg("name", &faction.name);
+                state.add_text_arg("description", &faction.description);
+                state.add_text_arg("reputation", &reputation.to_string());
+                if faction.is_hostile(reputation) {
+                    state.add_text_arg("hostile", "true");
+                }
+            }
+            pane.borrow().add_to_content(entry);
+        }
+
+        widget
+    }
+
+    fn create_conversation_panes(&mut self) -> (Rc<RefCell<Widget>>, Rc<RefCell<Widget>>) {
+        let log = GameState::conversation_log();
+        if self.active_conversation.is_none() && !log.is_empty() {
+            self.active_conversation = Some(log.len() - 1);
+        }
+
+        let list_pane = ScrollPane::new(ScrollDirection::Vertical);
+        let list_widget = Widget::with_theme(list_pane.clone(), "quest_list");
+
+        // show the most recent conversations first
+        for (index, record) in log.iter().enumerate().rev() {
+            let button = Widget::with_theme(Button::empty(), "conversation_button");
+            button
+                .borrow_mut()
+                .state
+                .set_active(self.active_conversation == Some(index));
+
+            button
+                .borrow_mut()
+                .state
+                .add_callback(Callback::new(Rc::new(move |widget, _| {
+                    let (window, quest_window) = Widget::parent_mut::<QuestWindow>(widget);
+                    quest_window.active_conversation = Some(index);
+                    window.borrow_mut().invalidate_children();
+                })));
+
+            let text_area = Widget::with_defaults(TextArea::empty());
+            {
+                let state = &mut text_area.borrow_mut().state;
+                state.add_text_arg("target", &record.target);
+                state.add_text_arg("time", &record.time.to_string());
+            }
+
+            Widget::add_child_to(&button, text_area);
+            list_pane.borrow().add_to_content(button);
+        }
+
+        let lines_pane = ScrollPane::new(ScrollDirection::Vertical);
+        let lines_widget = Widget::with_theme(lines_pane.clone(), "quest_entries");
+
+        let record = self.active_conversation.and_then(|index| log.get(index));
+        if let Some(record) = record {
+            for line in record.lines.iter() {
+                let widget = Widget::with_theme(TextArea::empty(), "conversation_line");
+                {
+                    let state = &mut widget.borrow_mut().state;
+                    state.add_text_arg("speaker", &line.speaker);
+                    state.add_text_arg("text", &line.text);
+                }
+                lines_pane.borrow().add_to_content(widget);
+            }
+        }
+
+        (list_widget, lines_widget)
+    }
 }
 
 impl WidgetKind for QuestWindow {
@@ -82,6 +173,70 @@ impl WidgetKind for QuestWindow {
 
         let show_completed_label = Widget::with_theme(Label::empty(), "show_completed_label");
 
+        let show_conversations_toggle =
+            Widget::with_theme(Button::empty(), "show_conversations_toggle");
+        show_conversations_toggle
+            .borrow_mut()
+            .state
+            .set_active(self.show_conversations);
+        show_conversations_toggle
+            .borrow_mut()
+            .state
+            .add_callback(Callback::new(Rc::new(|widget, _| {
+                let (parent, window) = Widget::parent_mut::<QuestWindow>(widget);
+                let cur = window.show_conversations;
+                window.show_conversations = !cur;
+                window.show_factions = false;
+                window.active_conversation = None;
+                parent.borrow_mut().invalidate_children();
+            })));
+
+        let show_conversations_label =
+            Widget::with_theme(Label::empty(), "show_conversations_label");
+
+        let show_factions_toggle = Widget::with_theme(Button::empty(), "show_factions_toggle");
+        show_factions_toggle
+            .borrow_mut()
+            .state
+            .set_active(self.show_factions);
+        show_factions_toggle
+            .borrow_mut()
+            .state
+            .add_callback(Callback::new(Rc::new(|widget, _| {
+                let (parent, window) = Widget::parent_mut::<QuestWindow>(widget);
+                let cur = window.show_factions;
+                window.show_factions = !cur;
+                window.show_conversations = false;
+                parent.borrow_mut().invalidate_children();
+            })));
+
+        let show_factions_label = Widget::with_theme(Label::empty(), "show_factions_label");
+
+        if self.show_conversations {
+            let (list, lines) = self.create_conversation_panes();
+            return vec![
+                close,
+                list,
+                lines,
+                show_conversations_toggle,
+                show_conversations_label,
+                show_factions_toggle,
+                show_factions_label,
+            ];
+        }
+
+        if self.show_factions {
+            let factions = self.create_faction_pane();
+            return vec![
+                close,
+                factions,
+                show_conversations_toggle,
+                show_conversations_label,
+                show_factions_toggle,
+                show_factions_label,
+            ];
+        }
+
         let quest_list_pane = ScrollPane::new(ScrollDirection::Vertical);
         let quest_list_widget = Widget::with_theme(quest_list_pane.clone(), "quest_list");
 
@@ -159,6 +314,8 @@ impl WidgetKind for QuestWindow {
 
                         if let Some(ref quest_data) = quest.entries.get(id) {
                             state.add_text_arg("description", &quest_data.description);
+                            add_objectives_text_args(state, quest_state, id, quest_data);
+                            add_rewards_text_args(state, &quest_data.rewards);
                         }
                     }
 
@@ -173,6 +330,67 @@ impl WidgetKind for QuestWindow {
             quest_entries_widget,
             show_completed_toggle,
             show_completed_label,
+            show_conversations_toggle,
+            show_conversations_label,
+            show_factions_toggle,
+            show_factions_label,
         ]
     }
 }
+
+fn add_objectives_text_args(
+    state: &mut WidgetState,
+    quest_state: &QuestState,
+    id: &str,
+    entry: &QuestEntry,
+) {
+    if entry.objectives.is_empty() {
+        return;
+    }
+
+    let objectives: Vec<String> = entry
+        .objectives
+        .iter()
+        .enumerate()
+        .map(|(index, objective)| {
+            let count = objective.kind.count();
+            let progress = quest_state.progress(id, index);
+            format!("- {} ({}/{})", objective.description, progress, count)
+        })
+        .collect();
+
+    state.add_text_arg("objectives", &objectives.join("\n"));
+}
+
+fn add_rewards_text_args(state: &mut WidgetState, rewards: &QuestRewards) {
+    if rewards.is_empty() {
+        return;
+    }
+
+    state.add_text_arg("rewards", "true");
+    if rewards.xp > 0 {
+        state.add_text_arg("reward_xp", &rewards.xp.to_string());
+    }
+
+    if rewards.coins != 0 {
+        state.add_text_arg("reward_coins", &format_item_value(rewards.coins));
+    }
+
+    let mut items: Vec<String> = rewards
+        .items
+        .iter()
+        .filter_map(|(id, quantity)| {
+            let item = Module::item(id)?;
+            if *quantity > 1 {
+                Some(format!("{} x{}", item.name, quantity))
+            } else {
+                Some(item.name.to_string())
+            }
+        })
+        .collect();
+    items.sort();
+
+    if !items.is_empty() {
+        state.add_text_arg("reward_items", &items.join(", "));
+    }
+}
@@ -18,10 +18,11 @@ use std::collections::HashMap;
 use std::{any::Any, cell::RefCell, rc::Rc, time::Instant};
 
 use crate::{
-    character_window, formation_window, inventory_window, merchant_window, prop_window,
-    quest_window, world_map_window, AbilitiesBar, ApBar, AreaView, CharacterWindow, ConsoleWindow,
-    FormationWindow, GameOverWindow, InGameMenu, InitiativeTicker, InventoryWindow, MerchantWindow,
-    PortraitPane, PropWindow, QuestWindow, QuickItemBar, WorldMapWindow,
+    character_window, crafting_window, formation_window, inventory_window, merchant_window,
+    prop_window, quest_window, world_map_window, AbilitiesBar, ApBar, AreaView, CharacterWindow,
+    ConsoleWindow, CraftingWindow, FormationWindow, GameOverWindow, InGameMenu, InitiativeTicker,
+    InventoryWindow, MerchantWindow, PortraitPane, PropWindow, QuestWindow, QuickItemBar,
+    WorldMapWindow,
 };
 use sulis_core::config::Config;
 use sulis_core::io::{keyboard_event::Key, InputAction};
@@ -35,7 +36,7 @@ use sulis_state::{
     Script,
 };
 
-const WINDOW_NAMES: [&str; 7] = [
+const WINDOW_NAMES: [&str; 8] = [
     self::formation_window::NAME,
     self::inventory_window::NAME,
     self::character_window::NAME,
@@ -43,6 +44,7 @@ const WINDOW_NAMES: [&str; 7] = [
     self::world_map_window::NAME,
     self::merchant_window::NAME,
     self::prop_window::NAME,
+    self::crafting_window::NAME,
 ];
 
 const NAME: &str = "game";
@@ -185,6 +187,15 @@ impl RootView {
         });
     }
 
+    pub fn set_crafting_window(&mut self, widget: &Rc<RefCell<Widget>>, desired_state: bool) {
+        self.set_window(widget, self::crafting_window::NAME, desired_state, &|| {
+            match GameState::selected().first() {
+                None => None,
+                Some(ref entity) => Some(CraftingWindow::new(entity)),
+            }
+        });
+    }
+
     pub fn set_formation_window(&mut self, widget: &Rc<RefCell<Widget>>, desired_state: bool) {
         self.set_window(widget, self::formation_window::NAME, desired_state, &|| {
             Some(FormationWindow::new())
@@ -269,6 +280,11 @@ impl RootView {
         self.set_quest_window(widget, desired_state);
     }
 
+    pub fn toggle_crafting_window(&mut self, widget: &Rc<RefCell<Widget>>) {
+        let desired_state = !Widget::has_child_with_name(widget, self::crafting_window::NAME);
+        self.set_crafting_window(widget, desired_state);
+    }
+
     pub fn toggle_map_window(&mut self, widget: &Rc<RefCell<Widget>>) {
         let desired_state = !Widget::has_child_with_name(widget, self::world_map_window::NAME);
         self.set_map_window(widget, desired_state, false);
@@ -447,6 +463,7 @@ impl WidgetKind for RootView {
             ToggleMap => self.toggle_map_window(widget),
             ToggleJournal => self.toggle_quest_window(widget),
             ToggleFormation => self.toggle_formation_window(widget),
+            ToggleCrafting => self.toggle_crafting_window(widget),
             EndTurn => self.end_turn(),
             Rest => self.rest(),
             Exit => self.show_exit(widget),
@@ -590,6 +607,16 @@ impl WidgetKind for RootView {
                 }),
             );
 
+            let craft_button = create_button(
+                &keys,
+                ToggleCrafting,
+                "crafting_button",
+                Rc::new(|widget, _| {
+                    let (root, view) = Widget::parent_mut::<RootView>(widget);
+                    view.toggle_crafting_window(&root);
+                }),
+            );
+
             let men_button = create_button(
                 &keys,
                 Back,
@@ -608,6 +635,7 @@ impl WidgetKind for RootView {
                     cha_button,
                     map_button,
                     log_button,
+                    craft_button,
                     men_button,
                 ],
             );
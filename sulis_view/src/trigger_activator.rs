@@ -19,13 +19,15 @@ use std::rc::Rc;
 
 use sulis_core::ui::{Callback, Widget};
 use sulis_module::{
+    cutscene::CutsceneKind,
     on_trigger::{self, Kind, ModuleLoadData, QuestStateData},
     Actor, ItemState, MerchantData, Module, OnTrigger,
 };
 use sulis_state::{
     area_feedback_text::ColorKind,
+    condition,
     script::{entity_with_id, CallbackData, FuncKind, ScriptEntity},
-    AreaFeedbackText, EntityState, GameState, NextGameStep, Script,
+    AreaFeedbackText, EntityState, GameState, NextGameStep, QuestEvent, Script,
 };
 
 use crate::{
@@ -47,6 +49,16 @@ pub fn is_match(
                     return false;
                 }
             }
+            FactionReputation(ref data) => {
+                if GameState::faction_reputation(&data.faction) < data.amount {
+                    return false;
+                }
+            }
+            CompanionApproval(ref data) => {
+                if GameState::approval(&data.companion) < data.amount {
+                    return false;
+                }
+            }
             PartyMember(ref id) => {
                 if !GameState::has_party_member(id) {
                     return false;
@@ -133,6 +145,11 @@ pub fn is_match(
                     return false;
                 }
             }
+            Condition(ref condition) => {
+                if !condition::is_met(condition, pc, target) {
+                    return false;
+                }
+            }
             _ => {
                 warn!("Unsupported OnTrigger kind '{:?}' in validator", trigger);
             }
@@ -187,6 +204,12 @@ pub fn activate(
             PlayerCoins(amount) => {
                 GameState::add_party_coins(*amount);
             }
+            FactionReputation(ref data) => {
+                GameState::add_faction_reputation(&data.faction, data.amount);
+            }
+            CompanionApproval(ref data) => {
+                GameState::add_approval(&data.companion, data.amount);
+            }
             PartyMember(ref id) => match entity_with_id(id.to_string()) {
                 None => warn!(
                     "Attempted to add party member '{}' but entity does not exist",
@@ -261,6 +284,9 @@ pub fn activate(
             NotQuestState(_) => {
                 warn!("NotQuestState invalid for trigger/dialog on_activate");
             }
+            Condition(_) => {
+                warn!("Condition invalid for trigger/dialog on_activate");
+            }
         }
     }
 }
@@ -482,13 +508,15 @@ fn show_merchant(widget: &Rc<RefCell<Widget>>, merch: &MerchantData) {
         let area_state = GameState::area_state();
         let mut area_state = area_state.borrow_mut();
 
-        area_state.get_or_create_merchant(
+        let merchant = area_state.get_or_create_merchant(
             id,
             &loot,
             merch.buy_frac,
             merch.sell_frac,
+            merch.repair_frac,
             merch.refresh_time,
         );
+        merchant.faction = merch.faction.clone();
     }
 
     let (root, view) = Widget::parent_mut::<RootView>(widget);
@@ -511,8 +539,18 @@ fn show_cutscene(widget: &Rc<RefCell<Widget>>, cutscene_id: &str) {
     );
 
     let root = Widget::get_root(widget);
-    let window = Widget::with_defaults(CutsceneWindow::new(cutscene));
-    window.borrow_mut().state.set_modal(true);
+    let window = match cutscene.kind {
+        CutsceneKind::Window => {
+            let window = Widget::with_defaults(CutsceneWindow::new(cutscene));
+            window.borrow_mut().state.set_modal(true);
+            window
+        }
+        // area cutscenes block the UI one frame at a time so the area can
+        // continue to update
+        CutsceneKind::Area => {
+            Widget::with_theme(CutsceneWindow::new(cutscene), "area_cutscene_window")
+        }
+    };
     Widget::add_child_to(&root, window);
 }
 
@@ -532,5 +570,8 @@ fn start_convo(
 
     info!("Showing conversation {}", convo_id);
 
+    let id = target.borrow().actor.actor.id.to_string();
+    GameState::add_quest_event(QuestEvent::Talked(id));
+
     dialog_window::show_convo(convo, pc, target, widget);
 }
@@ -19,16 +19,16 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use sulis_core::ui::{animation_state, Callback, Widget, WidgetKind};
-use sulis_core::util::Point;
-use sulis_core::widgets::{Button, TextArea};
-use sulis_module::{campaign::WorldMapLocation, Module, Time};
-use sulis_state::GameState;
+use sulis_core::widgets::{Button, Label, TextArea};
+use sulis_module::{campaign::WorldMapLocation, quest::QuestMarker, Module, Time};
+use sulis_state::{ChangeListener, GameState};
 
 pub const NAME: &str = "world_map_window";
 
 pub struct Entry {
     child: Rc<RefCell<Widget>>,
     label: Rc<RefCell<Widget>>,
+    marker: Rc<RefCell<Widget>>,
     position: (f32, f32),
 }
 
@@ -75,13 +75,16 @@ impl WidgetKind for WorldMapWindow {
                 let y = start_y + (grid_h * entry.position.1 + offset_y) as i32;
                 entry.child.borrow_mut().state.set_position(x, y);
                 entry.label.borrow_mut().state.set_position(x, y);
+                entry.marker.borrow_mut().state.set_position(x, y);
             }
         }
 
         widget.do_children_layout();
     }
 
-    fn on_add(&mut self, _widget: &Rc<RefCell<Widget>>) -> Vec<Rc<RefCell<Widget>>> {
+    fn on_add(&mut self, widget: &Rc<RefCell<Widget>>) -> Vec<Rc<RefCell<Widget>>> {
+        GameState::add_quest_state_change_listener(ChangeListener::invalidate(NAME, widget));
+
         let bg = Widget::empty("bg");
 
         let close = Widget::with_theme(Button::empty(), "close");
@@ -107,6 +110,8 @@ impl WidgetKind for WorldMapWindow {
         let area_state = GameState::area_state();
         let cur_location_id = area_state.borrow().area.area.world_map_location.clone();
 
+        let quest_markers = GameState::quest_state().active_markers();
+
         for location in map.locations.iter() {
             let button = Widget::with_theme(Button::empty(), "location");
 
@@ -147,13 +152,27 @@ impl WidgetKind for WorldMapWindow {
                 )
             };
 
-            if add_callback && !add_travel_callback(&cur_location_id, &location, &button, &label) {
+            let destination = map_state.travel_destination();
+            if add_callback
+                && !add_travel_callback(&cur_location_id, destination, &location, &button, &label)
+            {
                 button.borrow_mut().state.set_enabled(false);
             }
 
+            let marker = Widget::with_theme(Label::empty(), "quest_marker");
+            let has_marker = quest_markers
+                .iter()
+                .any(|marker| marker_in_location(marker, location));
+            let is_visible = button.borrow().state.is_visible();
+            marker
+                .borrow_mut()
+                .state
+                .set_visible(has_marker && is_visible);
+
             let entry = Entry {
                 child: Rc::clone(&button),
                 label: Rc::clone(&label),
+                marker,
                 position: location.position,
             };
 
@@ -161,22 +180,44 @@ impl WidgetKind for WorldMapWindow {
             Widget::add_child_to(&self.content, button);
         }
 
-        // add labels after buttons so they show up on top
+        // add labels and markers after buttons so they show up on top
         for entry in self.entries.iter() {
             Widget::add_child_to(&self.content, Rc::clone(&entry.label));
         }
 
+        for entry in self.entries.iter() {
+            Widget::add_child_to(&self.content, Rc::clone(&entry.marker));
+        }
+
         vec![bg, close, labels, Rc::clone(&self.content)]
     }
 }
 
+fn marker_in_location(marker: &QuestMarker, location: &WorldMapLocation) -> bool {
+    if location.linked_area.as_ref() == Some(&marker.area) {
+        return true;
+    }
+
+    match Module::area(&marker.area) {
+        None => false,
+        Some(area) => area.world_map_location.as_ref() == Some(&location.id),
+    }
+}
+
 fn add_travel_callback(
     cur_location_id: &Option<String>,
+    travel_destination: Option<&str>,
     location: &WorldMapLocation,
     button: &Rc<RefCell<Widget>>,
     label: &Rc<RefCell<Widget>>,
 ) -> bool {
     let cur_location_id = match cur_location_id {
+        // the party may be partway through a journey that was interrupted
+        // by a travel encounter
+        None if travel_destination == Some(&location.id) => {
+            button.borrow_mut().state.add_callback(resume_callback());
+            return true;
+        }
         None => return false,
         Some(id) => id,
     };
@@ -194,27 +235,52 @@ fn add_travel_callback(
         .state
         .add_text_arg("travel_time", &travel_time.to_string());
 
-    let (x, y) = (location.linked_area_pos.x, location.linked_area_pos.y);
-    let area_id = match &location.linked_area {
-        None => return false,
-        Some(id) => id.to_string(),
-    };
+    if location.linked_area.is_none() {
+        return false;
+    }
 
-    button
-        .borrow_mut()
-        .state
-        .add_callback(travel_callback(area_id, x, y, travel_time));
+    if let Some(provisions) = &Module::campaign().world_map.provisions {
+        let required = provisions.required(hours);
+        let name = match Module::item(&provisions.item) {
+            None => provisions.item.to_string(),
+            Some(item) => item.name.to_string(),
+        };
+
+        {
+            let state = &mut label.borrow_mut().state;
+            state.add_text_arg("provisions", &required.to_string());
+            state.add_text_arg("provisions_item", &name);
+        }
+
+        let stash = GameState::party_stash();
+        if stash.borrow().quantity_of(&provisions.item) < required {
+            return false;
+        }
+    }
+
+    let cb = travel_callback(cur_location_id.to_string(), location.id.to_string());
+    button.borrow_mut().state.add_callback(cb);
     true
 }
 
-fn travel_callback(area_id: String, x: i32, y: i32, travel_time: Time) -> Callback {
+fn travel_callback(from: String, to: String) -> Callback {
     Callback::new(Rc::new(move |widget, _| {
-        GameState::transition_to(
-            Some(&area_id),
-            Some(Point::new(x, y)),
-            Point::default(),
-            travel_time,
-        );
+        let campaign = Module::campaign();
+        let location = campaign.world_map.locations.iter().find(|l| l.id == to);
+        match location {
+            None => warn!("Invalid travel location '{}'", to),
+            Some(location) => {
+                GameState::travel_to(&from, location);
+            }
+        }
+        let root = Widget::get_root(&widget);
+        root.borrow_mut().invalidate_children();
+    }))
+}
+
+fn resume_callback() -> Callback {
+    Callback::new(Rc::new(|widget, _| {
+        GameState::resume_travel();
         let root = Widget::get_root(&widget);
         root.borrow_mut().invalidate_children();
     }))